@@ -1,29 +1,82 @@
-use std::{path::Path, rc::Rc};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    rc::Rc,
+    sync::Arc,
+};
 
-use anyhow::Result;
+use anyhow::{Result, bail};
 use rustlings::{
-    game_data::{GameData, read_game_data},
+    game_data::{
+        GameData, Loading, OverlayVfs,
+        file::profile::{Profile, format_profile, parse_profile},
+        open_vfs, read_game_data_from, verify_game_data,
+    },
     sdl::{current_refresh_rate, get_canvas_vsync},
 };
 use sdl3::{
     Sdl,
+    filesystem::get_pref_path,
     render::{Canvas, TextureCreator},
+    sys::video::SDL_WINDOW_FULLSCREEN,
     video::{Window, WindowContext},
 };
 
 use crate::{
+    keymap::Keymap,
     scenes::create_scene,
     stage::{Stage, StopReason},
-    state::{GameState, SceneState},
+    state::{GameState, SceneState, Screen},
 };
 
+const PROFILE_ORGANIZATION: &str = "rustlings";
+const PROFILE_APPLICATION: &str = "rustlings";
+const PROFILE_FILE_NAME: &str = "profile.txt";
+
+pub const MIN_WINDOW_WIDTH: u32 = 640;
+pub const MIN_WINDOW_HEIGHT: u32 = 480;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct StartLevel {
+    pub index: usize,
+    // Only a level code unlocks the level, otherwise the level is played for this session
+    pub from_code: bool,
+}
+
 pub struct Config {
     pub data_dir: String,
+    // Directory or zip archive with files that replace those of the game data, usually levels
+    pub level_pack: Option<String>,
+    // The settings below override the profile if present
+    pub start_level: Option<StartLevel>,
+    pub fullscreen: Option<bool>,
+    pub window_width: u32,
+    pub window_height: u32,
+    pub render_driver: Option<String>,
+    pub vsync: bool,
 }
 
-fn init_sdl() -> Result<(Sdl, Window)> {
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            data_dir: String::new(),
+            level_pack: None,
+            start_level: None,
+            fullscreen: None,
+            window_width: MIN_WINDOW_WIDTH,
+            window_height: MIN_WINDOW_HEIGHT,
+            render_driver: None,
+            vsync: true,
+        }
+    }
+}
+
+fn init_sdl(config: &Config) -> Result<(Sdl, Window)> {
     let sdl_context = sdl3::init()?;
-    sdl3::hint::set("SDL_RENDER_VSYNC", "1");
+    sdl3::hint::set("SDL_RENDER_VSYNC", if config.vsync { "1" } else { "0" });
+    if let Some(render_driver) = &config.render_driver {
+        sdl3::hint::set("SDL_RENDER_DRIVER", render_driver);
+    }
     sdl3::hint::set("SDL_FRAMEBUFFER_ACCELERATION", "1");
     sdl3::hint::set("SDL_VIDEO_MAC_FULLSCREEN_SPACES", "1");
     sdl3::hint::set("SDL_VIDEO_MAC_FULLSCREEN_MENU_VISIBILITY", "1");
@@ -31,13 +84,17 @@ fn init_sdl() -> Result<(Sdl, Window)> {
     let sdl_video = sdl_context.video()?;
 
     let mut window = sdl_video
-        .window("Rustlings", 640, 480)
+        .window(
+            "Rustlings",
+            config.window_width.max(MIN_WINDOW_WIDTH),
+            config.window_height.max(MIN_WINDOW_HEIGHT),
+        )
         .position_centered()
         .set_flags(sdl3::video::WindowFlags::HIGH_PIXEL_DENSITY)
         .resizable()
         .build()?;
 
-    let _ = window.set_minimum_size(640, 480);
+    let _ = window.set_minimum_size(MIN_WINDOW_WIDTH, MIN_WINDOW_HEIGHT);
     let _ = window.raise();
 
     sdl_context.mouse().show_cursor(false);
@@ -64,23 +121,125 @@ fn init_canvas(window: Window) -> Result<(Canvas<Window>, TextureCreator<WindowC
     Ok((canvas, texture_creator))
 }
 
+fn profile_path() -> Option<PathBuf> {
+    match get_pref_path(PROFILE_ORGANIZATION, PROFILE_APPLICATION) {
+        Ok(path) => Some(path.join(PROFILE_FILE_NAME)),
+        Err(err) => {
+            println!("no profile directory, progress will not be saved: {}", err);
+            None
+        }
+    }
+}
+
+// A missing profile starts a new one, an unreadable one is reported and replaced on the next save
+fn load_profile(path: &Path) -> Profile {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Default::default(),
+        Err(err) => {
+            println!("unable to read profile {}: {}", path.display(), err);
+            return Default::default();
+        }
+    };
+
+    parse_profile(&text).unwrap_or_else(|err| {
+        println!("ignoring invalid profile {}: {}", path.display(), err);
+        Default::default()
+    })
+}
+
+fn save_profile(path: Option<&Path>, game_state: &GameState, window: &Window) {
+    let Some(path) = path else {
+        return;
+    };
+
+    // Levels played without being unlocked are not remembered
+    let current_level = if game_state.profile.is_unlocked(game_state.current_level) {
+        game_state.current_level
+    } else {
+        game_state.profile.current_level
+    };
+
+    let profile = Profile {
+        current_level,
+        fullscreen: window.window_flags().0 & SDL_WINDOW_FULLSCREEN.0 != 0,
+        ..game_state.profile.clone()
+    };
+
+    if let Err(err) = fs::write(path, format_profile(&profile)) {
+        println!("unable to save profile {}: {}", path.display(), err);
+    }
+}
+
 pub fn run(config: &Config) -> Result<()> {
-    let game_data: Rc<GameData> = read_game_data(Path::new(&config.data_dir))?.into();
+    let data_path = Path::new(&config.data_dir);
+
+    let mut vfs = open_vfs(data_path)?;
+    if let Some(level_pack) = &config.level_pack {
+        vfs = Arc::new(OverlayVfs::new(open_vfs(Path::new(level_pack))?, vfs));
+    }
+
+    let report = verify_game_data(vfs.as_ref());
+    if !report.is_ok() {
+        bail!("invalid game data in {}: {}", config.data_dir, report);
+    }
+
+    println!("found {}", report);
+
+    let game_data: Rc<GameData> = read_game_data_from(vfs, Loading::Eager)?.into();
+
+    let (sdl_context, window) = init_sdl(config)?;
+
+    let profile_path = profile_path();
+    let mut profile = profile_path
+        .as_deref()
+        .map(load_profile)
+        .unwrap_or_default();
+
+    if config.fullscreen.unwrap_or(profile.fullscreen) {
+        let _ = window.clone().set_fullscreen(true);
+    }
 
-    let mut game_state = GameState {
-        current_level: 0,
-        ..Default::default()
+    let keymap = Rc::new(Keymap::new(&profile.keys).unwrap_or_else(|err| {
+        println!("ignoring invalid key bindings: {}", err);
+        Default::default()
+    }));
+
+    // A start level given as code is treated like an entered code
+    let mut game_state = match config.start_level {
+        Some(start_level) => {
+            if start_level.from_code {
+                profile.unlock(start_level.index);
+            }
+
+            GameState {
+                screen: Screen::Preview,
+                current_level: start_level.index,
+                profile,
+                keymap,
+            }
+        }
+        None => GameState {
+            current_level: if profile.is_unlocked(profile.current_level) {
+                profile.current_level
+            } else {
+                0
+            },
+            profile,
+            keymap,
+            ..Default::default()
+        },
     };
 
     let mut scene_state: SceneState = Default::default();
 
-    let (sdl_context, window) = init_sdl()?;
-
     // Creating the canvas consumes the window. However, we need to preserve it in order
     // to spawn a new canvas if we lose context (see below), so we clone it. Looks expensive,
     // but window is just an Arc around a handle, so it is actually cheap.
     let (mut canvas, mut texture_creator) = init_canvas(window.clone())?;
 
+    let mut fade_in = true;
+
     loop {
         let run_result: StopReason;
 
@@ -98,10 +257,14 @@ pub fn run(config: &Config) -> Result<()> {
                 &texture_creator,
             )?;
 
-            run_result = stage.run(&mut *scene)?;
+            run_result = stage.run(&mut *scene, fade_in)?;
             (game_state, scene_state) = scene.finish();
         }
 
+        if !matches!(run_result, StopReason::RenderReset) {
+            save_profile(profile_path.as_deref(), &game_state, &window);
+        }
+
         match run_result {
             StopReason::Quit => {
                 println!("shutting down");
@@ -109,6 +272,7 @@ pub fn run(config: &Config) -> Result<()> {
             }
             StopReason::RenderReset => {
                 println!("render reset");
+                fade_in = false;
 
                 // release the renderer before creating a new one; otherwise, SDL will crash
                 drop(texture_creator);
@@ -117,7 +281,11 @@ pub fn run(config: &Config) -> Result<()> {
                 // See above.
                 (canvas, texture_creator) = init_canvas(window.clone())?;
             }
-            StopReason::NextScene => (),
+            StopReason::NextScene if matches!(game_state.screen, Screen::Exit) => {
+                println!("shutting down");
+                break;
+            }
+            StopReason::NextScene => fade_in = true,
         }
     }
 
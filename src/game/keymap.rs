@@ -0,0 +1,488 @@
+use std::collections::BTreeMap;
+
+use anyhow::{Result, anyhow};
+use rustlings::game_data::Skill;
+use sdl3::keyboard::{Keycode, Mod};
+use strum::{EnumString, IntoStaticStr, VariantArray};
+
+// Named actions that can be bound to keys in a level. The names are used in the [keys] table of
+// the profile.
+#[derive(Clone, Copy, PartialEq, Debug, VariantArray, IntoStaticStr, EnumString)]
+#[strum(serialize_all = "snake_case")]
+pub enum Action {
+    NextLevel,
+    PreviousLevel,
+    EndLevel,
+    Pause,
+    Step,
+    FastForward,
+    SpeedUp,
+    SpeedDown,
+    NormalSpeed,
+    AssignSkill,
+    RightButton,
+    ReleaseRateUp,
+    ReleaseRateDown,
+    Nuke,
+    ScrollLeft,
+    ScrollRight,
+    SelectClimber,
+    SelectFloater,
+    SelectBomber,
+    SelectBlocker,
+    SelectBuilder,
+    SelectBasher,
+    SelectMiner,
+    SelectDigger,
+}
+
+impl Action {
+    pub fn skill(self) -> Option<Skill> {
+        match self {
+            Action::SelectClimber => Some(Skill::Climber),
+            Action::SelectFloater => Some(Skill::Floater),
+            Action::SelectBomber => Some(Skill::Bomber),
+            Action::SelectBlocker => Some(Skill::Blocker),
+            Action::SelectBuilder => Some(Skill::Builder),
+            Action::SelectBasher => Some(Skill::Basher),
+            Action::SelectMiner => Some(Skill::Miner),
+            Action::SelectDigger => Some(Skill::Digger),
+            _ => None,
+        }
+    }
+
+    fn default_keys(self) -> &'static str {
+        match self {
+            Action::NextLevel => "PageUp",
+            Action::PreviousLevel => "PageDown",
+            Action::EndLevel => "Escape",
+            Action::Pause => "P",
+            Action::Step => "Shift+Space",
+            Action::FastForward => "Ctrl+Space",
+            Action::SpeedUp => "]",
+            Action::SpeedDown => "[",
+            Action::NormalSpeed => "Backspace",
+            Action::AssignSkill => "Return",
+            Action::RightButton => "Left Shift, Right Shift",
+            Action::ReleaseRateUp => "+, =, Keypad +",
+            Action::ReleaseRateDown => "-, Keypad -",
+            Action::Nuke => "",
+            Action::ScrollLeft => "Left",
+            Action::ScrollRight => "Right",
+            Action::SelectClimber => "1",
+            Action::SelectFloater => "2",
+            Action::SelectBomber => "3",
+            Action::SelectBlocker => "4",
+            Action::SelectBuilder => "5",
+            Action::SelectBasher => "6",
+            Action::SelectMiner => "7",
+            Action::SelectDigger => "8",
+        }
+    }
+
+    // The function keys of the DOS version, bound in addition to the default keys
+    fn dos_keys(self) -> &'static str {
+        match self {
+            Action::ReleaseRateDown => "F1",
+            Action::ReleaseRateUp => "F2",
+            Action::SelectClimber => "F3",
+            Action::SelectFloater => "F4",
+            Action::SelectBomber => "F5",
+            Action::SelectBlocker => "F6",
+            Action::SelectBuilder => "F7",
+            Action::SelectBasher => "F8",
+            Action::SelectMiner => "F9",
+            Action::SelectDigger => "F10",
+            Action::Pause => "F11",
+            Action::Nuke => "F12",
+            _ => "",
+        }
+    }
+}
+
+// Sets of default bindings, chosen with "preset" in the [keys] table of the profile
+#[derive(Clone, Copy, PartialEq, Debug, Default, EnumString)]
+#[strum(serialize_all = "snake_case")]
+pub enum Preset {
+    #[default]
+    Standard,
+    Dos,
+}
+
+impl Preset {
+    fn keys(self, action: Action) -> Vec<&'static str> {
+        let mut keys = vec![action.default_keys()];
+
+        if self == Preset::Dos {
+            keys.push(action.dos_keys());
+        }
+
+        keys
+    }
+}
+
+bitflags::bitflags! {
+    #[derive(Clone, Copy, PartialEq, Debug)]
+    pub struct Modifiers: u8 {
+        const SHIFT = 0x01;
+        const CTRL = 0x02;
+        const ALT = 0x04;
+        const GUI = 0x08;
+    }
+}
+
+const MODIFIER_NAMES: [(&str, Modifiers); 4] = [
+    ("shift", Modifiers::SHIFT),
+    ("ctrl", Modifiers::CTRL),
+    ("alt", Modifiers::ALT),
+    ("gui", Modifiers::GUI),
+];
+
+impl Modifiers {
+    // Left and right modifier keys are not told apart, and lock keys are ignored
+    fn from_keymod(keymod: Mod) -> Self {
+        let mut modifiers = Modifiers::empty();
+
+        modifiers.set(
+            Modifiers::SHIFT,
+            keymod.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD),
+        );
+        modifiers.set(
+            Modifiers::CTRL,
+            keymod.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD),
+        );
+        modifiers.set(
+            Modifiers::ALT,
+            keymod.intersects(Mod::LALTMOD | Mod::RALTMOD),
+        );
+        modifiers.set(
+            Modifiers::GUI,
+            keymod.intersects(Mod::LGUIMOD | Mod::RGUIMOD),
+        );
+
+        modifiers
+    }
+
+    // The modifier a modifier key sets while it is held
+    fn of_key(keycode: Keycode) -> Self {
+        match keycode {
+            Keycode::LShift | Keycode::RShift => Modifiers::SHIFT,
+            Keycode::LCtrl | Keycode::RCtrl => Modifiers::CTRL,
+            Keycode::LAlt | Keycode::RAlt => Modifiers::ALT,
+            Keycode::LGui | Keycode::RGui => Modifiers::GUI,
+            _ => Modifiers::empty(),
+        }
+    }
+}
+
+// Keys without a character, named as SDL names them. Looking names up through SDL instead would
+// make parsing depend on its keyboard state.
+const NAMED_KEYS: [(&str, Keycode); 51] = [
+    ("Return", Keycode::Return),
+    ("Escape", Keycode::Escape),
+    ("Backspace", Keycode::Backspace),
+    ("Tab", Keycode::Tab),
+    ("Space", Keycode::Space),
+    ("Comma", Keycode::Comma),
+    ("Delete", Keycode::Delete),
+    ("Insert", Keycode::Insert),
+    ("Home", Keycode::Home),
+    ("End", Keycode::End),
+    ("PageUp", Keycode::PageUp),
+    ("PageDown", Keycode::PageDown),
+    ("Left", Keycode::Left),
+    ("Right", Keycode::Right),
+    ("Up", Keycode::Up),
+    ("Down", Keycode::Down),
+    ("Pause", Keycode::Pause),
+    ("F1", Keycode::F1),
+    ("F2", Keycode::F2),
+    ("F3", Keycode::F3),
+    ("F4", Keycode::F4),
+    ("F5", Keycode::F5),
+    ("F6", Keycode::F6),
+    ("F7", Keycode::F7),
+    ("F8", Keycode::F8),
+    ("F9", Keycode::F9),
+    ("F10", Keycode::F10),
+    ("F11", Keycode::F11),
+    ("F12", Keycode::F12),
+    ("Keypad 0", Keycode::Kp0),
+    ("Keypad 1", Keycode::Kp1),
+    ("Keypad 2", Keycode::Kp2),
+    ("Keypad 3", Keycode::Kp3),
+    ("Keypad 4", Keycode::Kp4),
+    ("Keypad 5", Keycode::Kp5),
+    ("Keypad 6", Keycode::Kp6),
+    ("Keypad 7", Keycode::Kp7),
+    ("Keypad 8", Keycode::Kp8),
+    ("Keypad 9", Keycode::Kp9),
+    ("Keypad +", Keycode::KpPlus),
+    ("Keypad -", Keycode::KpMinus),
+    ("Keypad *", Keycode::KpMultiply),
+    ("Keypad /", Keycode::KpDivide),
+    ("Keypad .", Keycode::KpPeriod),
+    ("Keypad Enter", Keycode::KpEnter),
+    ("Left Shift", Keycode::LShift),
+    ("Right Shift", Keycode::RShift),
+    ("Left Ctrl", Keycode::LCtrl),
+    ("Right Ctrl", Keycode::RCtrl),
+    ("Left Alt", Keycode::LAlt),
+    ("Right Alt", Keycode::RAlt),
+];
+
+// Keys with a character are named by it. SDL uses the unshifted character of the current layout
+// as the keycode, so "A" is the key labelled A on QWERTY and AZERTY keyboards alike.
+fn key_from_name(name: &str) -> Option<Keycode> {
+    let mut chars = name.chars();
+
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Keycode::from_u32(c.to_ascii_lowercase() as u32)
+            .filter(|keycode| *keycode != Keycode::Unknown);
+    }
+
+    NAMED_KEYS
+        .iter()
+        .find(|(key_name, _)| key_name.eq_ignore_ascii_case(name))
+        .map(|(_, keycode)| *keycode)
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct KeyBinding {
+    pub keycode: Keycode,
+    pub modifiers: Modifiers,
+}
+
+impl KeyBinding {
+    // Key names with optional modifier prefixes, e.g. "Ctrl+Space", "Keypad +" or "Shift++"
+    pub fn parse(spec: &str) -> Result<Self> {
+        let mut rest = spec.trim();
+        let mut modifiers = Modifiers::empty();
+
+        'prefixes: loop {
+            for (name, modifier) in MODIFIER_NAMES {
+                let prefix = rest.get(..name.len() + 1);
+
+                if prefix.is_some_and(|prefix| prefix.eq_ignore_ascii_case(&format!("{}+", name)))
+                    && rest.len() > name.len() + 1
+                {
+                    modifiers |= modifier;
+                    rest = &rest[name.len() + 1..];
+                    continue 'prefixes;
+                }
+            }
+
+            break;
+        }
+
+        let keycode =
+            key_from_name(rest).ok_or_else(|| anyhow!("unknown key '{}'", spec.trim()))?;
+
+        Ok(Self { keycode, modifiers })
+    }
+}
+
+const PRESET_KEY: &str = "preset";
+
+// Several keys can trigger the same action, and a key can trigger several actions
+pub struct Keymap {
+    bindings: Vec<(KeyBinding, Action)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::new(&BTreeMap::new()).expect("invalid default key bindings")
+    }
+}
+
+impl Keymap {
+    // The overrides map action names to a comma separated list of keys, replacing the keys of the
+    // preset. An empty list unbinds the action.
+    pub fn new(overrides: &BTreeMap<String, String>) -> Result<Self> {
+        let mut preset = Preset::default();
+
+        for (name, value) in overrides {
+            if name == PRESET_KEY {
+                preset = value
+                    .parse()
+                    .map_err(|_| anyhow!("unknown key preset '{}'", value))?;
+            } else {
+                name.parse::<Action>()
+                    .map_err(|_| anyhow!("unknown action '{}'", name))?;
+            }
+        }
+
+        let mut bindings = Vec::new();
+
+        for action in Action::VARIANTS {
+            let name: &'static str = action.into();
+            let keys = match overrides.get(name) {
+                Some(keys) => vec![keys.as_str()],
+                None => preset.keys(*action),
+            };
+
+            for spec in keys
+                .iter()
+                .flat_map(|keys| keys.split(','))
+                .filter(|spec| !spec.trim().is_empty())
+            {
+                let binding = KeyBinding::parse(spec)
+                    .map_err(|err| anyhow!("invalid binding for '{}': {}", name, err))?;
+                bindings.push((binding, *action));
+            }
+        }
+
+        Ok(Self { bindings })
+    }
+
+    // Bindings that name the held modifiers exactly take precedence. Otherwise, bindings without
+    // modifiers match regardless of the modifiers held, as some keys need shift on some layouts.
+    pub fn pressed(&self, keycode: Keycode, keymod: Mod) -> Vec<Action> {
+        let modifiers = Modifiers::from_keymod(keymod) - Modifiers::of_key(keycode);
+
+        let matching = |modifiers: Modifiers| -> Vec<Action> {
+            self.bindings
+                .iter()
+                .filter(|(binding, _)| binding.keycode == keycode && binding.modifiers == modifiers)
+                .map(|(_, action)| *action)
+                .collect()
+        };
+
+        let actions = matching(modifiers);
+        if actions.is_empty() {
+            matching(Modifiers::empty())
+        } else {
+            actions
+        }
+    }
+
+    // Releasing a key ends the actions it is bound to, whatever the modifiers
+    pub fn released(&self, keycode: Keycode) -> Vec<Action> {
+        self.bindings
+            .iter()
+            .filter(|(binding, _)| binding.keycode == keycode)
+            .map(|(_, action)| *action)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use sdl3::keyboard::{Keycode, Mod};
+
+    use crate::keymap::{Action, KeyBinding, Keymap, Modifiers};
+
+    #[test]
+    fn parse_bindings() {
+        assert_eq!(
+            KeyBinding::parse("Ctrl+Space").unwrap(),
+            KeyBinding {
+                keycode: Keycode::Space,
+                modifiers: Modifiers::CTRL
+            }
+        );
+        assert_eq!(
+            KeyBinding::parse(" shift+alt++ ").unwrap(),
+            KeyBinding {
+                keycode: Keycode::Plus,
+                modifiers: Modifiers::SHIFT | Modifiers::ALT
+            }
+        );
+        assert_eq!(
+            KeyBinding::parse("Keypad +").unwrap().keycode,
+            Keycode::KpPlus
+        );
+        assert_eq!(KeyBinding::parse("Comma").unwrap().keycode, Keycode::Comma);
+        assert_eq!(KeyBinding::parse("shift+q").unwrap().keycode, Keycode::Q);
+        assert_eq!(
+            KeyBinding::parse("page down").unwrap_err().to_string(),
+            "unknown key 'page down'"
+        );
+        assert!(KeyBinding::parse("Ctrl+").is_err());
+        assert!(KeyBinding::parse("NoSuchKey").is_err());
+    }
+
+    #[test]
+    fn default_bindings() {
+        let keymap = Keymap::default();
+
+        assert_eq!(
+            keymap.pressed(Keycode::PageUp, Mod::NOMOD),
+            [Action::NextLevel]
+        );
+        assert_eq!(
+            keymap.pressed(Keycode::Space, Mod::LCTRLMOD),
+            [Action::FastForward]
+        );
+        assert_eq!(
+            keymap.pressed(Keycode::Space, Mod::RSHIFTMOD | Mod::NUMMOD),
+            [Action::Step]
+        );
+        assert_eq!(keymap.pressed(Keycode::Space, Mod::NOMOD), []);
+        assert_eq!(
+            keymap.pressed(Keycode::Plus, Mod::LSHIFTMOD),
+            [Action::ReleaseRateUp]
+        );
+        assert_eq!(
+            keymap.pressed(Keycode::LShift, Mod::LSHIFTMOD),
+            [Action::RightButton]
+        );
+        assert_eq!(
+            keymap.released(Keycode::Space),
+            [Action::Step, Action::FastForward]
+        );
+    }
+
+    #[test]
+    fn overrides() {
+        let overrides = BTreeMap::from([
+            ("release_rate_up".to_string(), "F2, Ctrl+Up".to_string()),
+            ("pause".to_string(), "".to_string()),
+        ]);
+        let keymap = Keymap::new(&overrides).unwrap();
+
+        assert_eq!(
+            keymap.pressed(Keycode::F2, Mod::NOMOD),
+            [Action::ReleaseRateUp]
+        );
+        assert_eq!(
+            keymap.pressed(Keycode::Up, Mod::RCTRLMOD),
+            [Action::ReleaseRateUp]
+        );
+        assert_eq!(keymap.pressed(Keycode::Plus, Mod::NOMOD), []);
+        assert_eq!(keymap.pressed(Keycode::P, Mod::NOMOD), []);
+
+        let unknown = BTreeMap::from([("jump".to_string(), "J".to_string())]);
+        assert!(Keymap::new(&unknown).is_err());
+    }
+
+    #[test]
+    fn dos_preset() {
+        let overrides = BTreeMap::from([
+            ("preset".to_string(), "dos".to_string()),
+            ("pause".to_string(), "Pause".to_string()),
+        ]);
+        let keymap = Keymap::new(&overrides).unwrap();
+
+        assert_eq!(
+            keymap.pressed(Keycode::F1, Mod::NOMOD),
+            [Action::ReleaseRateDown]
+        );
+        assert_eq!(
+            keymap.pressed(Keycode::F10, Mod::NOMOD),
+            [Action::SelectDigger]
+        );
+        assert_eq!(keymap.pressed(Keycode::F12, Mod::NOMOD), [Action::Nuke]);
+        assert_eq!(
+            keymap.pressed(Keycode::_8, Mod::NOMOD),
+            [Action::SelectDigger]
+        );
+        assert_eq!(keymap.pressed(Keycode::F11, Mod::NOMOD), []);
+        assert_eq!(Keymap::default().pressed(Keycode::F12, Mod::NOMOD), []);
+
+        let unknown = BTreeMap::from([("preset".to_string(), "amiga".to_string())]);
+        assert!(Keymap::new(&unknown).is_err());
+    }
+}
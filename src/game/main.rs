@@ -1,32 +1,145 @@
 use anyhow::Result;
-use clap::{Arg, Command};
+use clap::{Arg, ArgAction, Command};
+use rustlings::game_data::{DifficultyRating, LEVELS_PER_DIFFICULTY, NUM_LEVELS, level_for_code};
 
-use crate::game::Config;
+use crate::game::{Config, StartLevel};
 
-mod code;
 mod game;
 mod geometry;
+mod keymap;
 mod scene;
 mod scenes;
 mod stage;
 mod state;
 
+// A level index (0 - 119), a rating and level number like "tricky:5", or a level code
+fn parse_start_level(value: &str) -> Result<StartLevel, String> {
+    if let Ok(index) = value.parse::<usize>() {
+        return if index < NUM_LEVELS {
+            Ok(StartLevel {
+                index,
+                from_code: false,
+            })
+        } else {
+            Err(format!("level index must be below {}", NUM_LEVELS))
+        };
+    }
+
+    if let Some((rating, number)) = value.split_once(':') {
+        let rating = (0..NUM_LEVELS / LEVELS_PER_DIFFICULTY)
+            .find(|i| {
+                DifficultyRating::from(*i)
+                    .to_string()
+                    .eq_ignore_ascii_case(rating.trim())
+            })
+            .ok_or_else(|| format!("unknown rating '{}'", rating))?;
+
+        return match number.trim().parse::<usize>() {
+            Ok(number @ 1..=LEVELS_PER_DIFFICULTY) => Ok(StartLevel {
+                index: rating * LEVELS_PER_DIFFICULTY + number - 1,
+                from_code: false,
+            }),
+            _ => Err(format!(
+                "level number must be between 1 and {}",
+                LEVELS_PER_DIFFICULTY
+            )),
+        };
+    }
+
+    level_for_code(value)
+        .map(|(index, _, _)| index)
+        .filter(|index| *index < NUM_LEVELS)
+        .map(|index| StartLevel {
+            index,
+            from_code: true,
+        })
+        .ok_or_else(|| format!("invalid level code '{}'", value))
+}
+
+fn parse_window_size(value: &str) -> Result<(u32, u32), String> {
+    value
+        .split_once('x')
+        .and_then(|(width, height)| Some((width.parse().ok()?, height.parse().ok()?)))
+        .ok_or_else(|| format!("expected WIDTHxHEIGHT, got '{}'", value))
+}
+
 fn main() -> Result<()> {
     let command = Command::new("rustlings")
         .about("rustlings is a replacement engine for Lemmings / DOS")
         .arg(
             Arg::new("DATA DIRECTORY")
                 .required(true)
-                .help("path to data files")
+                .help("path to data files or zip archive")
                 .index(1),
+        )
+        .arg(
+            Arg::new("level")
+                .help("start level: index (0 - 119), rating:number (e.g. tricky:5) or code")
+                .long("level")
+                .value_name("LEVEL")
+                .value_parser(parse_start_level),
+        )
+        .arg(
+            Arg::new("level-pack")
+                .help("directory or zip archive with level files replacing the original ones")
+                .long("level-pack")
+                .value_name("PATH"),
+        )
+        .arg(
+            Arg::new("fullscreen")
+                .help("start in fullscreen mode")
+                .long("fullscreen")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("windowed"),
+        )
+        .arg(
+            Arg::new("windowed")
+                .help("start in windowed mode")
+                .long("windowed")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("window-size")
+                .help("initial window size, at least 640x480")
+                .long("window-size")
+                .value_name("WIDTHxHEIGHT")
+                .value_parser(parse_window_size),
+        )
+        .arg(
+            Arg::new("renderer")
+                .help("SDL render driver, e.g. software, opengl, vulkan, metal or gpu")
+                .long("renderer")
+                .value_name("DRIVER"),
+        )
+        .arg(
+            Arg::new("no-vsync")
+                .help("disable vsync")
+                .long("no-vsync")
+                .action(ArgAction::SetTrue),
         );
 
     let matches = command.get_matches();
 
-    let config = Config {
+    let mut config = Config {
         data_dir: matches.get_one::<String>("DATA DIRECTORY").unwrap().clone(),
+        level_pack: matches.get_one::<String>("level-pack").cloned(),
+        start_level: matches.get_one::<StartLevel>("level").copied(),
+        render_driver: matches.get_one::<String>("renderer").cloned(),
+        vsync: !matches.get_flag("no-vsync"),
+        ..Default::default()
     };
 
+    if matches.get_flag("fullscreen") {
+        config.fullscreen = Some(true);
+    } else if matches.get_flag("windowed") {
+        config.fullscreen = Some(false);
+    }
+
+    if let Some((width, height)) = matches.get_one::<(u32, u32)>("window-size") {
+        config.window_width = *width;
+        config.window_height = *height;
+    }
+
     let run_result = game::run(&config);
 
     if let Err(err) = &run_result {
@@ -35,3 +148,40 @@ fn main() -> Result<()> {
 
     run_result
 }
+
+#[cfg(test)]
+mod test {
+    use rustlings::game_data::code_for_level;
+
+    use crate::{parse_start_level, parse_window_size};
+
+    #[test]
+    fn start_level() {
+        let index = |value: &str| parse_start_level(value).map(|level| level.index);
+
+        assert_eq!(index("0"), Ok(0));
+        assert_eq!(index("119"), Ok(119));
+        assert!(index("120").is_err());
+
+        assert_eq!(index("Fun:1"), Ok(0));
+        assert_eq!(index("tricky:5"), Ok(34));
+        assert_eq!(index("MAYHEM:30"), Ok(119));
+        assert!(index("mayhem:31").is_err());
+        assert!(index("easy:1").is_err());
+
+        let code = code_for_level(42, None, None).unwrap();
+        assert_eq!(index(&code), Ok(42));
+        assert!(index("AAAAAAAAAA").is_err());
+
+        assert!(parse_start_level(&code).unwrap().from_code);
+        assert!(!parse_start_level("42").unwrap().from_code);
+        assert!(!parse_start_level("tricky:5").unwrap().from_code);
+    }
+
+    #[test]
+    fn window_size() {
+        assert_eq!(parse_window_size("1280x960"), Ok((1280, 960)));
+        assert!(parse_window_size("1280").is_err());
+        assert!(parse_window_size("ax960").is_err());
+    }
+}
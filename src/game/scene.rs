@@ -7,6 +7,7 @@ use sdl3::{
 
 use crate::{
     geometry::Rect,
+    keymap::Action,
     state::{GameState, SceneState},
 };
 
@@ -40,6 +41,9 @@ pub enum SceneEvent {
     MouseMove(MouseCoordinates),
     MouseDown(MouseButton, MouseCoordinates),
     MouseUp(MouseButton, MouseCoordinates),
+    // Key events translated by the keymap
+    ActionDown(Action),
+    ActionUp(Action),
 }
 
 #[derive(Clone, Copy, PartialEq)]
@@ -57,7 +61,8 @@ pub trait Scene<'texture_creator> {
     fn width(&self) -> u32;
     fn height(&self) -> u32;
     fn aspect(&self) -> f32;
-    fn opacity(&self) -> u8;
+    // Duration of the fade in and fade out transitions, shorter while fast forwarding
+    fn fade_msec(&self) -> u64;
 
     fn set_is_fullscreen(&mut self, is_fullscreen: bool);
     fn set_mouse_enabled(&mut self, mouse_enabled: bool);
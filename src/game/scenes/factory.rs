@@ -1,12 +1,12 @@
 use std::rc::Rc;
 
-use anyhow::Result;
+use anyhow::{Result, bail};
 use rustlings::game_data::GameData;
 use sdl3::render::TextureCreator;
 
 use crate::{
     scene::Scene,
-    scenes::SceneLevel,
+    scenes::{SceneCode, SceneLevel, SceneMenu, ScenePostview, ScenePreview},
     state::{GameState, SceneState, Screen},
 };
 
@@ -17,11 +17,33 @@ pub fn create_scene<'texture_creator, T>(
     texture_creator: &'texture_creator TextureCreator<T>,
 ) -> Result<Box<dyn Scene<'texture_creator> + 'texture_creator>> {
     match game_state.screen {
+        Screen::Menu => Ok(Box::from(SceneMenu::new(
+            game_data,
+            game_state,
+            texture_creator,
+        )?)),
+        Screen::Code => Ok(Box::from(SceneCode::new(
+            game_data,
+            game_state,
+            texture_creator,
+        )?)),
+        Screen::Preview => Ok(Box::from(ScenePreview::new(
+            game_data,
+            game_state,
+            texture_creator,
+        )?)),
         Screen::Level => Ok(Box::from(SceneLevel::new(
             game_data,
             game_state,
             scene_state,
             texture_creator,
         )?)),
+        Screen::Postview => Ok(Box::from(ScenePostview::new(
+            game_data,
+            game_state,
+            scene_state,
+            texture_creator,
+        )?)),
+        Screen::Exit => bail!("no scene for the exit screen"),
     }
 }
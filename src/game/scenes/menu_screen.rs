@@ -0,0 +1,88 @@
+use anyhow::Result;
+use rustlings::{
+    game_data::{GameData, resolve_menu_font_index},
+    sdl::SDLSprite,
+};
+use sdl3::{
+    pixels::Color,
+    render::{Canvas, RenderTarget, TextureCreator},
+};
+
+// The menu, preview and postview screens use the 640x350 EGA mode of the DOS version
+pub const MENU_WIDTH: u32 = 640;
+pub const MENU_HEIGHT: u32 = 350;
+pub const MENU_ASPECT: f32 = 480. / 350.;
+pub const MENU_FADE_MSEC: u64 = 500;
+
+pub const MENU_GLYPH_WIDTH: u32 = 16;
+pub const MENU_GLYPH_HEIGHT: u32 = 16;
+
+// Tiled background and font shared by the screens outside of a level
+pub struct MenuScreen<'texture_creator> {
+    background: SDLSprite<'texture_creator>,
+    font: SDLSprite<'texture_creator>,
+}
+
+impl<'texture_creator> MenuScreen<'texture_creator> {
+    pub fn new<T>(
+        game_data: &GameData,
+        texture_creator: &'texture_creator TextureCreator<T>,
+    ) -> Result<Self> {
+        let menu = &game_data.main_menu;
+
+        Ok(MenuScreen {
+            background: SDLSprite::from_bitmap(&menu.background, &menu.palette, texture_creator)?,
+            font: SDLSprite::from_sprite(&menu.font, &menu.palette, texture_creator)?,
+        })
+    }
+
+    pub fn draw_background<T: RenderTarget>(&self, canvas: &mut Canvas<T>) -> Result<()> {
+        canvas.set_draw_color(Color::RGBA(0, 0, 0, 0xff));
+        canvas.clear();
+
+        for y in (0..MENU_HEIGHT).step_by(self.background.height as usize) {
+            for x in (0..MENU_WIDTH).step_by(self.background.width as usize) {
+                self.background
+                    .blit(canvas, x as i32, y as i32, 0, 1, false, false)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    // Characters outside of the font are drawn as blanks
+    pub fn draw_text<T: RenderTarget>(
+        &self,
+        canvas: &mut Canvas<T>,
+        x: i32,
+        y: i32,
+        text: &str,
+    ) -> Result<()> {
+        for (i, c) in text.chars().enumerate() {
+            if let Some(index) = resolve_menu_font_index(c) {
+                self.font.blit(
+                    canvas,
+                    x + (i as u32 * MENU_GLYPH_WIDTH) as i32,
+                    y,
+                    index,
+                    1,
+                    false,
+                    false,
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn draw_text_centered<T: RenderTarget>(
+        &self,
+        canvas: &mut Canvas<T>,
+        y: i32,
+        text: &str,
+    ) -> Result<()> {
+        let width = text.chars().count() as u32 * MENU_GLYPH_WIDTH;
+
+        self.draw_text(canvas, (MENU_WIDTH as i32 - width as i32) / 2, y, text)
+    }
+}
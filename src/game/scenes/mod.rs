@@ -1,5 +1,14 @@
 mod factory;
+mod menu_screen;
+mod scene_code;
 mod scene_level;
+mod scene_menu;
+mod scene_postview;
+mod scene_preview;
 
 pub use factory::*;
+pub use scene_code::*;
 pub use scene_level::*;
+pub use scene_menu::*;
+pub use scene_postview::*;
+pub use scene_preview::*;
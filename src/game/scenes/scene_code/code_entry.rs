@@ -0,0 +1,117 @@
+use rustlings::game_data::{NUM_LEVELS, level_for_code};
+
+pub const CODE_LENGTH: usize = 10;
+
+// The code typed so far. A rejected code stays on screen until the next letter starts over.
+#[derive(Default)]
+pub struct CodeEntry {
+    code: String,
+    rejected: bool,
+}
+
+impl CodeEntry {
+    pub fn type_char(&mut self, c: char) {
+        if !c.is_ascii_alphabetic() {
+            return;
+        }
+
+        if self.rejected {
+            self.code.clear();
+            self.rejected = false;
+        }
+
+        if self.code.len() < CODE_LENGTH {
+            self.code.push(c.to_ascii_uppercase());
+        }
+    }
+
+    pub fn erase(&mut self) {
+        self.rejected = false;
+        self.code.pop();
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.code.len() == CODE_LENGTH
+    }
+
+    pub fn is_rejected(&self) -> bool {
+        self.rejected
+    }
+
+    // Blanks are shown as dots, like the DOS version
+    pub fn display(&self) -> String {
+        format!("{:.<width$}", self.code, width = CODE_LENGTH)
+    }
+
+    // Returns the level index of a valid code. Codes decoding to an index past the last level
+    // are rejected as well.
+    pub fn submit(&mut self) -> Option<usize> {
+        let level_index = level_for_code(&self.code)
+            .map(|(level_index, _, _)| level_index)
+            .filter(|level_index| *level_index < NUM_LEVELS);
+
+        self.rejected = level_index.is_none();
+        level_index
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rustlings::game_data::code_for_level;
+
+    use crate::scenes::scene_code::code_entry::CodeEntry;
+
+    fn type_code(entry: &mut CodeEntry, code: &str) {
+        for c in code.chars() {
+            entry.type_char(c);
+        }
+    }
+
+    #[test]
+    fn valid_code() {
+        let mut entry = CodeEntry::default();
+        type_code(
+            &mut entry,
+            &code_for_level(42, None, None).unwrap().to_lowercase(),
+        );
+
+        assert!(entry.is_complete());
+        assert_eq!(entry.submit(), Some(42));
+        assert!(!entry.is_rejected());
+    }
+
+    #[test]
+    fn invalid_code() {
+        let mut entry = CodeEntry::default();
+        type_code(&mut entry, "AAAAAAAAAA");
+
+        assert_eq!(entry.submit(), None);
+        assert!(entry.is_rejected());
+        assert_eq!(entry.display(), "AAAAAAAAAA");
+
+        entry.type_char('b');
+        assert!(!entry.is_rejected());
+        assert_eq!(entry.display(), "B.........");
+    }
+
+    #[test]
+    fn index_out_of_range() {
+        let mut entry = CodeEntry::default();
+        type_code(&mut entry, &code_for_level(200, None, None).unwrap());
+
+        assert_eq!(entry.submit(), None);
+    }
+
+    #[test]
+    fn editing() {
+        let mut entry = CodeEntry::default();
+        type_code(&mut entry, "AB1CDEFGHIJKL");
+
+        assert_eq!(entry.display(), "ABCDEFGHIJ");
+
+        entry.erase();
+        entry.erase();
+        assert!(!entry.is_complete());
+        assert_eq!(entry.display(), "ABCDEFGH..");
+    }
+}
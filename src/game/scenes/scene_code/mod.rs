@@ -0,0 +1,5 @@
+mod code_entry;
+mod renderer;
+mod scene_code;
+
+pub use scene_code::*;
@@ -0,0 +1,103 @@
+use anyhow::{Result, anyhow};
+use rustlings::{game_data::GameData, sdl::with_texture_canvas};
+use sdl3::{
+    pixels::PixelFormat,
+    render::{Canvas, Texture, TextureCreator},
+    video::Window,
+};
+
+use crate::{
+    geometry::Rect,
+    scene::Compositor,
+    scenes::menu_screen::{MENU_HEIGHT, MENU_WIDTH, MenuScreen},
+};
+
+const TEXTURE_ID_SCREEN: usize = 0;
+
+const PROMPT_Y: i32 = 120;
+const CODE_Y: i32 = 160;
+const MESSAGE_Y: i32 = 220;
+const CONTINUE_Y: i32 = 310;
+
+pub struct Renderer<'texture_creator> {
+    texture_screen: Texture<'texture_creator>,
+    screen: MenuScreen<'texture_creator>,
+
+    redraw: bool,
+}
+
+impl<'texture_creator> Renderer<'texture_creator> {
+    pub fn new<T>(
+        game_data: &GameData,
+        texture_creator: &'texture_creator TextureCreator<T>,
+    ) -> Result<Self> {
+        let texture_screen = texture_creator.create_texture_target(
+            PixelFormat::RGBA8888,
+            MENU_WIDTH,
+            MENU_HEIGHT,
+        )?;
+
+        Ok(Renderer {
+            texture_screen,
+            screen: MenuScreen::new(game_data, texture_creator)?,
+            redraw: true,
+        })
+    }
+
+    pub fn will_redraw(&self) -> bool {
+        self.redraw
+    }
+
+    pub fn mark_for_redraw(&mut self) {
+        self.redraw = true;
+    }
+
+    pub fn register_layers(&self, compositor: &mut dyn Compositor) {
+        compositor.add_layer(
+            TEXTURE_ID_SCREEN,
+            MENU_WIDTH,
+            MENU_HEIGHT,
+            Rect::new(0, 0, MENU_WIDTH, MENU_HEIGHT),
+        );
+    }
+
+    pub fn texture(&mut self, id: usize) -> Result<&mut Texture<'texture_creator>> {
+        match id {
+            TEXTURE_ID_SCREEN => Ok(&mut self.texture_screen),
+            _ => Err(anyhow!("invalid texture id {}", id)),
+        }
+    }
+
+    pub fn draw(
+        &mut self,
+        canvas: &mut Canvas<Window>,
+        code: &str,
+        rejected: bool,
+    ) -> Result<bool> {
+        if !self.redraw {
+            return Ok(false);
+        }
+        self.redraw = false;
+
+        let Self {
+            texture_screen,
+            screen,
+            ..
+        } = self;
+
+        with_texture_canvas(canvas, texture_screen, |canvas| -> Result<()> {
+            screen.draw_background(canvas)?;
+
+            screen.draw_text_centered(canvas, PROMPT_Y, "Enter Code")?;
+            screen.draw_text_centered(canvas, CODE_Y, code)?;
+
+            if rejected {
+                screen.draw_text_centered(canvas, MESSAGE_Y, "INCORRECT CODE")?;
+            }
+
+            screen.draw_text_centered(canvas, CONTINUE_Y, "Press ESC for menu")
+        })?;
+
+        Ok(true)
+    }
+}
@@ -0,0 +1,154 @@
+use std::rc::Rc;
+
+use anyhow::Result;
+use rustlings::game_data::GameData;
+use sdl3::{
+    keyboard::Keycode,
+    render::{Canvas, Texture, TextureCreator},
+    video::Window,
+};
+
+use crate::{
+    scene::{Compositor, CursorType, Scene, SceneEvent},
+    scenes::{
+        menu_screen::{MENU_ASPECT, MENU_FADE_MSEC, MENU_HEIGHT, MENU_WIDTH},
+        scene_code::{code_entry::CodeEntry, renderer::Renderer},
+    },
+    state::{GameState, SceneState, Screen},
+};
+
+#[derive(PartialEq)]
+enum Status {
+    Running,
+    DonePreview,
+    DoneMenu,
+}
+
+// The "New Level" screen of the DOS version. A complete code is checked right away and a valid one
+// continues with the briefing of the decoded level.
+pub struct SceneCode<'texture_creator> {
+    game_state: GameState,
+    status: Status,
+    entry: CodeEntry,
+    renderer: Renderer<'texture_creator>,
+}
+
+impl<'texture_creator> SceneCode<'texture_creator> {
+    pub fn new<T>(
+        game_data: Rc<GameData>,
+        game_state: GameState,
+        texture_creator: &'texture_creator TextureCreator<T>,
+    ) -> Result<Self> {
+        Ok(SceneCode {
+            game_state,
+            status: Status::Running,
+            entry: CodeEntry::default(),
+            renderer: Renderer::new(&game_data, texture_creator)?,
+        })
+    }
+
+    fn submit(&mut self) {
+        if let Some(level_index) = self.entry.submit() {
+            self.game_state.current_level = level_index;
+            self.game_state.profile.unlock(level_index);
+            self.status = Status::DonePreview;
+        }
+
+        self.renderer.mark_for_redraw();
+    }
+}
+
+impl<'texture_creator> Scene<'texture_creator> for SceneCode<'texture_creator> {
+    fn finish(mut self: Box<Self>) -> (GameState, SceneState) {
+        match self.status {
+            Status::Running => (),
+            Status::DonePreview => self.game_state.screen = Screen::Preview,
+            Status::DoneMenu => self.game_state.screen = Screen::Menu,
+        }
+
+        (self.game_state, SceneState::None)
+    }
+
+    fn width(&self) -> u32 {
+        MENU_WIDTH
+    }
+
+    fn height(&self) -> u32 {
+        MENU_HEIGHT
+    }
+
+    fn aspect(&self) -> f32 {
+        MENU_ASPECT
+    }
+
+    fn fade_msec(&self) -> u64 {
+        MENU_FADE_MSEC
+    }
+
+    fn set_is_fullscreen(&mut self, _is_fullscreen: bool) {}
+
+    fn set_mouse_enabled(&mut self, _mouse_enabled: bool) {}
+
+    fn cursor_type(&mut self) -> CursorType {
+        CursorType::None
+    }
+
+    fn dispatch_event(&mut self, event: SceneEvent) {
+        if self.status != Status::Running {
+            return;
+        }
+
+        let SceneEvent::KeyDown { keycode, .. } = event else {
+            return;
+        };
+
+        match keycode {
+            Keycode::Escape => self.status = Status::DoneMenu,
+            Keycode::Return => self.submit(),
+            Keycode::Backspace => {
+                self.entry.erase();
+                self.renderer.mark_for_redraw();
+            }
+            _ => {
+                // SDL keycodes of letter keys are their lowercase ASCII values
+                if let Some(c) = char::from_u32(keycode.to_ll().0) {
+                    self.entry.type_char(c);
+                    self.renderer.mark_for_redraw();
+
+                    if self.entry.is_complete() {
+                        self.submit();
+                    }
+                }
+            }
+        }
+    }
+
+    fn tick(&mut self, _canvas: &mut Canvas<Window>, _clock_msec: u64) -> Result<()> {
+        Ok(())
+    }
+
+    fn next_tick_at_msec(&self) -> u64 {
+        u64::MAX
+    }
+
+    fn is_complete(&self) -> bool {
+        self.status != Status::Running
+    }
+
+    fn texture(&mut self, id: usize) -> Result<&mut Texture<'texture_creator>> {
+        self.renderer.texture(id)
+    }
+
+    fn register_layers(&self, compositor: &mut dyn Compositor) {
+        self.renderer.register_layers(compositor);
+    }
+
+    fn draw(&mut self, canvas: &mut Canvas<Window>) -> Result<bool> {
+        self.renderer
+            .draw(canvas, &self.entry.display(), self.entry.is_rejected())
+    }
+
+    fn will_redraw(&self) -> bool {
+        self.renderer.will_redraw()
+    }
+}
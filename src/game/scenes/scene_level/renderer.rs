@@ -6,7 +6,7 @@ use rustlings::{
         GameData, LEVEL_HEIGHT, LEVEL_WIDTH, Level, MINIMAP_AREA_Y, MINIMAP_FRAME_HEIGHT,
         MINIMAP_FRAME_WIDTH, MINIMAP_VIEW_HEIGHT, MINIMAP_VIEW_WIDTH, MINIMAP_VIEW_X,
         MINIMAP_VIEW_Y, OBJECTS_PER_TILESET, SCREEN_HEIGHT, SCREEN_WIDTH, SKILL_PANEL_HEIGHT,
-        file::level,
+        file::level, minimap_position,
     },
     sdl::{
         SdlAtlas, SdlAtlasBuilder, apply_blend_mode, texture_from_bitmap,
@@ -35,9 +35,9 @@ use crate::{
         cache::Cache,
         selection_controller::SelectionMode,
         skill_panel_renderer::SkillPanelRenderer,
-        terrain_diff::{TerrainDiff, TerrainDiffKind, VisibilityTarget},
+        terrain_diff::{TerrainDiff, VisibilityTarget},
     },
-    state::{Direction, LemmingAnimation, SceneStateLevel},
+    state::{Direction, LemmingAnimation, SceneStateLevel, Speed},
 };
 
 bitflags::bitflags! {
@@ -109,7 +109,7 @@ impl<'texture_creator> Renderer<'texture_creator> {
         texture_creator: &'texture_creator TextureCreator<T>,
     ) -> Result<Self> {
         let palette = game_data.resolve_palette(level)?;
-        let palette_skill_panel = game_data.resolve_skill_panel_palette(0);
+        let palette_skill_panel = game_data.resolve_skill_panel_palette(0)?;
 
         let texture_terrain = texture_from_bitmap(
             &scene_state.terrain,
@@ -162,9 +162,7 @@ impl<'texture_creator> Renderer<'texture_creator> {
             });
 
         let object_atlas_index: Vec<Option<usize>> = game_data
-            .tilesets
-            .get(level.graphics_set as usize)
-            .ok_or(anyhow!("invalid tileset {}", level.graphics_set))?
+            .tileset(level.graphics_set as usize)?
             .object_sprites
             .iter()
             .map(|sprite| sprite.as_ref().map(|s| atlas_builder.add_sprite(s)))
@@ -306,17 +304,13 @@ impl<'texture_creator> Renderer<'texture_creator> {
                     continue;
                 }
 
-                match entry.kind {
-                    TerrainDiffKind::Dig => {
-                        canvas.set_draw_color(Color::RGBA(0, 0, 0, 0));
-                        canvas.set_blend_mode(BlendMode::None);
+                canvas.set_draw_color(Color::RGBA(0, 0, 0, 0));
+                canvas.set_blend_mode(BlendMode::None);
 
-                        canvas.draw_line(
-                            Point::new(entry.x, entry.y),
-                            Point::new(entry.x + 8, entry.y),
-                        )?;
-                    }
-                }
+                canvas.draw_line(
+                    Point::new(entry.x, entry.y),
+                    Point::new(entry.end_x(), entry.y),
+                )?;
             }
 
             Ok(())
@@ -342,17 +336,13 @@ impl<'texture_creator> Renderer<'texture_creator> {
                     continue;
                 }
 
-                match entry.kind {
-                    TerrainDiffKind::Dig => {
-                        canvas.set_draw_color(Color::RGBA(0, 0, 0, 0));
-                        canvas.set_blend_mode(BlendMode::None);
+                canvas.set_draw_color(Color::RGBA(0, 0, 0, 0));
+                canvas.set_blend_mode(BlendMode::None);
 
-                        canvas.draw_line(
-                            Point::new(entry.x, entry.y),
-                            Point::new(entry.x + 8, entry.y),
-                        )?;
-                    }
-                }
+                canvas.draw_line(
+                    Point::new(entry.x, entry.y),
+                    Point::new(entry.end_x(), entry.y),
+                )?;
             }
 
             Ok(())
@@ -364,17 +354,13 @@ impl<'texture_creator> Renderer<'texture_creator> {
                     continue;
                 }
 
-                match entry.kind {
-                    TerrainDiffKind::Dig => {
-                        canvas.set_draw_color(Color::RGBA(0, 0, 0, 0));
-                        canvas.set_blend_mode(BlendMode::None);
+                canvas.set_draw_color(Color::RGBA(0, 0, 0, 0));
+                canvas.set_blend_mode(BlendMode::None);
 
-                        canvas.draw_line(
-                            Point::new(entry.x, entry.y),
-                            Point::new(entry.x + 8, entry.y),
-                        )?;
-                    }
-                }
+                canvas.draw_line(
+                    Point::new(entry.x, entry.y),
+                    Point::new(entry.end_x(), entry.y),
+                )?;
             }
 
             Ok(())
@@ -412,7 +398,7 @@ impl<'texture_creator> Renderer<'texture_creator> {
 
     fn minimap_frame_position(&self, state: &SceneStateLevel) -> (u32, u32) {
         (
-            MINIMAP_VIEW_X + (state.level_x * MINIMAP_VIEW_WIDTH) / LEVEL_WIDTH - 1,
+            MINIMAP_VIEW_X + minimap_position(state.level_x as i32, 0).0 - 1,
             SKILL_PANEL_Y + MINIMAP_AREA_Y,
         )
     }
@@ -430,6 +416,8 @@ impl<'texture_creator> Renderer<'texture_creator> {
         state: &SceneStateLevel,
         cache: &mut Cache,
         selection_mode: SelectionMode,
+        pressed_tile: Option<u32>,
+        speed: Speed,
         canvas: &mut Canvas<Window>,
     ) -> Result<bool> {
         if self.redraw.is_empty() {
@@ -446,9 +434,14 @@ impl<'texture_creator> Renderer<'texture_creator> {
         }
 
         if redraw.contains(Redraw::SKILL_PANEL)
-            && self
-                .skill_panel_renderer
-                .draw(state, cache, selection_mode, canvas)?
+            && self.skill_panel_renderer.draw(
+                state,
+                cache,
+                selection_mode,
+                pressed_tile,
+                speed,
+                canvas,
+            )?
         {
             redraw.insert(Redraw::SCREEN);
         }
@@ -758,11 +751,7 @@ fn draw_minimap_lemmings<T: RenderTarget>(
     let mut num_points: usize = 0;
 
     for lemming in &state.lemmings {
-        let minimap_x: u32 =
-            (lemming.x.clamp(0, LEVEL_WIDTH as i32 - 1) as u32 * MINIMAP_VIEW_WIDTH) / LEVEL_WIDTH;
-        let minimap_y: u32 = ((lemming.y - 5).clamp(0, LEVEL_HEIGHT as i32 - 1) as u32
-            * MINIMAP_VIEW_HEIGHT)
-            / LEVEL_HEIGHT;
+        let (minimap_x, minimap_y) = minimap_position(lemming.x, lemming.y - 5);
 
         let pixel_index = minimap_x + minimap_y * MINIMAP_VIEW_WIDTH;
         let lookup_index = (pixel_index >> 5) as usize;
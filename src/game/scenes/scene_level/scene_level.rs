@@ -1,18 +1,22 @@
-use std::{cmp, collections::VecDeque, rc::Rc};
+use std::{
+    cmp,
+    collections::VecDeque,
+    rc::Rc,
+    time::{Duration, Instant},
+};
 
 use anyhow::Result;
 use rustlings::game_data::{
     Bitmap, GameData, LEVEL_HEIGHT, LEVEL_WIDTH, Level, LevelParameters, NUM_LEVELS, SCREEN_HEIGHT,
-    SCREEN_WIDTH, Skill, decode_level_index, file::ground::InteractionType,
+    SCREEN_WIDTH, Skill, code_for_level, decode_level_index, file::ground::InteractionType,
 };
 use sdl3::{
-    keyboard::{Keycode, Mod},
     render::{Canvas, Texture, TextureCreator},
     video::Window,
 };
 
 use crate::{
-    code::code_for_level,
+    keymap::Action,
     scene::{CursorType, MouseButton, Scene, SceneEvent},
     scenes::scene_level::{
         cache::Cache,
@@ -26,17 +30,22 @@ use crate::{
 };
 use crate::{
     scenes::scene_level::scroll_controller::ScrollController,
-    state::{GameState, SceneState, SceneStateLevel},
+    state::{GameState, LevelOutcome, LevelState, SceneState, SceneStateLevel, Screen, Speed},
 };
 
 const ENGINE_TICK_MSEC: u64 = 1000 / 17; // 17 FPS
-const FADE_IN_MSEC: u64 = 1000;
+const ENGINE_TICK_USEC: u64 = ENGINE_TICK_MSEC * 1000;
+const FADE_MSEC: u64 = 1000;
+
+// Real time spent simulating per tick at uncapped speed, leaving the rest of a frame for drawing
+const UNCAPPED_BUDGET: Duration = Duration::from_millis(10);
 
 #[derive(PartialEq)]
 enum Status {
     Running,
     DoneNextLevel,
     DonePreviousLevel,
+    DoneLevelEnded,
 }
 
 pub struct SceneLevel<'texture_creator> {
@@ -53,7 +62,6 @@ pub struct SceneLevel<'texture_creator> {
     level_parameters: LevelParameters,
     clock_offset_msec: u64,
 
-    last_draw_at_clock_msec: u64,
     fast: bool,
 
     shift_down: bool,
@@ -114,7 +122,6 @@ impl<'texture_creator> SceneLevel<'texture_creator> {
             skill_panel_controller,
             simulation,
             level_parameters: level.parameters,
-            last_draw_at_clock_msec: 0,
             fast: false,
             shift_down: false,
             right_mouse_down: false,
@@ -123,19 +130,55 @@ impl<'texture_creator> SceneLevel<'texture_creator> {
         })
     }
 
-    fn engine_tick_msec(&self) -> u64 {
+    // Holding fast forward runs at least at 4x
+    fn speed(&self) -> Speed {
         if self.fast {
-            ENGINE_TICK_MSEC >> 2
+            cmp::max(self.state.speed, Speed::Quadruple)
         } else {
-            ENGINE_TICK_MSEC
+            self.state.speed
         }
     }
 
-    fn fade_in_msec(&self) -> u64 {
-        if self.fast {
-            FADE_IN_MSEC >> 2
-        } else {
-            FADE_IN_MSEC
+    fn remaining_time_seconds(&self) -> u32 {
+        (self.level_parameters.time_limit * 60)
+            .saturating_sub((self.state.game_clock_usec / 1_000_000) as u32)
+    }
+
+    fn is_level_over(&self) -> bool {
+        let all_lemmings_gone =
+            self.state.level_state == LevelState::Late && self.state.lemmings.is_empty();
+
+        all_lemmings_gone || self.remaining_time_seconds() == 0
+    }
+
+    fn set_speed(&mut self, speed: Speed) {
+        self.state.speed = speed;
+        self.renderer.mark_for_redraw(Redraw::SKILL_PANEL);
+    }
+
+    fn engine_tick(&mut self, canvas: &mut Canvas<Window>) -> Result<()> {
+        self.renderer
+            .apply_diff(canvas, self.simulation.get_diff(), VisibilityTarget::Late)?;
+        self.simulation.clear_diff();
+
+        self.simulation_tick();
+
+        self.renderer
+            .apply_diff(canvas, self.simulation.get_diff(), VisibilityTarget::Early)
+    }
+
+    fn next_level(&self) -> usize {
+        (self.game_state.current_level + 1) % NUM_LEVELS
+    }
+
+    fn previous_level(&self) -> usize {
+        (self.game_state.current_level + NUM_LEVELS - 1) % NUM_LEVELS
+    }
+
+    // Skipping levels is limited to the levels the profile has unlocked
+    fn skip_to(&mut self, level_index: usize, status: Status) {
+        if self.status == Status::Running && self.game_state.profile.is_unlocked(level_index) {
+            self.status = status;
         }
     }
 
@@ -197,6 +240,67 @@ impl<'texture_creator> SceneLevel<'texture_creator> {
         self.simulation
             .assign_skill(&mut self.state, lemming_index, skill)
     }
+
+    fn dispatch_level_event(&mut self, event: SceneEvent) {
+        if self
+            .selection_controller
+            .dispatch_event(event, &mut self.state, &mut self.cache)
+        {
+            self.renderer.mark_for_redraw(Redraw::SKILL_PANEL);
+        }
+
+        match event {
+            SceneEvent::ActionDown(Action::PreviousLevel) => {
+                self.skip_to(self.previous_level(), Status::DonePreviousLevel)
+            }
+            SceneEvent::ActionDown(Action::NextLevel) => {
+                self.skip_to(self.next_level(), Status::DoneNextLevel)
+            }
+            SceneEvent::ActionDown(Action::EndLevel) if self.status == Status::Running => {
+                self.status = Status::DoneLevelEnded
+            }
+
+            SceneEvent::ActionDown(Action::Step) if self.state.paused => {
+                self.pause_tick_scheduled = true
+            }
+            SceneEvent::ActionDown(Action::FastForward) => {
+                self.fast = true;
+                self.renderer.mark_for_redraw(Redraw::SKILL_PANEL);
+            }
+            SceneEvent::ActionUp(Action::FastForward) => {
+                self.fast = false;
+                self.renderer.mark_for_redraw(Redraw::SKILL_PANEL);
+            }
+            SceneEvent::ActionDown(Action::SpeedUp) => self.set_speed(self.state.speed.faster()),
+            SceneEvent::ActionDown(Action::SpeedDown) => self.set_speed(self.state.speed.slower()),
+            SceneEvent::ActionDown(Action::NormalSpeed) => self.set_speed(Speed::Normal),
+
+            SceneEvent::ActionDown(Action::RightButton) => self.shift_down = true,
+            SceneEvent::ActionUp(Action::RightButton) => self.shift_down = false,
+
+            SceneEvent::MouseDown(MouseButton::Right, _) => self.right_mouse_down = true,
+            SceneEvent::MouseUp(MouseButton::Right, _) => self.right_mouse_down = false,
+
+            SceneEvent::MouseDown(MouseButton::Left, _) => self.assign_skill(),
+            SceneEvent::ActionDown(Action::AssignSkill) => self.assign_skill(),
+
+            _ => (),
+        };
+
+        if self
+            .scroll_controller
+            .dispatch_event(event, &mut self.state)
+        {
+            self.renderer.mark_for_redraw(Redraw::SCREEN);
+        }
+
+        if self
+            .skill_panel_controller
+            .dispatch_event(event, &mut self.state)
+        {
+            self.renderer.mark_for_redraw(Redraw::SKILL_PANEL);
+        }
+    }
 }
 
 impl<'texture_creator> Scene<'texture_creator> for SceneLevel<'texture_creator> {
@@ -204,14 +308,26 @@ impl<'texture_creator> Scene<'texture_creator> for SceneLevel<'texture_creator>
         match self.status {
             Status::Running => (self.game_state, SceneState::Level(self.state)),
             Status::DoneNextLevel => {
-                self.game_state.current_level = (self.game_state.current_level + 1) % NUM_LEVELS;
+                self.game_state.current_level = self.next_level();
                 (self.game_state, SceneState::None)
             }
             Status::DonePreviousLevel => {
-                self.game_state.current_level =
-                    (self.game_state.current_level + NUM_LEVELS - 1) % NUM_LEVELS;
+                self.game_state.current_level = self.previous_level();
                 (self.game_state, SceneState::None)
             }
+            Status::DoneLevelEnded => {
+                let outcome = LevelOutcome {
+                    released: self.level_parameters.released,
+                    rescued: self.state.lemmings_in,
+                    required: self.level_parameters.required,
+                    remaining_time_seconds: self.state.remaining_time_seconds,
+                    skills_used: self.level_parameters.skills.iter().sum::<u32>()
+                        - self.state.remaining_skills.iter().sum::<u32>(),
+                };
+
+                self.game_state.screen = Screen::Postview;
+                (self.game_state, SceneState::Postview(outcome))
+            }
         }
     }
 
@@ -227,8 +343,8 @@ impl<'texture_creator> Scene<'texture_creator> for SceneLevel<'texture_creator>
         1.2
     }
 
-    fn opacity(&self) -> u8 {
-        ((self.state.clock_msec * 255) / self.fade_in_msec()).min(255) as u8
+    fn fade_msec(&self) -> u64 {
+        self.speed().real_msec(FADE_MSEC)
     }
 
     fn set_is_fullscreen(&mut self, is_fullscreen: bool) {
@@ -252,104 +368,41 @@ impl<'texture_creator> Scene<'texture_creator> for SceneLevel<'texture_creator>
     }
 
     fn dispatch_event(&mut self, event: SceneEvent) {
-        if self
-            .selection_controller
-            .dispatch_event(event, &mut self.state, &mut self.cache)
-        {
-            self.renderer.mark_for_redraw(Redraw::SKILL_PANEL);
-        }
+        let keymap = Rc::clone(&self.game_state.keymap);
 
         match event {
             SceneEvent::KeyDown {
-                keycode: Keycode::PageDown,
-                keymod: Mod::NOMOD,
-                ..
-            } => {
-                if self.status == Status::Running {
-                    self.status = Status::DonePreviousLevel;
-                }
-            }
-            SceneEvent::KeyDown {
-                keycode: Keycode::PageUp,
-                keymod: Mod::NOMOD,
-                ..
+                keycode, keymod, ..
             } => {
-                if self.status == Status::Running {
-                    self.status = Status::DoneNextLevel;
+                for action in keymap.pressed(keycode, keymod) {
+                    self.dispatch_level_event(SceneEvent::ActionDown(action));
                 }
             }
-
-            SceneEvent::KeyDown {
-                keycode: Keycode::Space,
-                keymod: Mod::LSHIFTMOD | Mod::RSHIFTMOD,
-                ..
-            } => {
-                if self.state.paused {
-                    self.pause_tick_scheduled = true;
+            SceneEvent::KeyUp { keycode, .. } => {
+                for action in keymap.released(keycode) {
+                    self.dispatch_level_event(SceneEvent::ActionUp(action));
                 }
             }
-            SceneEvent::KeyDown {
-                keycode: Keycode::Space,
-                keymod: Mod::LCTRLMOD,
-                ..
-            } => self.fast = true,
-            SceneEvent::KeyUp {
-                keycode: Keycode::Space,
-                ..
-            } => self.fast = false,
-
-            SceneEvent::KeyDown {
-                keycode: Keycode::LShift | Keycode::RShift,
-                keymod: Mod::LSHIFTMOD | Mod::RSHIFTMOD | Mod::NOMOD,
-                ..
-            } => self.shift_down = true,
-            SceneEvent::KeyUp {
-                keycode: Keycode::LShift | Keycode::RShift,
-                ..
-            } => self.shift_down = false,
-
-            SceneEvent::MouseDown(MouseButton::Right, _) => self.right_mouse_down = true,
-            SceneEvent::MouseUp(MouseButton::Right, _) => self.right_mouse_down = false,
-
-            SceneEvent::MouseDown(MouseButton::Left, _) => self.assign_skill(),
-            SceneEvent::KeyDown {
-                keycode: Keycode::Return,
-                keymod: Mod::NOMOD,
-                ..
-            } => self.assign_skill(),
-
-            _ => (),
-        };
-
-        if self
-            .scroll_controller
-            .dispatch_event(event, &mut self.state)
-        {
-            self.renderer.mark_for_redraw(Redraw::SCREEN);
-        }
-
-        if self
-            .skill_panel_controller
-            .dispatch_event(event, &mut self.state)
-        {
-            self.renderer.mark_for_redraw(Redraw::SKILL_PANEL);
+            _ => self.dispatch_level_event(event),
         }
     }
 
     fn tick(&mut self, canvas: &mut Canvas<Window>, mut clock_msec: u64) -> Result<()> {
+        // The level freezes while fading out, so the outcome stays as it was when the level ended
+        if self.status != Status::Running {
+            return Ok(());
+        }
+
         clock_msec += self.clock_offset_msec;
 
         let clock_msec_old = self.state.clock_msec;
         self.state.clock_msec = clock_msec;
 
-        if self.state.paused {
-            self.state.simulation_clock_offset += clock_msec_old as i64 - clock_msec as i64;
-        }
-
         if clock_msec <= clock_msec_old {
             return Ok(());
         }
 
+        // Scrolling and the skill panel run in real time, whatever the game speed
         if self
             .scroll_controller
             .tick(clock_msec, clock_msec_old, &mut self.state)
@@ -357,52 +410,72 @@ impl<'texture_creator> Scene<'texture_creator> for SceneLevel<'texture_creator>
             self.renderer.mark_for_redraw(Redraw::SCREEN);
         }
 
-        let engine_ticks_old = clock_msec_old / self.engine_tick_msec();
-        let engine_ticks = clock_msec / self.engine_tick_msec();
-
-        for _ in engine_ticks_old..engine_ticks {
-            if !self.state.paused || self.pause_tick_scheduled {
-                self.renderer.apply_diff(
-                    canvas,
-                    self.simulation.get_diff(),
-                    VisibilityTarget::Late,
-                )?;
-                self.simulation.clear_diff();
+        for _ in clock_msec_old / ENGINE_TICK_MSEC..clock_msec / ENGINE_TICK_MSEC {
+            if self.skill_panel_controller.tick(&mut self.state) {
+                self.renderer.mark_for_redraw(Redraw::SKILL_PANEL);
+            }
+        }
 
-                self.simulation_tick();
+        if self.state.paused {
+            if self.pause_tick_scheduled {
+                self.engine_tick(canvas)?;
                 self.pause_tick_scheduled = false;
-
-                self.renderer.apply_diff(
-                    canvas,
-                    self.simulation.get_diff(),
-                    VisibilityTarget::Early,
-                )?;
             }
+        } else if let Some(game_usec_per_msec) = self.speed().game_usec_per_msec() {
+            let game_clock_usec_old = self.state.game_clock_usec;
+            self.state.game_clock_usec += (clock_msec - clock_msec_old) * game_usec_per_msec;
+
+            for _ in game_clock_usec_old / ENGINE_TICK_USEC
+                ..self.state.game_clock_usec / ENGINE_TICK_USEC
+            {
+                self.engine_tick(canvas)?;
+            }
+        } else {
+            let started_at = Instant::now();
 
-            if self.skill_panel_controller.tick(&mut self.state) {
-                self.renderer.mark_for_redraw(Redraw::SKILL_PANEL);
+            while started_at.elapsed() < UNCAPPED_BUDGET && !self.is_level_over() {
+                self.state.game_clock_usec =
+                    (self.state.game_clock_usec / ENGINE_TICK_USEC + 1) * ENGINE_TICK_USEC;
+                self.engine_tick(canvas)?;
             }
         }
 
-        let remaining_time_seconds = (self.level_parameters.time_limit * 60).saturating_sub(
-            ((clock_msec as i64 + self.state.simulation_clock_offset).max(0) / 1000) as u32,
-        );
+        let remaining_time_seconds = self.remaining_time_seconds();
 
         if remaining_time_seconds != self.state.remaining_time_seconds {
             self.state.remaining_time_seconds = remaining_time_seconds;
             self.renderer.mark_for_redraw(Redraw::SKILL_PANEL);
         }
 
+        if self.status == Status::Running && self.is_level_over() {
+            self.status = Status::DoneLevelEnded;
+        }
+
         Ok(())
     }
 
     fn next_tick_at_msec(&self) -> u64 {
-        let next_tick_engine =
-            ((self.state.clock_msec / self.engine_tick_msec()) + 1) * self.engine_tick_msec();
+        let clock_msec = self.state.clock_msec;
+        let next_tick_panel = (clock_msec / ENGINE_TICK_MSEC + 1) * ENGINE_TICK_MSEC;
+
+        let next_tick_engine = match self.speed().game_usec_per_msec() {
+            _ if self.pause_tick_scheduled => clock_msec,
+            _ if self.state.paused => u64::MAX,
+            Some(game_usec_per_msec) => {
+                let game_clock_usec = self.state.game_clock_usec;
+                let next_engine_tick_usec =
+                    (game_clock_usec / ENGINE_TICK_USEC + 1) * ENGINE_TICK_USEC;
+
+                clock_msec + (next_engine_tick_usec - game_clock_usec).div_ceil(game_usec_per_msec)
+            }
+            None => clock_msec,
+        };
+
+        let next_tick = cmp::min(next_tick_panel, next_tick_engine);
 
         match self.scroll_controller.next_tick_at_msec(&self.state) {
-            None => next_tick_engine,
-            Some(next_tick_scroll) => cmp::min(next_tick_engine, next_tick_scroll),
+            None => next_tick,
+            Some(next_tick_scroll) => cmp::min(next_tick, next_tick_scroll),
         }
         .saturating_sub(self.clock_offset_msec)
     }
@@ -412,17 +485,17 @@ impl<'texture_creator> Scene<'texture_creator> for SceneLevel<'texture_creator>
     }
 
     fn draw(&mut self, canvas: &mut Canvas<Window>) -> Result<bool> {
-        let last_draw_at_clock_msec = self.last_draw_at_clock_msec;
-        self.last_draw_at_clock_msec = self.state.clock_msec;
-
         let selection_mode = self.selection_mode();
-        self.renderer
-            .draw(&self.state, &mut self.cache, selection_mode, canvas)
-            .map(|updated| {
-                updated
-                    | (self.state.clock_msec != last_draw_at_clock_msec
-                        && self.state.clock_msec <= self.fade_in_msec())
-            })
+        let pressed_tile = self.skill_panel_controller.pressed_tile();
+        let speed = self.speed();
+        self.renderer.draw(
+            &self.state,
+            &mut self.cache,
+            selection_mode,
+            pressed_tile,
+            speed,
+            canvas,
+        )
     }
 
     fn will_redraw(&self) -> bool {
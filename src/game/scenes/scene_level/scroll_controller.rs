@@ -5,9 +5,9 @@ use rustlings::game_data::{
     MINIMAP_FRAME_WIDTH, MINIMAP_VIEW_WIDTH, MINIMAP_VIEW_X, SCREEN_HEIGHT, SCREEN_WIDTH,
     SKILL_PANEL_HEIGHT,
 };
-use sdl3::keyboard::Scancode;
 
 use crate::{
+    keymap::Action,
     scene::{MouseButton, MouseCoordinates, SceneEvent},
     state::SceneStateLevel,
 };
@@ -80,20 +80,20 @@ impl ScrollController {
 
     pub fn dispatch_event(&mut self, event: SceneEvent, state: &mut SceneStateLevel) -> bool {
         match event {
-            SceneEvent::KeyDown { scancode, .. } => {
-                match scancode {
-                    Scancode::Left => self.arrow_left_down = true,
-                    Scancode::Right => self.arrow_right_down = true,
-                    Scancode::LShift | Scancode::RShift => self.shift_down = true,
+            SceneEvent::ActionDown(action) => {
+                match action {
+                    Action::ScrollLeft => self.arrow_left_down = true,
+                    Action::ScrollRight => self.arrow_right_down = true,
+                    Action::RightButton => self.shift_down = true,
                     _ => (),
                 };
                 false
             }
-            SceneEvent::KeyUp { scancode, .. } => {
-                match scancode {
-                    Scancode::Left => self.arrow_left_down = false,
-                    Scancode::Right => self.arrow_right_down = false,
-                    Scancode::LShift | Scancode::RShift => self.shift_down = false,
+            SceneEvent::ActionUp(action) => {
+                match action {
+                    Action::ScrollLeft => self.arrow_left_down = false,
+                    Action::ScrollRight => self.arrow_right_down = false,
+                    Action::RightButton => self.shift_down = false,
                     _ => (),
                 };
                 false
@@ -134,6 +134,7 @@ impl ScrollController {
                 self.mouse_down_right = false;
                 false
             }
+            SceneEvent::KeyDown { .. } | SceneEvent::KeyUp { .. } => false,
         }
     }
 
@@ -34,6 +34,7 @@ pub struct Simulation {
     entrances: Vec<usize>,
     released_total: u32,
     terrain_diff: Vec<TerrainDiff>,
+    explosion_mask: Bitmap,
 }
 
 #[derive(Clone, Copy, PartialEq)]
@@ -50,6 +51,7 @@ enum LemmingVerdict {
     Continue,
     Death,
     Exit,
+    Explode,
 }
 
 const TERRAIN_DIFF_CAPACITY: usize = 99;
@@ -80,6 +82,8 @@ const CEILING_HIT_Y_RESET: i32 = MIN_FOOT_Y - 2;
 const DROWNER_MIN_WALL_DISTANCE: u32 = 8;
 
 const BOMBER_COUNTDOWN_TICKS: u32 = 79;
+const EXPLOSION_X_OFFSET: i32 = -8;
+const EXPLOSION_Y_OFFSET: i32 = -14;
 
 impl Simulation {
     pub fn new(game_data: Rc<GameData>, level: &Level) -> Result<Self> {
@@ -120,6 +124,7 @@ impl Simulation {
             entrances,
             released_total: level.parameters.released,
             terrain_diff: Vec::with_capacity(TERRAIN_DIFF_CAPACITY),
+            explosion_mask: game_data.masks.explosion.clone(),
         })
     }
 
@@ -152,6 +157,10 @@ impl Simulation {
             _ => (),
         }
 
+        if state.nuked {
+            self.tick_nuke(state);
+        }
+
         if state.level_state == LevelState::Spawn {
             self.tick_spawn(state);
         }
@@ -202,8 +211,14 @@ impl Simulation {
         state.lemmings.retain_mut(|lemming| {
             let verdict = lemming.tick(&mut terrain, &mut state.object_state);
 
-            if verdict == LemmingVerdict::Exit {
-                lemmings_rescued += 1;
+            match verdict {
+                LemmingVerdict::Exit => lemmings_rescued += 1,
+                LemmingVerdict::Explode => terrain.explode(
+                    lemming.x + EXPLOSION_X_OFFSET,
+                    lemming.y + EXPLOSION_Y_OFFSET,
+                    &self.explosion_mask,
+                ),
+                _ => (),
             }
 
             verdict == LemmingVerdict::Continue
@@ -243,6 +258,17 @@ impl Simulation {
         }
     }
 
+    fn tick_nuke(&self, state: &mut SceneStateLevel) {
+        if state.level_state == LevelState::Spawn {
+            state.level_state = LevelState::Late;
+        }
+
+        // Like the DOS version, one lemming is armed per tick in release order
+        if let Some(lemming) = state.lemmings.iter_mut().find(|lemming| lemming.can_nuke()) {
+            lemming.countdown = Some(BOMBER_COUNTDOWN_TICKS);
+        }
+    }
+
     fn tick_objects(&self, state: &mut SceneStateLevel) {
         for (i, object) in self.objects.iter().enumerate() {
             let object_state = &mut state.object_state[i];
@@ -262,6 +288,14 @@ impl Simulation {
 
 impl LemmingState {
     fn tick(&mut self, terrain: &mut Terrain, objects: &mut [ObjectState]) -> LemmingVerdict {
+        if self.health == LemmingHealth::OhNo {
+            return self.tick_oh_no(terrain);
+        }
+
+        if let Some(verdict) = self.tick_countdown() {
+            return verdict;
+        }
+
         let verdict = match &self.activity {
             Activity::Falling(_) => self.tick_faller(terrain),
             Activity::Walking => self.tick_walker(terrain),
@@ -556,6 +590,55 @@ impl LemmingState {
         }
     }
 
+    // When the countdown expires, lemmings in the air or already dying explode right away, the
+    // others start the "oh no" animation. Lemmings leaving the level are spared.
+    fn tick_countdown(&mut self) -> Option<LemmingVerdict> {
+        match self.countdown? {
+            countdown if countdown > 1 => {
+                self.countdown = Some(countdown - 1);
+                return None;
+            }
+            _ => self.countdown = None,
+        }
+
+        match self.activity {
+            Activity::Exiting | Activity::Splatting => None,
+            Activity::Falling(_)
+            | Activity::Floating(_)
+            | Activity::Drowning
+            | Activity::Frying => {
+                self.health = LemmingHealth::Exploding;
+                Some(LemmingVerdict::Explode)
+            }
+            _ => {
+                self.health = LemmingHealth::OhNo;
+                self.animation = LemmingAnimation::OhNo;
+                self.frame = 0;
+                Some(LemmingVerdict::Continue)
+            }
+        }
+    }
+
+    fn tick_oh_no(&mut self, terrain: &Terrain) -> LemmingVerdict {
+        self.frame = (self.frame + 1) % self.animation.frame_count();
+        self.y += terrain.delta_y_descend(self.x, self.y, FALL_DISTANCE_PER_FRAME) as i32;
+
+        if self.y >= (LEVEL_HEIGHT + self.animation.foot().1) as i32 {
+            LemmingVerdict::Death
+        } else if self.frame > 0 {
+            LemmingVerdict::Continue
+        } else {
+            self.health = LemmingHealth::Exploding;
+            LemmingVerdict::Explode
+        }
+    }
+
+    fn can_nuke(&self) -> bool {
+        self.countdown.is_none()
+            && self.health == LemmingHealth::Healthy
+            && !matches!(self.activity, Activity::Exiting | Activity::Splatting)
+    }
+
     fn assign_skill_unchecked(&mut self, skill: Skill) {
         match skill {
             Skill::Floater => self.floater = true,
@@ -700,6 +783,47 @@ impl<'a> Terrain<'a> {
 
         true
     }
+
+    // Removes the terrain below the opaque pixels of the mask, except steel
+    fn explode(&mut self, x: i32, y: i32, mask: &Bitmap) {
+        for row in 0..mask.height as i32 {
+            let mut run_start: Option<i32> = None;
+
+            for col in 0..=mask.width as i32 {
+                let removed = col < mask.width as i32
+                    && !mask.transparency[(row * mask.width as i32 + col) as usize]
+                    && self.erase(x + col, y + row);
+
+                match (removed, run_start) {
+                    (true, None) => run_start = Some(col),
+                    (false, Some(start)) => {
+                        self.diff.push(TerrainDiff {
+                            x: x + start,
+                            y: y + row,
+                            kind: TerrainDiffKind::Explosion((col - start) as u32),
+                        });
+                        run_start = None;
+                    }
+                    _ => (),
+                }
+            }
+        }
+    }
+
+    fn erase(&mut self, x: i32, y: i32) -> bool {
+        match self.terrain_at(x, y) {
+            Some(props) if props.solid() && !props.steel() => {
+                let index = (x + y * self.bitmap.width as i32) as usize;
+
+                self.map[index] = props.with_solid(false);
+                self.bitmap.data[index] = 0;
+                self.bitmap.transparency[index] = false;
+
+                true
+            }
+            _ => false,
+        }
+    }
 }
 
 impl Activity {
@@ -0,0 +1,191 @@
+use std::collections::VecDeque;
+
+use rustlings::game_data::Bitmap;
+
+use crate::{
+    scenes::scene_level::{
+        simulation::{
+            BOMBER_COUNTDOWN_TICKS, LemmingVerdict, Simulation,
+            test::fixture::TerrainFixtureBuilder,
+        },
+        terrain_diff::{TerrainDiff, TerrainDiffKind},
+    },
+    state::{
+        Activity, Direction, LemmingAnimation, LemmingHealth, LemmingState, LevelState,
+        ObjectState, SceneStateLevel, TerrainProps,
+    },
+};
+
+#[test]
+fn countdown_expires_into_oh_no_then_explosion() {
+    let mut terrain_fixture = TerrainFixtureBuilder::new(40, 40)
+        .with_row(0, 30, 40, TerrainProps::new())
+        .build();
+
+    let mut objects_fixture: Vec<ObjectState> = Vec::new();
+
+    let mut lemming = LemmingState::fixture(20, 30, Direction::Right, Activity::Walking);
+    lemming.countdown = Some(2);
+
+    assert_eq!(
+        lemming.tick(&mut terrain_fixture, &mut objects_fixture),
+        LemmingVerdict::Continue
+    );
+    assert_eq!((lemming.x, lemming.countdown), (21, Some(1)));
+
+    assert_eq!(
+        lemming.tick(&mut terrain_fixture, &mut objects_fixture),
+        LemmingVerdict::Continue
+    );
+    assert_eq!(lemming.x, 21, "should stand still");
+    assert_eq!(lemming.countdown, None);
+    assert_eq!(lemming.health, LemmingHealth::OhNo);
+    assert_eq!(lemming.animation, LemmingAnimation::OhNo);
+
+    for _ in 1..LemmingAnimation::OhNo.frame_count() {
+        assert_eq!(
+            lemming.tick(&mut terrain_fixture, &mut objects_fixture),
+            LemmingVerdict::Continue
+        );
+    }
+
+    assert_eq!(
+        lemming.tick(&mut terrain_fixture, &mut objects_fixture),
+        LemmingVerdict::Explode
+    );
+    assert_eq!(lemming.health, LemmingHealth::Exploding);
+}
+
+#[test]
+fn falling_bomber_explodes_immediately() {
+    let mut terrain_fixture = TerrainFixtureBuilder::new(40, 40).build();
+    let mut objects_fixture: Vec<ObjectState> = Vec::new();
+
+    let mut lemming = LemmingState::fixture(
+        20,
+        10,
+        Direction::Right,
+        Activity::Falling(Default::default()),
+    );
+    lemming.countdown = Some(1);
+
+    assert_eq!(
+        lemming.tick(&mut terrain_fixture, &mut objects_fixture),
+        LemmingVerdict::Explode
+    );
+}
+
+#[test]
+fn exiting_bomber_does_not_explode() {
+    let mut terrain_fixture = TerrainFixtureBuilder::new(40, 40).build();
+    let mut objects_fixture: Vec<ObjectState> = Vec::new();
+
+    let mut lemming = LemmingState::fixture(20, 10, Direction::Right, Activity::Exiting);
+    lemming.countdown = Some(1);
+
+    assert_eq!(
+        lemming.tick(&mut terrain_fixture, &mut objects_fixture),
+        LemmingVerdict::Continue
+    );
+    assert_eq!(lemming.health, LemmingHealth::Healthy);
+}
+
+#[test]
+fn explosion_removes_terrain_except_steel() {
+    let mut terrain_fixture = TerrainFixtureBuilder::new(10, 10)
+        .with_block(0, 0, 10, 10, TerrainProps::new())
+        .with(4, 3, TerrainProps::new().with_steel(true))
+        .build();
+
+    let mut mask = Bitmap::filled(4, 2, 0, false);
+    mask.transparency[0] = true;
+
+    terrain_fixture.explode(2, 3, &mask);
+
+    assert!(terrain_fixture.is_solid(2, 3), "transparent mask pixel");
+    assert!(terrain_fixture.is_solid(4, 3), "steel");
+    assert!(terrain_fixture.is_block_blank(2, 4, 4, 1));
+    assert!(!terrain_fixture.is_solid(3, 3) && !terrain_fixture.is_solid(5, 3));
+
+    assert_eq!(
+        terrain_fixture.sorted_diff(),
+        [
+            TerrainDiff {
+                x: 3,
+                y: 3,
+                kind: TerrainDiffKind::Explosion(1)
+            },
+            TerrainDiff {
+                x: 5,
+                y: 3,
+                kind: TerrainDiffKind::Explosion(1)
+            },
+            TerrainDiff {
+                x: 2,
+                y: 4,
+                kind: TerrainDiffKind::Explosion(4)
+            },
+        ]
+    );
+}
+
+#[test]
+fn nuke_arms_one_lemming_per_tick_and_ends_level() {
+    const WIDTH: u32 = 200;
+    const HEIGHT: u32 = 60;
+    const FLOOR_Y: u32 = 50;
+
+    let mut terrain_map = vec![TerrainProps::new(); (WIDTH * HEIGHT) as usize];
+    terrain_map[(FLOOR_Y * WIDTH) as usize..].fill(TerrainProps::new().with_solid(true));
+
+    let lemmings: VecDeque<LemmingState> = (0..3)
+        .map(|i| {
+            LemmingState::fixture(
+                40 + 50 * i,
+                FLOOR_Y as i32,
+                Direction::Right,
+                Activity::Walking,
+            )
+        })
+        .collect();
+
+    let mut state = SceneStateLevel {
+        level_state: LevelState::Spawn,
+        tick: 50,
+        terrain: Bitmap::filled(WIDTH, HEIGHT, 0, false),
+        terrain_map,
+        lemmings_out: 3,
+        lemmings,
+        nuked: true,
+        ..Default::default()
+    };
+
+    let mut simulation = Simulation {
+        objects: Vec::new(),
+        entrances: Vec::new(),
+        released_total: 10,
+        terrain_diff: Vec::new(),
+        explosion_mask: Bitmap::filled(16, 22, 0, false),
+    };
+
+    simulation.tick(&mut state);
+    assert_eq!(state.level_state, LevelState::Late, "release should stop");
+
+    let countdowns: Vec<Option<u32>> = state.lemmings.iter().map(|l| l.countdown).collect();
+    assert_eq!(countdowns, [Some(BOMBER_COUNTDOWN_TICKS - 1), None, None]);
+
+    let mut ticks = 1;
+    while !state.lemmings.is_empty() {
+        simulation.tick(&mut state);
+        ticks += 1;
+
+        assert!(ticks < 200, "lemmings should have exploded by now");
+    }
+
+    assert_eq!(
+        ticks,
+        2 + BOMBER_COUNTDOWN_TICKS as usize + LemmingAnimation::OhNo.frame_count()
+    );
+    assert_eq!(state.lemmings_in, 0);
+    assert!(!simulation.get_diff().is_empty(), "should remove terrain");
+}
@@ -34,7 +34,11 @@ fn digger_digs_three_lines_and_moves_down_on_first_frame() {
         }
     );
 
-    assert!(terrain_fixture.is_block_blank(8 + DIG_X_OFFSET, 5, DIG_LINE_WIDTH, 3));
+    assert!(
+        terrain_fixture.is_block_blank(8 + DIG_X_OFFSET, 5, DIG_LINE_WIDTH, 3),
+        "{}",
+        terrain_fixture.to_text()
+    );
     assert_eq!(
         terrain_fixture.sorted_diff(),
         &[
@@ -88,7 +92,11 @@ fn digger_digs_and_moves_down_on_frames_15_and_7() {
                     }
                 );
 
-                assert!(terrain_fixture.is_row_blank(8 + DIG_X_OFFSET, 5, DIG_LINE_WIDTH));
+                assert!(
+                    terrain_fixture.is_row_blank(8 + DIG_X_OFFSET, 5, DIG_LINE_WIDTH),
+                    "{}",
+                    terrain_fixture.to_text()
+                );
                 assert_eq!(
                     terrain_fixture.sorted_diff(),
                     &[TerrainDiff {
@@ -107,7 +115,11 @@ fn digger_digs_and_moves_down_on_frames_15_and_7() {
                     }
                 );
 
-                assert!(!terrain_fixture.is_row_blank(8 + DIG_X_OFFSET, 5, DIG_LINE_WIDTH));
+                assert!(
+                    !terrain_fixture.is_row_blank(8 + DIG_X_OFFSET, 5, DIG_LINE_WIDTH),
+                    "{}",
+                    terrain_fixture.to_text()
+                );
                 assert_eq!(terrain_fixture.sorted_diff(), &[]);
             }
         }
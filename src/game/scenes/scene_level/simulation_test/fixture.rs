@@ -1,3 +1,5 @@
+use rustlings::game_data::{TextPixel, render_ascii};
+
 use crate::{scenes::scene_level::simulation::*, state::Direction};
 
 pub struct TerrainFixtureBuilder {
@@ -108,6 +110,33 @@ impl Terrain<'_> {
         true
     }
 
+    // Terrain and trigger areas as ASCII art for assertion messages
+    pub fn to_text(&self) -> String {
+        render_ascii(0..self.width(), self.height(), |x, y| {
+            let props = self.map[(y * self.width() + x) as usize];
+
+            if props.exit() {
+                TextPixel::Trigger(InteractionType::Exit)
+            } else if props.trap() {
+                TextPixel::Trigger(InteractionType::Trap)
+            } else if props.drown() {
+                TextPixel::Trigger(InteractionType::Drown)
+            } else if props.disintegrate() {
+                TextPixel::Trigger(InteractionType::Disintegrate)
+            } else if props.one_way_left() {
+                TextPixel::Trigger(InteractionType::OneWayLeft)
+            } else if props.one_way_right() {
+                TextPixel::Trigger(InteractionType::OneWayRight)
+            } else if props.steel() {
+                TextPixel::Steel
+            } else if props.solid() {
+                TextPixel::Terrain(0)
+            } else {
+                TextPixel::Empty
+            }
+        })
+    }
+
     pub fn sorted_diff(&self) -> Vec<TerrainDiff> {
         let mut sorted = self.diff.clone();
         sorted.sort_by_key(|d| d.y);
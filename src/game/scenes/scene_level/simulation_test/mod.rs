@@ -1,3 +1,4 @@
+mod bomber;
 mod digger;
 mod drowning;
 mod environment;
@@ -1,9 +1,7 @@
-use rustlings::game_data::{
-    Level, SCREEN_HEIGHT, SKILL_PANEL_HEIGHT, SKILL_TILE_WIDTH, SKILLS, Skill,
-};
-use sdl3::keyboard::Keycode;
+use rustlings::game_data::{Level, SCREEN_HEIGHT, SKILL_PANEL_HEIGHT, SKILL_TILE_WIDTH, SKILLS};
 
 use crate::{
+    keymap::Action,
     scene::{MouseButton, MouseCoordinates, SceneEvent},
     state::SceneStateLevel,
 };
@@ -17,10 +15,23 @@ pub struct SkillPanelController {
     decremented: u32,
 
     release_rate_min: u32,
+
+    pressed_tile: Option<u32>,
 }
 
 const SKILL_PANEL_Y: u32 = SCREEN_HEIGHT - SKILL_PANEL_HEIGHT;
 
+// Keys press the skill panel tile with the same function
+fn action_tile(action: Action) -> Option<u32> {
+    match action {
+        Action::ReleaseRateDown => Some(0),
+        Action::ReleaseRateUp => Some(1),
+        Action::Pause => Some(10),
+        Action::Nuke => Some(11),
+        _ => action.skill().map(|skill| skill as u32 + 2),
+    }
+}
+
 impl SkillPanelController {
     pub fn new(level: &Level) -> Self {
         Self {
@@ -32,71 +43,30 @@ impl SkillPanelController {
     pub fn dispatch_event(&mut self, event: SceneEvent, state: &mut SceneStateLevel) -> bool {
         match event {
             SceneEvent::MouseDown(MouseButton::Left, MouseCoordinates { x, y, .. }) => {
-                self.handle_mouse_down(state, x, y)
+                if y < SKILL_PANEL_Y + 16 {
+                    return false;
+                }
+
+                self.press_tile(state, x / SKILL_TILE_WIDTH)
             }
             SceneEvent::MouseUp(MouseButton::Left, _) => self.handle_mouse_up(state),
-            SceneEvent::KeyDown { keycode, .. } => match keycode {
-                Keycode::Plus => {
-                    self.start_increment();
-                    false
-                }
-                Keycode::Minus => {
-                    self.start_decrement();
-                    false
-                }
-                Keycode::_1 => {
-                    state.selected_skill = Skill::from_repr(0).unwrap();
-                    true
-                }
-                Keycode::_2 => {
-                    state.selected_skill = Skill::from_repr(1).unwrap();
-                    true
-                }
-                Keycode::_3 => {
-                    state.selected_skill = Skill::from_repr(2).unwrap();
-                    true
-                }
-                Keycode::_4 => {
-                    state.selected_skill = Skill::from_repr(3).unwrap();
-                    true
-                }
-                Keycode::_5 => {
-                    state.selected_skill = Skill::from_repr(4).unwrap();
-                    true
-                }
-                Keycode::_6 => {
-                    state.selected_skill = Skill::from_repr(5).unwrap();
-                    true
-                }
-                Keycode::_7 => {
-                    state.selected_skill = Skill::from_repr(6).unwrap();
-                    true
-                }
-                Keycode::_8 => {
-                    state.selected_skill = Skill::from_repr(7).unwrap();
-                    true
-                }
-                Keycode::P => {
-                    state.paused = !state.paused;
-                    true
-                }
-                _ => false,
+            SceneEvent::ActionDown(action) => match action_tile(action) {
+                Some(tile_index) => self.press_tile(state, tile_index),
+                None => false,
             },
-            SceneEvent::KeyUp { keycode, .. } => match keycode {
-                Keycode::Plus => {
-                    self.stop_increment(state);
-                    true
-                }
-                Keycode::Minus => {
-                    self.stop_decrement(state);
-                    true
-                }
-                _ => false,
+            SceneEvent::ActionUp(action) => match action_tile(action) {
+                Some(tile_index) => self.release_tile(state, tile_index),
+                None => false,
             },
             _ => false,
         }
     }
 
+    // The tile shown as pressed while its mouse button or key is held
+    pub fn pressed_tile(&self) -> Option<u32> {
+        self.pressed_tile
+    }
+
     pub fn tick(&mut self, state: &mut SceneStateLevel) -> bool {
         if self.incrementing {
             self.increase_release(state);
@@ -109,43 +79,40 @@ impl SkillPanelController {
         self.incrementing || self.decrementing
     }
 
-    fn handle_mouse_down(&mut self, state: &mut SceneStateLevel, x: u32, y: u32) -> bool {
-        if y < SKILL_PANEL_Y + 16 {
-            return false;
+    fn press_tile(&mut self, state: &mut SceneStateLevel, tile_index: u32) -> bool {
+        match tile_index {
+            0 => self.start_decrement(),
+            1 => self.start_increment(),
+            2..10 => state.selected_skill = SKILLS[(tile_index - 2) as usize],
+            10 => state.paused = !state.paused,
+            11 => state.nuked = true,
+            _ => return false,
         }
 
-        let tile_index = x / SKILL_TILE_WIDTH;
+        self.pressed_tile = Some(tile_index);
+        true
+    }
 
+    fn release_tile(&mut self, state: &mut SceneStateLevel, tile_index: u32) -> bool {
         match tile_index {
-            0 => {
-                self.start_decrement();
-                false
-            }
-            1 => {
-                self.start_increment();
-                false
-            }
-            2..10 => {
-                state.selected_skill = SKILLS[(tile_index - 2) as usize];
-                true
-            }
-            10 => {
-                state.paused = !state.paused;
-                true
-            }
-            11 => {
-                println!("armageddon");
-                false
-            }
-            _ => false,
+            0 => self.stop_decrement(state),
+            1 => self.stop_increment(state),
+            _ => (),
+        }
+
+        if self.pressed_tile == Some(tile_index) {
+            self.pressed_tile = None;
         }
+
+        true
     }
 
     fn handle_mouse_up(&mut self, state: &mut SceneStateLevel) -> bool {
-        let redraw = self.incrementing || self.decrementing;
+        let redraw = self.incrementing || self.decrementing || self.pressed_tile.is_some();
 
         self.stop_decrement(state);
         self.stop_increment(state);
+        self.pressed_tile = None;
 
         redraw
     }
@@ -21,7 +21,7 @@ use sdl3::{
 
 use crate::{
     scenes::scene_level::{cache::Cache, selection_controller::SelectionMode},
-    state::{Activity, LemmingState, SceneStateLevel},
+    state::{Activity, LemmingState, SceneStateLevel, Speed},
 };
 
 pub struct SkillPanelRenderer<'texture_creator> {
@@ -37,6 +37,7 @@ pub struct SkillPanelRenderer<'texture_creator> {
     lemmings_released_total: u32,
     release_rate_min: u32,
     selected_skill: Skill,
+    pressed_tile: Option<u32>,
 
     text_model: SkillPanelTextModel,
 
@@ -103,6 +104,7 @@ struct SkillPanelStatsModel {
 
     remaining_time_seconds: u32,
     paused: bool,
+    speed: Speed,
     selected_lemming_count: u32,
     selected_lemming_description: Option<LemmingDescription>,
 }
@@ -120,6 +122,7 @@ impl SkillPanelTextModel {
         state: &SceneStateLevel,
         cache: &mut Cache,
         selection_mode: SelectionMode,
+        speed: Speed,
     ) -> Self {
         Self {
             remaining_skills: state.remaining_skills,
@@ -130,6 +133,7 @@ impl SkillPanelTextModel {
                 lemmings_in: state.lemmings_in,
                 remaining_time_seconds: state.remaining_time_seconds,
                 paused: state.paused,
+                speed,
                 selected_lemming_count: state.selection.lemming_count,
                 selected_lemming_description: state
                     .selected_lemming_for_ui(selection_mode, cache)
@@ -145,7 +149,7 @@ impl<'texture_creator> SkillPanelRenderer<'texture_creator> {
         game_data: Rc<GameData>,
         texture_creator: &'texture_creator TextureCreator<T>,
     ) -> Result<Self> {
-        let palette_skill_panel = game_data.resolve_skill_panel_palette(0);
+        let palette_skill_panel = game_data.resolve_skill_panel_palette(0)?;
 
         let texture_skill_panel = texture_from_bitmap(
             &game_data.skill_panel.panel,
@@ -192,6 +196,7 @@ impl<'texture_creator> SkillPanelRenderer<'texture_creator> {
             lemmings_released_total: level.parameters.released,
             release_rate_min: level.parameters.release_rate,
             selected_skill: Default::default(),
+            pressed_tile: None,
             text_model: Default::default(),
             stats_current: String::with_capacity(40),
             stats_new: String::with_capacity(40),
@@ -207,11 +212,13 @@ impl<'texture_creator> SkillPanelRenderer<'texture_creator> {
         state: &SceneStateLevel,
         cache: &mut Cache,
         selection_mode: SelectionMode,
+        pressed_tile: Option<u32>,
+        speed: Speed,
         canvas: &mut Canvas<Window>,
     ) -> Result<bool> {
         let mut updated = false;
 
-        let text_model = SkillPanelTextModel::from_state(state, cache, selection_mode);
+        let text_model = SkillPanelTextModel::from_state(state, cache, selection_mode, speed);
 
         if text_model != self.text_model || self.full_redraw {
             self.draw_text_overlay(&text_model, canvas)?;
@@ -220,7 +227,10 @@ impl<'texture_creator> SkillPanelRenderer<'texture_creator> {
             updated = true;
         }
 
-        if updated || state.selected_skill != self.selected_skill {
+        if updated
+            || state.selected_skill != self.selected_skill
+            || pressed_tile != self.pressed_tile
+        {
             with_texture_canvas(canvas, &mut self.texture, |canvas| {
                 self.texture_skill_panel
                     .set_blend_mode(sdl3::render::BlendMode::None);
@@ -235,19 +245,27 @@ impl<'texture_creator> SkillPanelRenderer<'texture_creator> {
                     .set_blend_mode(BlendMode::Blend);
                 self.texture_selected_skill_frame
                     .set_scale_mode(ScaleMode::Nearest);
-                canvas.copy(
-                    &self.texture_selected_skill_frame,
-                    None,
-                    Rect::new(
-                        ((state.selected_skill as u32 + 2) * SKILL_TILE_WIDTH) as i32,
-                        16,
-                        SKILL_TILE_WIDTH,
-                        SKILL_TILE_HEIGHT,
-                    ),
-                )?;
+
+                // A held button is framed like the selected skill
+                for tile_index in [Some(state.selected_skill as u32 + 2), pressed_tile]
+                    .into_iter()
+                    .flatten()
+                {
+                    canvas.copy(
+                        &self.texture_selected_skill_frame,
+                        None,
+                        Rect::new(
+                            (tile_index * SKILL_TILE_WIDTH) as i32,
+                            16,
+                            SKILL_TILE_WIDTH,
+                            SKILL_TILE_HEIGHT,
+                        ),
+                    )?;
+                }
 
                 updated = true;
                 self.selected_skill = state.selected_skill;
+                self.pressed_tile = pressed_tile;
 
                 Ok(())
             })?;
@@ -420,13 +438,30 @@ fn format_stats(str: &mut String, model: &SkillPanelStatsModel, lemmings_release
         .unwrap();
     }
 
-    // 10 characters for time
+    // 10 characters for time, labelled with the game speed unless it is normal
     write!(
         str,
         "{} {:1}-{:0>2}",
-        if model.paused { "PAUSE" } else { " TIME" },
+        if model.paused {
+            "PAUSE"
+        } else {
+            speed_label(model.speed)
+        },
         model.remaining_time_seconds / 60,
         model.remaining_time_seconds % 60
     )
     .unwrap();
 }
+
+// The skill panel font has no '/' or '.', so speeds are shown as percentages
+fn speed_label(speed: Speed) -> &'static str {
+    match speed {
+        Speed::Quarter => "  25%",
+        Speed::Half => "  50%",
+        Speed::Normal => " TIME",
+        Speed::Double => " 200%",
+        Speed::Quadruple => " 400%",
+        Speed::Octuple => " 800%",
+        Speed::Uncapped => "  MAX",
+    }
+}
@@ -1,6 +1,8 @@
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum TerrainDiffKind {
     Dig,
+    // Width of the row removed by an explosion
+    Explosion(u32),
 }
 
 #[derive(Clone, Copy, PartialEq)]
@@ -20,7 +22,15 @@ pub struct TerrainDiff {
 impl TerrainDiff {
     pub fn visibility_target(self) -> VisibilityTarget {
         match self.kind {
-            TerrainDiffKind::Dig => VisibilityTarget::Late,
+            TerrainDiffKind::Dig | TerrainDiffKind::Explosion(_) => VisibilityTarget::Late,
+        }
+    }
+
+    // Last pixel of the removed row
+    pub fn end_x(self) -> i32 {
+        match self.kind {
+            TerrainDiffKind::Dig => self.x + 8,
+            TerrainDiffKind::Explosion(width) => self.x + width as i32 - 1,
         }
     }
 }
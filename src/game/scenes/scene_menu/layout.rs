@@ -0,0 +1,66 @@
+use strum::VariantArray;
+
+use crate::{
+    geometry::Rect,
+    scenes::menu_screen::{MENU_GLYPH_HEIGHT, MENU_WIDTH},
+};
+
+pub const LOGO_X: u32 = 4;
+pub const LOGO_Y: u32 = 10;
+
+pub const CARD_WIDTH: u32 = 120;
+pub const CARD_HEIGHT: u32 = 61;
+
+pub const DIFFICULTY_SIGN_X: u32 = 24;
+pub const DIFFICULTY_SIGN_Y: u32 = 26;
+
+pub const REEL_Y: u32 = 296;
+pub const REEL_END_WIDTH: u32 = 48;
+pub const REEL_X: u32 = REEL_END_WIDTH;
+pub const REEL_WIDTH: u32 = MENU_WIDTH - 2 * REEL_END_WIDTH;
+pub const REEL_HEIGHT: u32 = MENU_GLYPH_HEIGHT;
+
+#[derive(Clone, Copy, PartialEq, VariantArray)]
+#[cfg_attr(test, derive(Debug))]
+pub enum Button {
+    Play,
+    LevelCode,
+    Rating,
+    Exit,
+}
+
+impl Button {
+    pub fn rect(self) -> Rect {
+        let (x, y) = match self {
+            Self::Play => (136, 120),
+            Self::LevelCode => (264, 120),
+            Self::Rating => (392, 120),
+            Self::Exit => (264, 189),
+        };
+
+        Rect::new(x, y, CARD_WIDTH, CARD_HEIGHT)
+    }
+
+    pub fn at(x: u32, y: u32) -> Option<Self> {
+        Self::VARIANTS.iter().copied().find(|button| {
+            let rect = button.rect();
+
+            (rect.x..rect.x + rect.width).contains(&x)
+                && (rect.y..rect.y + rect.height).contains(&y)
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::scenes::scene_menu::layout::Button;
+
+    #[test]
+    fn button_at() {
+        assert_eq!(Button::at(136, 120), Some(Button::Play));
+        assert_eq!(Button::at(511, 180), Some(Button::Rating));
+        assert_eq!(Button::at(300, 200), Some(Button::Exit));
+        assert_eq!(Button::at(256, 150), None);
+        assert_eq!(Button::at(136, 181), None);
+    }
+}
@@ -0,0 +1,5 @@
+mod layout;
+mod renderer;
+mod scene_menu;
+
+pub use scene_menu::*;
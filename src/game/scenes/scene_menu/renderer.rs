@@ -0,0 +1,196 @@
+use anyhow::{Result, anyhow};
+use rustlings::{
+    game_data::{GameData, NUM_DIFFICULTY_SIGNS},
+    sdl::{SDLSprite, with_texture_canvas},
+};
+use sdl3::{
+    pixels::PixelFormat,
+    rect::Rect as SdlRect,
+    render::{Canvas, Texture, TextureCreator},
+    video::Window,
+};
+
+use crate::{
+    geometry::Rect,
+    scene::Compositor,
+    scenes::{
+        menu_screen::{MENU_HEIGHT, MENU_WIDTH, MenuScreen},
+        scene_menu::layout::{
+            Button, DIFFICULTY_SIGN_X, DIFFICULTY_SIGN_Y, LOGO_X, LOGO_Y, REEL_HEIGHT, REEL_WIDTH,
+            REEL_X, REEL_Y,
+        },
+    },
+};
+
+const TEXTURE_ID_SCREEN: usize = 0;
+
+pub struct Renderer<'texture_creator> {
+    texture_screen: Texture<'texture_creator>,
+
+    screen: MenuScreen<'texture_creator>,
+    logo: SDLSprite<'texture_creator>,
+    sign_play: SDLSprite<'texture_creator>,
+    sign_level_code: SDLSprite<'texture_creator>,
+    sign_difficulty: SDLSprite<'texture_creator>,
+    sign_exit: SDLSprite<'texture_creator>,
+    difficulty_signs: Vec<SDLSprite<'texture_creator>>,
+    reel: SDLSprite<'texture_creator>,
+    scroller_left: SDLSprite<'texture_creator>,
+    scroller_right: SDLSprite<'texture_creator>,
+
+    redraw: bool,
+}
+
+impl<'texture_creator> Renderer<'texture_creator> {
+    pub fn new<T>(
+        game_data: &GameData,
+        texture_creator: &'texture_creator TextureCreator<T>,
+    ) -> Result<Self> {
+        let menu = &game_data.main_menu;
+        let palette = &menu.palette;
+
+        let from_bitmap = |bitmap| SDLSprite::from_bitmap(bitmap, palette, texture_creator);
+        let from_sprite = |sprite| SDLSprite::from_sprite(sprite, palette, texture_creator);
+
+        let texture_screen = texture_creator.create_texture_target(
+            PixelFormat::RGBA8888,
+            MENU_WIDTH,
+            MENU_HEIGHT,
+        )?;
+
+        Ok(Renderer {
+            texture_screen,
+            screen: MenuScreen::new(game_data, texture_creator)?,
+            logo: from_bitmap(&menu.logo)?,
+            sign_play: from_bitmap(&menu.sign_play)?,
+            sign_level_code: from_bitmap(&menu.sign_level_code)?,
+            sign_difficulty: from_bitmap(&menu.sign_difficulty)?,
+            sign_exit: from_bitmap(&menu.sign_exit)?,
+            difficulty_signs: menu
+                .difficulty_signs
+                .iter()
+                .map(from_bitmap)
+                .collect::<Result<_>>()?,
+            reel: from_bitmap(&menu.reel)?,
+            scroller_left: from_sprite(&menu.scroller_left)?,
+            scroller_right: from_sprite(&menu.scroller_right)?,
+            redraw: true,
+        })
+    }
+
+    pub fn mark_for_redraw(&mut self) {
+        self.redraw = true;
+    }
+
+    pub fn will_redraw(&self) -> bool {
+        self.redraw
+    }
+
+    pub fn register_layers(&self, compositor: &mut dyn Compositor) {
+        compositor.add_layer(
+            TEXTURE_ID_SCREEN,
+            MENU_WIDTH,
+            MENU_HEIGHT,
+            Rect::new(0, 0, MENU_WIDTH, MENU_HEIGHT),
+        );
+    }
+
+    pub fn texture(&mut self, id: usize) -> Result<&mut Texture<'texture_creator>> {
+        match id {
+            TEXTURE_ID_SCREEN => Ok(&mut self.texture_screen),
+            _ => Err(anyhow!("invalid texture id {}", id)),
+        }
+    }
+
+    // `scroll_offset` is the distance the banner text has moved into the reel from the right
+    pub fn draw(
+        &mut self,
+        canvas: &mut Canvas<Window>,
+        rating: usize,
+        banner: &str,
+        scroll_offset: u32,
+        frame: usize,
+    ) -> Result<bool> {
+        if !self.redraw {
+            return Ok(false);
+        }
+        self.redraw = false;
+
+        let Self {
+            texture_screen,
+            screen,
+            logo,
+            sign_play,
+            sign_level_code,
+            sign_difficulty,
+            sign_exit,
+            difficulty_signs,
+            reel,
+            scroller_left,
+            scroller_right,
+            ..
+        } = self;
+
+        with_texture_canvas(canvas, texture_screen, |canvas| -> Result<()> {
+            screen.draw_background(canvas)?;
+
+            logo.blit(canvas, LOGO_X as i32, LOGO_Y as i32, 0, 1, false, false)?;
+
+            for (button, sign) in [
+                (Button::Play, &*sign_play),
+                (Button::LevelCode, &*sign_level_code),
+                (Button::Rating, &*sign_difficulty),
+                (Button::Exit, &*sign_exit),
+            ] {
+                let rect = button.rect();
+                sign.blit(canvas, rect.x as i32, rect.y as i32, 0, 1, false, false)?;
+            }
+
+            let rating_rect = Button::Rating.rect();
+            difficulty_signs[rating % NUM_DIFFICULTY_SIGNS].blit(
+                canvas,
+                (rating_rect.x + DIFFICULTY_SIGN_X) as i32,
+                (rating_rect.y + DIFFICULTY_SIGN_Y) as i32,
+                0,
+                1,
+                false,
+                false,
+            )?;
+
+            for x in (REEL_X..REEL_X + REEL_WIDTH).step_by(reel.width as usize) {
+                reel.blit(canvas, x as i32, REEL_Y as i32, 0, 1, false, false)?;
+            }
+
+            scroller_left.blit(canvas, 0, REEL_Y as i32, frame, 1, false, false)?;
+            scroller_right.blit(
+                canvas,
+                (REEL_X + REEL_WIDTH) as i32,
+                REEL_Y as i32,
+                frame,
+                1,
+                false,
+                false,
+            )?;
+
+            canvas.set_clip_rect(SdlRect::new(
+                REEL_X as i32,
+                REEL_Y as i32,
+                REEL_WIDTH,
+                REEL_HEIGHT,
+            ));
+
+            screen.draw_text(
+                canvas,
+                (REEL_X + REEL_WIDTH) as i32 - scroll_offset as i32,
+                REEL_Y as i32,
+                banner,
+            )?;
+
+            canvas.set_clip_rect(None);
+
+            Ok(())
+        })?;
+
+        Ok(true)
+    }
+}
@@ -0,0 +1,202 @@
+use std::rc::Rc;
+
+use anyhow::Result;
+use rustlings::game_data::{
+    GameData, LEVELS_PER_DIFFICULTY, NUM_DIFFICULTY_SIGNS, decode_level_index,
+};
+use sdl3::{
+    keyboard::Keycode,
+    render::{Canvas, Texture, TextureCreator},
+    video::Window,
+};
+
+use crate::{
+    scene::{Compositor, CursorType, MouseButton, Scene, SceneEvent},
+    scenes::{
+        menu_screen::{MENU_ASPECT, MENU_FADE_MSEC, MENU_GLYPH_WIDTH, MENU_HEIGHT, MENU_WIDTH},
+        scene_menu::{
+            layout::{Button, REEL_WIDTH},
+            renderer::Renderer,
+        },
+    },
+    state::{GameState, SceneState, Screen},
+};
+
+const SCROLL_TICK_MSEC: u64 = 1000 / 30;
+const SCROLL_PIXELS_PER_TICK: u64 = 2;
+
+const BANNER: &str = "Lemmings  -  a game by DMA Design  -  rustlings engine  -  \
+    Click on PLAY or press F1 to start  -  \
+    Press F2 to enter a level code  -  \
+    Press F4 or the arrow keys to select the difficulty rating  -  \
+    Press ESC to exit";
+
+#[derive(PartialEq)]
+enum Status {
+    Running,
+    DonePlay,
+    DoneCode,
+    DoneExit,
+}
+
+pub struct SceneMenu<'texture_creator> {
+    game_state: GameState,
+    status: Status,
+    renderer: Renderer<'texture_creator>,
+
+    clock_msec: u64,
+}
+
+impl<'texture_creator> SceneMenu<'texture_creator> {
+    pub fn new<T>(
+        game_data: Rc<GameData>,
+        game_state: GameState,
+        texture_creator: &'texture_creator TextureCreator<T>,
+    ) -> Result<Self> {
+        Ok(SceneMenu {
+            game_state,
+            status: Status::Running,
+            renderer: Renderer::new(&game_data, texture_creator)?,
+            clock_msec: 0,
+        })
+    }
+
+    fn rating(&self) -> usize {
+        decode_level_index(self.game_state.current_level).0 as usize
+    }
+
+    // Switching the rating starts over at its first level
+    fn select_rating(&mut self, rating: usize) {
+        let rating = rating % NUM_DIFFICULTY_SIGNS;
+
+        if rating != self.rating() {
+            self.game_state.current_level = rating * LEVELS_PER_DIFFICULTY;
+            self.renderer.mark_for_redraw();
+        }
+    }
+
+    fn activate(&mut self, button: Button, mouse_button: MouseButton) {
+        if self.status != Status::Running {
+            return;
+        }
+
+        match (button, mouse_button) {
+            (Button::Play, _) => self.status = Status::DonePlay,
+            (Button::LevelCode, _) => self.status = Status::DoneCode,
+            (Button::Rating, MouseButton::Left) => self.select_rating(self.rating() + 1),
+            (Button::Rating, MouseButton::Right) => {
+                self.select_rating(self.rating() + NUM_DIFFICULTY_SIGNS - 1)
+            }
+            (Button::Exit, _) => self.status = Status::DoneExit,
+        }
+    }
+
+    fn scroll_ticks(&self) -> u64 {
+        self.clock_msec / SCROLL_TICK_MSEC
+    }
+}
+
+impl<'texture_creator> Scene<'texture_creator> for SceneMenu<'texture_creator> {
+    fn finish(mut self: Box<Self>) -> (GameState, SceneState) {
+        match self.status {
+            Status::Running => (),
+            Status::DonePlay => self.game_state.screen = Screen::Preview,
+            Status::DoneCode => self.game_state.screen = Screen::Code,
+            Status::DoneExit => self.game_state.screen = Screen::Exit,
+        }
+
+        (self.game_state, SceneState::None)
+    }
+
+    fn width(&self) -> u32 {
+        MENU_WIDTH
+    }
+
+    fn height(&self) -> u32 {
+        MENU_HEIGHT
+    }
+
+    fn aspect(&self) -> f32 {
+        MENU_ASPECT
+    }
+
+    fn fade_msec(&self) -> u64 {
+        MENU_FADE_MSEC
+    }
+
+    fn set_is_fullscreen(&mut self, _is_fullscreen: bool) {}
+
+    fn set_mouse_enabled(&mut self, _mouse_enabled: bool) {}
+
+    fn cursor_type(&mut self) -> CursorType {
+        CursorType::Crosshair
+    }
+
+    fn dispatch_event(&mut self, event: SceneEvent) {
+        match event {
+            SceneEvent::MouseDown(mouse_button, coordinates) => {
+                if let Some(button) = Button::at(coordinates.x, coordinates.y) {
+                    self.activate(button, mouse_button);
+                }
+            }
+            SceneEvent::KeyDown { keycode, .. } => match keycode {
+                Keycode::F1 | Keycode::Return => self.activate(Button::Play, MouseButton::Left),
+                Keycode::F2 => self.activate(Button::LevelCode, MouseButton::Left),
+                Keycode::F4 | Keycode::Up => self.activate(Button::Rating, MouseButton::Left),
+                Keycode::Down => self.activate(Button::Rating, MouseButton::Right),
+                Keycode::Escape => self.activate(Button::Exit, MouseButton::Left),
+                _ => (),
+            },
+            _ => (),
+        }
+    }
+
+    fn tick(&mut self, _canvas: &mut Canvas<Window>, clock_msec: u64) -> Result<()> {
+        let scroll_ticks_old = self.scroll_ticks();
+        self.clock_msec = clock_msec;
+
+        if self.scroll_ticks() != scroll_ticks_old {
+            self.renderer.mark_for_redraw();
+        }
+
+        Ok(())
+    }
+
+    fn next_tick_at_msec(&self) -> u64 {
+        (self.scroll_ticks() + 1) * SCROLL_TICK_MSEC
+    }
+
+    fn is_complete(&self) -> bool {
+        self.status != Status::Running
+    }
+
+    fn texture(&mut self, id: usize) -> Result<&mut Texture<'texture_creator>> {
+        self.renderer.texture(id)
+    }
+
+    fn register_layers(&self, compositor: &mut dyn Compositor) {
+        self.renderer.register_layers(compositor);
+    }
+
+    fn draw(&mut self, canvas: &mut Canvas<Window>) -> Result<bool> {
+        // The banner enters the reel from the right and starts over once it has left on the left
+        let banner_width = BANNER.chars().count() as u64 * MENU_GLYPH_WIDTH as u64;
+        let scroll_ticks = self.scroll_ticks();
+        let scroll_offset =
+            (scroll_ticks * SCROLL_PIXELS_PER_TICK) % (banner_width + REEL_WIDTH as u64);
+
+        let rating = self.rating();
+
+        self.renderer.draw(
+            canvas,
+            rating,
+            BANNER,
+            scroll_offset as u32,
+            scroll_ticks as usize,
+        )
+    }
+
+    fn will_redraw(&self) -> bool {
+        self.renderer.will_redraw()
+    }
+}
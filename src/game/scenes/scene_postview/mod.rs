@@ -0,0 +1,4 @@
+mod renderer;
+mod scene_postview;
+
+pub use scene_postview::*;
@@ -0,0 +1,266 @@
+use anyhow::{Result, anyhow};
+use rustlings::{
+    game_data::{GameData, NUM_LEVELS, code_for_level, decode_level_index},
+    sdl::with_texture_canvas,
+};
+use sdl3::{
+    pixels::PixelFormat,
+    render::{Canvas, Texture, TextureCreator},
+    video::Window,
+};
+
+use crate::{
+    geometry::Rect,
+    scene::Compositor,
+    scenes::menu_screen::{MENU_HEIGHT, MENU_WIDTH, MenuScreen},
+    state::LevelOutcome,
+};
+
+const TEXTURE_ID_SCREEN: usize = 0;
+
+const TEXT_Y: u32 = 16;
+const LINE_HEIGHT: u32 = 24;
+const CONTINUE_Y: u32 = 286;
+
+pub struct Renderer<'texture_creator> {
+    texture_screen: Texture<'texture_creator>,
+    screen: MenuScreen<'texture_creator>,
+
+    lines: Vec<String>,
+    continue_lines: [&'static str; 2],
+    redraw: bool,
+}
+
+impl<'texture_creator> Renderer<'texture_creator> {
+    pub fn new<T>(
+        game_data: &GameData,
+        level_index: usize,
+        outcome: &LevelOutcome,
+        texture_creator: &'texture_creator TextureCreator<T>,
+    ) -> Result<Self> {
+        let texture_screen = texture_creator.create_texture_target(
+            PixelFormat::RGBA8888,
+            MENU_WIDTH,
+            MENU_HEIGHT,
+        )?;
+
+        let continue_line = if outcome.is_success() {
+            "Press left mouse button for next level"
+        } else {
+            "Press left mouse button to retry level"
+        };
+
+        Ok(Renderer {
+            texture_screen,
+            screen: MenuScreen::new(game_data, texture_creator)?,
+            lines: results(level_index, outcome)?,
+            continue_lines: [continue_line, "Press right mouse button for menu"],
+            redraw: true,
+        })
+    }
+
+    pub fn will_redraw(&self) -> bool {
+        self.redraw
+    }
+
+    pub fn register_layers(&self, compositor: &mut dyn Compositor) {
+        compositor.add_layer(
+            TEXTURE_ID_SCREEN,
+            MENU_WIDTH,
+            MENU_HEIGHT,
+            Rect::new(0, 0, MENU_WIDTH, MENU_HEIGHT),
+        );
+    }
+
+    pub fn texture(&mut self, id: usize) -> Result<&mut Texture<'texture_creator>> {
+        match id {
+            TEXTURE_ID_SCREEN => Ok(&mut self.texture_screen),
+            _ => Err(anyhow!("invalid texture id {}", id)),
+        }
+    }
+
+    pub fn draw(&mut self, canvas: &mut Canvas<Window>) -> Result<bool> {
+        if !self.redraw {
+            return Ok(false);
+        }
+        self.redraw = false;
+
+        let Self {
+            texture_screen,
+            screen,
+            lines,
+            continue_lines,
+            ..
+        } = self;
+
+        with_texture_canvas(canvas, texture_screen, |canvas| -> Result<()> {
+            screen.draw_background(canvas)?;
+
+            for (i, line) in lines.iter().enumerate() {
+                screen.draw_text_centered(
+                    canvas,
+                    (TEXT_Y + i as u32 * LINE_HEIGHT) as i32,
+                    line,
+                )?;
+            }
+
+            for (i, line) in continue_lines.iter().enumerate() {
+                screen.draw_text_centered(
+                    canvas,
+                    (CONTINUE_Y + i as u32 * LINE_HEIGHT) as i32,
+                    line,
+                )?;
+            }
+
+            Ok(())
+        })?;
+
+        Ok(true)
+    }
+}
+
+// The graded messages of the DOS version, depending on how the rescued percentage compares to
+// the required one
+fn verdict(rescued: u32, required: u32) -> [&'static str; 2] {
+    if rescued == 0 {
+        [
+            "ROCK BOTTOM! I hope for your sake",
+            "that you nuked that level.",
+        ]
+    } else if rescued < required / 2 {
+        [
+            "Better rethink your strategy before",
+            "you try this level again!",
+        ]
+    } else if rescued + 5 < required {
+        [
+            "A little more practice on this level",
+            "is definitely recommended.",
+        ]
+    } else if rescued + 1 < required {
+        [
+            "You got pretty close that time.",
+            "Now try again for that few % extra.",
+        ]
+    } else if rescued + 1 == required {
+        ["OH NO, So near and yet so far.", "Maybe this time....."]
+    } else if rescued == required {
+        [
+            "SPOT ON. You can't get much closer",
+            "than that. Let's try the next....",
+        ]
+    } else if rescued < required + 20 {
+        [
+            "That level seemed no problem to you",
+            "on that attempt. Onto the next....",
+        ]
+    } else if rescued < 100 {
+        [
+            "You totally stormed that level!",
+            "Let's see if you can storm the next",
+        ]
+    } else {
+        [
+            "Superb! You rescued every lemming",
+            "on that level. Can you do it again?",
+        ]
+    }
+}
+
+fn results(level_index: usize, outcome: &LevelOutcome) -> Result<Vec<String>> {
+    let rescued = outcome.percentage_rescued();
+    let required = outcome.percentage_required();
+    let [verdict_top, verdict_bottom] = verdict(rescued, required);
+
+    let mut lines = vec![
+        if outcome.time_up() {
+            "Your time is up!"
+        } else {
+            "All lemmings accounted for."
+        }
+        .to_string(),
+        String::new(),
+        format!("You rescued {}%", rescued),
+        format!("You needed  {}%", required),
+        String::new(),
+        verdict_top.to_string(),
+        verdict_bottom.to_string(),
+    ];
+
+    if outcome.is_success() {
+        let next_level = (level_index + 1) % NUM_LEVELS;
+        let (_, number) = decode_level_index(next_level);
+
+        lines.push(String::new());
+        lines.push(format!("Your Access Code for Level {}", number));
+        lines.push(format!(
+            "is {}",
+            code_for_level(next_level, Some(rescued as usize), None)?
+        ));
+    }
+
+    Ok(lines)
+}
+
+#[cfg(test)]
+mod test {
+    use rustlings::game_data::code_for_level;
+
+    use crate::{
+        scenes::scene_postview::renderer::{results, verdict},
+        state::LevelOutcome,
+    };
+
+    #[test]
+    fn verdicts() {
+        let first_line = |rescued, required| verdict(rescued, required)[0];
+
+        assert_eq!(first_line(0, 10), "ROCK BOTTOM! I hope for your sake");
+        assert_eq!(first_line(20, 50), "Better rethink your strategy before");
+        assert_eq!(first_line(44, 50), "A little more practice on this level");
+        assert_eq!(first_line(45, 50), "You got pretty close that time.");
+        assert_eq!(first_line(49, 50), "OH NO, So near and yet so far.");
+        assert_eq!(first_line(50, 50), "SPOT ON. You can't get much closer");
+        assert_eq!(first_line(69, 50), "That level seemed no problem to you");
+        assert_eq!(first_line(70, 50), "You totally stormed that level!");
+        assert_eq!(first_line(100, 50), "Superb! You rescued every lemming");
+    }
+
+    #[test]
+    fn results_success() {
+        let outcome = LevelOutcome {
+            released: 50,
+            rescued: 40,
+            required: 25,
+            remaining_time_seconds: 30,
+            skills_used: 4,
+        };
+
+        let lines = results(29, &outcome).unwrap();
+
+        assert_eq!(lines[0], "All lemmings accounted for.");
+        assert_eq!(lines[2], "You rescued 80%");
+        assert_eq!(lines[3], "You needed  50%");
+        assert_eq!(lines[8], "Your Access Code for Level 1");
+        assert_eq!(
+            lines[9],
+            format!("is {}", code_for_level(30, Some(80), None).unwrap())
+        );
+    }
+
+    #[test]
+    fn results_failure() {
+        let outcome = LevelOutcome {
+            released: 10,
+            rescued: 0,
+            required: 5,
+            remaining_time_seconds: 0,
+            skills_used: 0,
+        };
+
+        let lines = results(0, &outcome).unwrap();
+
+        assert_eq!(lines[0], "Your time is up!");
+        assert_eq!(lines.len(), 7);
+    }
+}
@@ -0,0 +1,170 @@
+use std::rc::Rc;
+
+use anyhow::{Result, bail};
+use rustlings::game_data::{GameData, NUM_LEVELS, file::profile::LevelRecord};
+use sdl3::{
+    keyboard::Keycode,
+    render::{Canvas, Texture, TextureCreator},
+    video::Window,
+};
+
+use crate::{
+    scene::{Compositor, CursorType, MouseButton, Scene, SceneEvent},
+    scenes::{
+        menu_screen::{MENU_ASPECT, MENU_FADE_MSEC, MENU_HEIGHT, MENU_WIDTH},
+        scene_postview::renderer::Renderer,
+    },
+    state::{GameState, LevelOutcome, SceneState, Screen},
+};
+
+#[derive(PartialEq)]
+enum Status {
+    Running,
+    DoneContinue,
+    DoneMenu,
+}
+
+// Results shown after a level has ended. The game only advances to the next level if enough
+// lemmings were rescued, otherwise continuing retries the level. Successful results are recorded
+// in the profile.
+pub struct ScenePostview<'texture_creator> {
+    game_state: GameState,
+    outcome: LevelOutcome,
+    status: Status,
+    renderer: Renderer<'texture_creator>,
+}
+
+impl<'texture_creator> ScenePostview<'texture_creator> {
+    pub fn new<T>(
+        game_data: Rc<GameData>,
+        game_state: GameState,
+        scene_state: SceneState,
+        texture_creator: &'texture_creator TextureCreator<T>,
+    ) -> Result<Self> {
+        let outcome = match scene_state {
+            SceneState::Postview(outcome) => outcome,
+            _ => bail!("no level outcome for the postview screen"),
+        };
+
+        let renderer = Renderer::new(
+            &game_data,
+            game_state.current_level,
+            &outcome,
+            texture_creator,
+        )?;
+
+        Ok(ScenePostview {
+            game_state,
+            outcome,
+            status: Status::Running,
+            renderer,
+        })
+    }
+
+    fn complete(&mut self, status: Status) {
+        if self.status == Status::Running {
+            self.status = status;
+        }
+    }
+}
+
+impl<'texture_creator> Scene<'texture_creator> for ScenePostview<'texture_creator> {
+    fn finish(mut self: Box<Self>) -> (GameState, SceneState) {
+        if self.status == Status::Running {
+            return (self.game_state, SceneState::Postview(self.outcome));
+        }
+
+        if self.outcome.is_success() {
+            let outcome = &self.outcome;
+            let profile = &mut self.game_state.profile;
+
+            profile.record(
+                self.game_state.current_level,
+                LevelRecord {
+                    rescued: outcome.rescued,
+                    percentage: outcome.percentage_rescued(),
+                    time_remaining_seconds: outcome.remaining_time_seconds,
+                    skills_used: outcome.skills_used,
+                },
+            );
+
+            self.game_state.current_level = (self.game_state.current_level + 1) % NUM_LEVELS;
+            profile.unlock(self.game_state.current_level);
+        }
+
+        self.game_state.screen = match self.status {
+            Status::DoneMenu => Screen::Menu,
+            _ => Screen::Preview,
+        };
+
+        (self.game_state, SceneState::None)
+    }
+
+    fn width(&self) -> u32 {
+        MENU_WIDTH
+    }
+
+    fn height(&self) -> u32 {
+        MENU_HEIGHT
+    }
+
+    fn aspect(&self) -> f32 {
+        MENU_ASPECT
+    }
+
+    fn fade_msec(&self) -> u64 {
+        MENU_FADE_MSEC
+    }
+
+    fn set_is_fullscreen(&mut self, _is_fullscreen: bool) {}
+
+    fn set_mouse_enabled(&mut self, _mouse_enabled: bool) {}
+
+    fn cursor_type(&mut self) -> CursorType {
+        CursorType::Crosshair
+    }
+
+    fn dispatch_event(&mut self, event: SceneEvent) {
+        match event {
+            SceneEvent::MouseDown(MouseButton::Left, _) => self.complete(Status::DoneContinue),
+            SceneEvent::MouseDown(MouseButton::Right, _) => self.complete(Status::DoneMenu),
+            SceneEvent::KeyDown {
+                keycode: Keycode::Return | Keycode::Space,
+                ..
+            } => self.complete(Status::DoneContinue),
+            SceneEvent::KeyDown {
+                keycode: Keycode::Escape,
+                ..
+            } => self.complete(Status::DoneMenu),
+            _ => (),
+        }
+    }
+
+    fn tick(&mut self, _canvas: &mut Canvas<Window>, _clock_msec: u64) -> Result<()> {
+        Ok(())
+    }
+
+    fn next_tick_at_msec(&self) -> u64 {
+        u64::MAX
+    }
+
+    fn is_complete(&self) -> bool {
+        self.status != Status::Running
+    }
+
+    fn texture(&mut self, id: usize) -> Result<&mut Texture<'texture_creator>> {
+        self.renderer.texture(id)
+    }
+
+    fn register_layers(&self, compositor: &mut dyn Compositor) {
+        self.renderer.register_layers(compositor);
+    }
+
+    fn draw(&mut self, canvas: &mut Canvas<Window>) -> Result<bool> {
+        self.renderer.draw(canvas)
+    }
+
+    fn will_redraw(&self) -> bool {
+        self.renderer.will_redraw()
+    }
+}
@@ -0,0 +1,4 @@
+mod renderer;
+mod scene_preview;
+
+pub use scene_preview::*;
@@ -0,0 +1,200 @@
+use anyhow::{Result, anyhow};
+use rustlings::{
+    game_data::{
+        GameData, Level, MINIMAP_VIEW_HEIGHT, MINIMAP_VIEW_WIDTH, decode_level_index,
+        render_minimap,
+    },
+    sdl::{texture_from_bitmap, with_texture_canvas},
+};
+use sdl3::{
+    pixels::{Color, PixelFormat},
+    rect::Rect as SdlRect,
+    render::{Canvas, Texture, TextureAccess, TextureCreator},
+    video::Window,
+};
+
+use crate::{
+    geometry::Rect,
+    scene::Compositor,
+    scenes::menu_screen::{MENU_HEIGHT, MENU_WIDTH, MenuScreen},
+};
+
+const TEXTURE_ID_SCREEN: usize = 0;
+
+const MINIMAP_SCALE: u32 = 4;
+const MINIMAP_Y: u32 = 16;
+const MINIMAP_BORDER_COLOR: Color = Color::RGBA(0, 0xb0, 0, 0xff);
+
+const TEXT_Y: u32 = 104;
+const LINE_HEIGHT: u32 = 24;
+const CONTINUE_Y: u32 = 310;
+
+pub struct Renderer<'texture_creator> {
+    texture_screen: Texture<'texture_creator>,
+    texture_minimap: Texture<'texture_creator>,
+    screen: MenuScreen<'texture_creator>,
+
+    lines: Vec<String>,
+    redraw: bool,
+}
+
+impl<'texture_creator> Renderer<'texture_creator> {
+    pub fn new<T>(
+        game_data: &GameData,
+        level_index: usize,
+        level: &Level,
+        texture_creator: &'texture_creator TextureCreator<T>,
+    ) -> Result<Self> {
+        let minimap = render_minimap(&game_data.compose_terrain(level)?);
+        let texture_minimap = texture_from_bitmap(
+            &minimap,
+            &game_data.resolve_palette(level)?,
+            texture_creator,
+            TextureAccess::Static,
+        )?;
+
+        let texture_screen = texture_creator.create_texture_target(
+            PixelFormat::RGBA8888,
+            MENU_WIDTH,
+            MENU_HEIGHT,
+        )?;
+
+        Ok(Renderer {
+            texture_screen,
+            texture_minimap,
+            screen: MenuScreen::new(game_data, texture_creator)?,
+            lines: briefing(level_index, level),
+            redraw: true,
+        })
+    }
+
+    pub fn will_redraw(&self) -> bool {
+        self.redraw
+    }
+
+    pub fn register_layers(&self, compositor: &mut dyn Compositor) {
+        compositor.add_layer(
+            TEXTURE_ID_SCREEN,
+            MENU_WIDTH,
+            MENU_HEIGHT,
+            Rect::new(0, 0, MENU_WIDTH, MENU_HEIGHT),
+        );
+    }
+
+    pub fn texture(&mut self, id: usize) -> Result<&mut Texture<'texture_creator>> {
+        match id {
+            TEXTURE_ID_SCREEN => Ok(&mut self.texture_screen),
+            _ => Err(anyhow!("invalid texture id {}", id)),
+        }
+    }
+
+    pub fn draw(&mut self, canvas: &mut Canvas<Window>) -> Result<bool> {
+        if !self.redraw {
+            return Ok(false);
+        }
+        self.redraw = false;
+
+        let Self {
+            texture_screen,
+            texture_minimap,
+            screen,
+            lines,
+            ..
+        } = self;
+
+        with_texture_canvas(canvas, texture_screen, |canvas| -> Result<()> {
+            screen.draw_background(canvas)?;
+
+            let width = MINIMAP_VIEW_WIDTH * MINIMAP_SCALE;
+            let height = MINIMAP_VIEW_HEIGHT * MINIMAP_SCALE;
+            let x = (MENU_WIDTH - width) / 2;
+
+            canvas.set_draw_color(MINIMAP_BORDER_COLOR);
+            canvas.fill_rect(SdlRect::new(
+                x as i32 - 2,
+                MINIMAP_Y as i32 - 2,
+                width + 4,
+                height + 4,
+            ))?;
+            canvas.set_draw_color(Color::RGBA(0, 0, 0, 0xff));
+            canvas.fill_rect(SdlRect::new(x as i32, MINIMAP_Y as i32, width, height))?;
+
+            canvas.copy(
+                texture_minimap,
+                None,
+                SdlRect::new(x as i32, MINIMAP_Y as i32, width, height),
+            )?;
+
+            for (i, line) in lines.iter().enumerate() {
+                screen.draw_text_centered(
+                    canvas,
+                    (TEXT_Y + i as u32 * LINE_HEIGHT) as i32,
+                    line,
+                )?;
+            }
+
+            screen.draw_text_centered(canvas, CONTINUE_Y as i32, "Press mouse button to continue")
+        })?;
+
+        Ok(true)
+    }
+}
+
+// The text of the DOS briefing screen
+fn briefing(level_index: usize, level: &Level) -> Vec<String> {
+    let parameters = &level.parameters;
+    let (rating, number) = decode_level_index(level_index);
+    let percentage = (parameters.required * 100)
+        .checked_div(parameters.released)
+        .unwrap_or(0);
+
+    vec![
+        format!("Level {}", number),
+        parameters.name.trim().to_string(),
+        format!("Number of Lemmings {}", parameters.released),
+        format!("{}% To Be Saved", percentage),
+        format!("Release Rate {}", parameters.release_rate),
+        format!("Time {} Minutes", parameters.time_limit),
+        format!("Rating {}", rating),
+    ]
+}
+
+#[cfg(test)]
+mod test {
+    use rustlings::game_data::{Level, LevelParameters};
+
+    use crate::scenes::scene_preview::renderer::briefing;
+
+    #[test]
+    fn briefing_text() {
+        let level = Level {
+            parameters: LevelParameters {
+                release_rate: 50,
+                released: 10,
+                required: 1,
+                time_limit: 5,
+                skills: [0, 0, 0, 0, 0, 0, 0, 10],
+                name: "       Just dig!                ".to_string(),
+            },
+            start_x: 0,
+            graphics_set: 0,
+            extended_graphics_set: 0,
+            terrain_tiles: Vec::new(),
+            objects: Vec::new(),
+            steel_areas: Vec::new(),
+        };
+
+        assert_eq!(
+            briefing(30, &level),
+            [
+                "Level 1",
+                "Just dig!",
+                "Number of Lemmings 10",
+                "10% To Be Saved",
+                "Release Rate 50",
+                "Time 5 Minutes",
+                "Rating Tricky",
+            ]
+        );
+    }
+}
@@ -0,0 +1,139 @@
+use std::rc::Rc;
+
+use anyhow::Result;
+use rustlings::game_data::GameData;
+use sdl3::{
+    keyboard::Keycode,
+    render::{Canvas, Texture, TextureCreator},
+    video::Window,
+};
+
+use crate::{
+    scene::{Compositor, CursorType, Scene, SceneEvent},
+    scenes::{
+        menu_screen::{MENU_ASPECT, MENU_FADE_MSEC, MENU_HEIGHT, MENU_WIDTH},
+        scene_preview::renderer::Renderer,
+    },
+    state::{GameState, SceneState, Screen},
+};
+
+#[derive(PartialEq)]
+enum Status {
+    Running,
+    DonePlay,
+    DoneMenu,
+}
+
+// Briefing shown before a level is started
+pub struct ScenePreview<'texture_creator> {
+    game_state: GameState,
+    status: Status,
+    renderer: Renderer<'texture_creator>,
+}
+
+impl<'texture_creator> ScenePreview<'texture_creator> {
+    pub fn new<T>(
+        game_data: Rc<GameData>,
+        game_state: GameState,
+        texture_creator: &'texture_creator TextureCreator<T>,
+    ) -> Result<Self> {
+        let level = game_data.resolve_level(game_state.current_level)?;
+        let renderer = Renderer::new(
+            &game_data,
+            game_state.current_level,
+            &level,
+            texture_creator,
+        )?;
+
+        Ok(ScenePreview {
+            game_state,
+            status: Status::Running,
+            renderer,
+        })
+    }
+
+    fn complete(&mut self, status: Status) {
+        if self.status == Status::Running {
+            self.status = status;
+        }
+    }
+}
+
+impl<'texture_creator> Scene<'texture_creator> for ScenePreview<'texture_creator> {
+    fn finish(mut self: Box<Self>) -> (GameState, SceneState) {
+        match self.status {
+            Status::Running => (),
+            Status::DonePlay => self.game_state.screen = Screen::Level,
+            Status::DoneMenu => self.game_state.screen = Screen::Menu,
+        }
+
+        (self.game_state, SceneState::None)
+    }
+
+    fn width(&self) -> u32 {
+        MENU_WIDTH
+    }
+
+    fn height(&self) -> u32 {
+        MENU_HEIGHT
+    }
+
+    fn aspect(&self) -> f32 {
+        MENU_ASPECT
+    }
+
+    fn fade_msec(&self) -> u64 {
+        MENU_FADE_MSEC
+    }
+
+    fn set_is_fullscreen(&mut self, _is_fullscreen: bool) {}
+
+    fn set_mouse_enabled(&mut self, _mouse_enabled: bool) {}
+
+    fn cursor_type(&mut self) -> CursorType {
+        CursorType::Crosshair
+    }
+
+    fn dispatch_event(&mut self, event: SceneEvent) {
+        match event {
+            SceneEvent::MouseDown(..) => self.complete(Status::DonePlay),
+            SceneEvent::KeyDown {
+                keycode: Keycode::Return | Keycode::Space,
+                ..
+            } => self.complete(Status::DonePlay),
+            SceneEvent::KeyDown {
+                keycode: Keycode::Escape,
+                ..
+            } => self.complete(Status::DoneMenu),
+            _ => (),
+        }
+    }
+
+    fn tick(&mut self, _canvas: &mut Canvas<Window>, _clock_msec: u64) -> Result<()> {
+        Ok(())
+    }
+
+    fn next_tick_at_msec(&self) -> u64 {
+        u64::MAX
+    }
+
+    fn is_complete(&self) -> bool {
+        self.status != Status::Running
+    }
+
+    fn texture(&mut self, id: usize) -> Result<&mut Texture<'texture_creator>> {
+        self.renderer.texture(id)
+    }
+
+    fn register_layers(&self, compositor: &mut dyn Compositor) {
+        self.renderer.register_layers(compositor);
+    }
+
+    fn draw(&mut self, canvas: &mut Canvas<Window>) -> Result<bool> {
+        self.renderer.draw(canvas)
+    }
+
+    fn will_redraw(&self) -> bool {
+        self.renderer.will_redraw()
+    }
+}
@@ -1,5 +1,6 @@
 mod event_collector;
 mod render_state;
 mod stage;
+mod transition;
 
 pub use stage::*;
@@ -20,6 +20,7 @@ use sdl3::{
 use crate::scene::{self, CursorType, MouseCoordinates, Scene, SceneEvent};
 use crate::stage::event_collector::{EventCollector, GameEvent, MouseButton, MouseOwnerChange};
 use crate::stage::render_state::{Layer, PrescalingMode, RenderState, StaticTexture};
+use crate::stage::transition::Transition;
 
 const MAX_TIMESLICE_MSEC: u64 = 100;
 const TIME_BUDGET_SAFETY_MARGIN_MSEC: u64 = 1;
@@ -40,6 +41,8 @@ pub struct Stage<'sdl> {
 
     rerender: bool,
     suspended: bool,
+    transition: Transition,
+    opacity: u8,
     ts_reference: Instant,
     time_old: u64,
 
@@ -70,6 +73,8 @@ impl<'sdl> Stage<'sdl> {
             game_data,
             rerender: false,
             suspended: false,
+            transition: Transition::Shown,
+            opacity: 255,
             ts_reference: Instant::now(),
             time_old: 0,
             last_time_per_frame_update: Instant::now(),
@@ -77,7 +82,8 @@ impl<'sdl> Stage<'sdl> {
         }
     }
 
-    pub fn run(&mut self, scene: &mut dyn Scene<'sdl>) -> Result<StopReason> {
+    // Scenes that are recreated after a render reset are shown right away instead of fading in
+    pub fn run(&mut self, scene: &mut dyn Scene<'sdl>, fade_in: bool) -> Result<StopReason> {
         let mut render_state = RenderState::new(scene, &self.game_data, self.texture_creator)?;
 
         scene.register_layers(&mut render_state);
@@ -93,6 +99,13 @@ impl<'sdl> Stage<'sdl> {
         self.ts_reference = Instant::now();
         self.time_old = 0;
 
+        self.transition = if fade_in {
+            Transition::FadingIn
+        } else {
+            Transition::Shown
+        };
+        self.opacity = self.transition.opacity(0, scene.fade_msec());
+
         self.transfer_mouse_state(&mut render_state)?;
 
         scene.set_is_fullscreen(self.is_fullscreen());
@@ -107,6 +120,12 @@ impl<'sdl> Stage<'sdl> {
         )));
 
         loop {
+            let opacity = self.transition.opacity(self.time_old, scene.fade_msec());
+            if opacity != self.opacity {
+                self.opacity = opacity;
+                self.rerender = true;
+            }
+
             if !self.suspended {
                 self.render_scene(scene, &mut render_state)?;
             }
@@ -135,12 +154,15 @@ impl<'sdl> Stage<'sdl> {
                 self.time_old = time;
                 scene.tick(self.canvas, time)?;
 
-                if scene.is_complete() {
+                if self
+                    .transition
+                    .advance(time, scene.fade_msec(), scene.is_complete())
+                {
                     return Ok(StopReason::NextScene);
                 }
             }
 
-            let aggregate_timeout = if scene.will_redraw() {
+            let aggregate_timeout = if scene.will_redraw() || self.transition.is_fading() {
                 0
             } else {
                 scene.next_tick_at_msec().saturating_sub(time)
@@ -363,9 +385,8 @@ impl<'sdl> Stage<'sdl> {
             self.canvas.copy(texture, None, Some(dest.into()))?;
         }
 
-        let scene_opacity = scene.opacity();
-        if scene_opacity != 255 {
-            render_state.overlay.set_alpha_mod(255 - scene_opacity);
+        if self.opacity != 255 {
+            render_state.overlay.set_alpha_mod(255 - self.opacity);
             self.canvas.copy(&render_state.overlay, None, None)?;
         }
 
@@ -0,0 +1,97 @@
+// Scenes fade in after creation and fade out once they are complete
+#[derive(Clone, Copy, PartialEq)]
+#[cfg_attr(test, derive(Debug))]
+pub enum Transition {
+    FadingIn,
+    Shown,
+    FadingOut { end_msec: u64 },
+}
+
+impl Transition {
+    pub fn opacity(&self, time_msec: u64, fade_msec: u64) -> u8 {
+        let faded_in = |elapsed_msec: u64| {
+            (elapsed_msec * 255)
+                .checked_div(fade_msec)
+                .unwrap_or(255)
+                .min(255) as u8
+        };
+
+        match *self {
+            Transition::FadingIn => faded_in(time_msec),
+            Transition::Shown => 255,
+            Transition::FadingOut { end_msec } if time_msec >= end_msec => 0,
+            Transition::FadingOut { end_msec } => faded_in(end_msec - time_msec),
+        }
+    }
+
+    pub fn is_fading(&self) -> bool {
+        *self != Transition::Shown
+    }
+
+    // Returns true once the fade out is over. A scene that completes while still fading in fades
+    // out starting from its current opacity.
+    pub fn advance(&mut self, time_msec: u64, fade_msec: u64, is_complete: bool) -> bool {
+        match *self {
+            Transition::FadingIn if is_complete => {
+                *self = Transition::FadingOut {
+                    end_msec: time_msec + time_msec.min(fade_msec),
+                };
+            }
+            Transition::FadingIn if time_msec >= fade_msec => *self = Transition::Shown,
+            Transition::Shown if is_complete => {
+                *self = Transition::FadingOut {
+                    end_msec: time_msec + fade_msec,
+                }
+            }
+            _ => (),
+        }
+
+        match *self {
+            Transition::FadingOut { end_msec } => time_msec >= end_msec,
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::stage::transition::Transition;
+
+    #[test]
+    fn fade_in_and_out() {
+        let mut transition = Transition::FadingIn;
+
+        assert_eq!(transition.opacity(0, 1000), 0);
+        assert_eq!(transition.opacity(500, 1000), 127);
+
+        assert!(!transition.advance(1000, 1000, false));
+        assert_eq!(transition, Transition::Shown);
+        assert_eq!(transition.opacity(1500, 1000), 255);
+
+        assert!(!transition.advance(2000, 1000, true));
+        assert_eq!(transition, Transition::FadingOut { end_msec: 3000 });
+        assert_eq!(transition.opacity(2250, 1000), 191);
+
+        assert!(!transition.advance(2999, 1000, true));
+        assert!(transition.advance(3000, 1000, true));
+        assert_eq!(transition.opacity(3000, 1000), 0);
+    }
+
+    #[test]
+    fn complete_while_fading_in() {
+        let mut transition = Transition::FadingIn;
+
+        let opacity = transition.opacity(250, 1000);
+        assert!(!transition.advance(250, 1000, true));
+        assert_eq!(transition.opacity(250, 1000), opacity);
+        assert!(transition.advance(500, 1000, true));
+    }
+
+    #[test]
+    fn no_fade() {
+        let mut transition = Transition::Shown;
+
+        assert!(transition.advance(0, 0, true));
+        assert_eq!(transition.opacity(0, 0), 0);
+    }
+}
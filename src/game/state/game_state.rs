@@ -1,11 +1,25 @@
+use std::rc::Rc;
+
+use rustlings::game_data::file::profile::Profile;
+
+use crate::keymap::Keymap;
+
 #[derive(Default, Clone)]
 pub enum Screen {
     #[default]
+    Menu,
+    Code,
+    Preview,
     Level,
+    Postview,
+    // Not a scene, the game shuts down
+    Exit,
 }
 
 #[derive(Default, Clone)]
 pub struct GameState {
     pub screen: Screen,
     pub current_level: usize,
+    pub profile: Profile,
+    pub keymap: Rc<Keymap>,
 }
@@ -2,8 +2,12 @@ mod game_state;
 mod lemming_animation;
 mod scene_state;
 mod scene_state_level;
+mod scene_state_postview;
+mod speed;
 
 pub use crate::state::game_state::*;
 pub use crate::state::lemming_animation::*;
 pub use crate::state::scene_state::*;
 pub use crate::state::scene_state_level::*;
+pub use crate::state::scene_state_postview::*;
+pub use crate::state::speed::*;
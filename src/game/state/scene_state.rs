@@ -1,8 +1,9 @@
-use crate::state::SceneStateLevel;
+use crate::state::{LevelOutcome, SceneStateLevel};
 
 #[derive(Default, Clone)]
 pub enum SceneState {
     #[default]
     None,
     Level(Box<SceneStateLevel>),
+    Postview(LevelOutcome),
 }
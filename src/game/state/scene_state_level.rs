@@ -3,7 +3,7 @@ use std::{collections::VecDeque, ops::Not};
 use bitfield_struct::bitfield;
 use rustlings::game_data::{Bitmap, NUM_SKILLS, Skill};
 
-use crate::state::LemmingAnimation;
+use crate::state::{LemmingAnimation, Speed};
 
 #[derive(Clone, Default)]
 pub struct ObjectState {
@@ -108,6 +108,7 @@ pub struct LemmingState {
 }
 
 #[derive(Clone, Copy, PartialEq, Default)]
+#[cfg_attr(test, derive(Debug))]
 pub enum LevelState {
     #[default]
     Intro,
@@ -139,7 +140,9 @@ pub struct SceneStateLevel {
     pub object_state: Vec<ObjectState>,
 
     pub clock_msec: u64,
-    pub simulation_clock_offset: i64,
+    // Level time at the game speed, stands still while paused
+    pub game_clock_usec: u64,
+    pub speed: Speed,
     pub paused: bool,
     pub tick: u64,
 
@@ -157,4 +160,6 @@ pub struct SceneStateLevel {
     pub lemmings: VecDeque<LemmingState>,
 
     pub spawn_countdown: u32,
+    // Release stops and the lemmings are armed as bombers one by one
+    pub nuked: bool,
 }
@@ -0,0 +1,30 @@
+// Result of a level as shown on the postview screen
+#[derive(Clone, Copy, Default)]
+#[cfg_attr(test, derive(Debug))]
+pub struct LevelOutcome {
+    pub released: u32,
+    pub rescued: u32,
+    pub required: u32,
+    pub remaining_time_seconds: u32,
+    pub skills_used: u32,
+}
+
+impl LevelOutcome {
+    pub fn percentage_rescued(&self) -> u32 {
+        (self.rescued * 100).checked_div(self.released).unwrap_or(0)
+    }
+
+    pub fn percentage_required(&self) -> u32 {
+        (self.required * 100)
+            .checked_div(self.released)
+            .unwrap_or(0)
+    }
+
+    pub fn time_up(&self) -> bool {
+        self.remaining_time_seconds == 0
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.rescued >= self.required
+    }
+}
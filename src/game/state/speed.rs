@@ -0,0 +1,78 @@
+use strum::VariantArray;
+
+// Game speeds, from slow motion to as fast as the machine allows
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Default, VariantArray)]
+pub enum Speed {
+    Quarter,
+    Half,
+    #[default]
+    Normal,
+    Double,
+    Quadruple,
+    Octuple,
+    Uncapped,
+}
+
+impl Speed {
+    // Game time passing per millisecond of real time in microseconds, None if uncapped
+    pub fn game_usec_per_msec(self) -> Option<u64> {
+        match self {
+            Speed::Quarter => Some(250),
+            Speed::Half => Some(500),
+            Speed::Normal => Some(1000),
+            Speed::Double => Some(2000),
+            Speed::Quadruple => Some(4000),
+            Speed::Octuple => Some(8000),
+            Speed::Uncapped => None,
+        }
+    }
+
+    // Real time in milliseconds for the given game time, uncapped counts as the fastest capped speed
+    pub fn real_msec(self, game_msec: u64) -> u64 {
+        let usec_per_msec = self
+            .game_usec_per_msec()
+            .or(Speed::Octuple.game_usec_per_msec())
+            .unwrap_or(1000);
+
+        game_msec * 1000 / usec_per_msec
+    }
+
+    pub fn faster(self) -> Self {
+        Self::VARIANTS
+            .get(self as usize + 1)
+            .copied()
+            .unwrap_or(self)
+    }
+
+    pub fn slower(self) -> Self {
+        (self as usize)
+            .checked_sub(1)
+            .map_or(self, |index| Self::VARIANTS[index])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::state::Speed;
+
+    #[test]
+    fn faster_and_slower() {
+        assert_eq!(Speed::Normal.faster(), Speed::Double);
+        assert_eq!(Speed::Octuple.faster(), Speed::Uncapped);
+        assert_eq!(Speed::Uncapped.faster(), Speed::Uncapped);
+
+        assert_eq!(Speed::Normal.slower(), Speed::Half);
+        assert_eq!(Speed::Quarter.slower(), Speed::Quarter);
+
+        assert!(Speed::Octuple > Speed::Quadruple);
+        assert_eq!(Speed::Uncapped.game_usec_per_msec(), None);
+    }
+
+    #[test]
+    fn real_msec() {
+        assert_eq!(Speed::Normal.real_msec(1000), 1000);
+        assert_eq!(Speed::Quarter.real_msec(1000), 4000);
+        assert_eq!(Speed::Quadruple.real_msec(1000), 250);
+        assert_eq!(Speed::Uncapped.real_msec(1000), 125);
+    }
+}
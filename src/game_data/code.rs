@@ -27,9 +27,51 @@ pub fn code_for_level(index: usize, percentage: Option<usize>, salt: Option<u8>)
     Ok(str::from_utf8(&code)?.to_owned())
 }
 
+pub fn level_for_code(code: &str) -> Option<(usize, usize, u8)> {
+    let code_upper = code.to_ascii_uppercase();
+    let mut code: [u8; 10] = code_upper.as_bytes().try_into().ok()?;
+
+    let index_low = code[7].checked_sub(SECRET[7]).filter(|x| *x <= 0xf)?;
+    let index_high = code[8].checked_sub(SECRET[8]).filter(|x| *x <= 0xf)?;
+    let index = (index_high << 4) | index_low;
+
+    let checksum =
+        (code[..9].iter().copied().fold(0u8, u8::wrapping_add) & 0xf).wrapping_add(SECRET[9]);
+    if checksum != code[9] {
+        return None;
+    }
+
+    code[..7].rotate_left((8 - (index & 0x07) as usize) % 7);
+
+    let mut bits = [0u8; 7];
+    for (i, bit) in bits.iter_mut().enumerate() {
+        *bit = code[i].checked_sub(SECRET[i])?;
+    }
+
+    let percentage = ((bits[0] & 0x04) >> 2)
+        | ((bits[1] & 0x01) << 1)
+        | ((bits[2] & 0x02) << 1)
+        | ((bits[3] & 0x02) << 2)
+        | ((bits[4] & 0x08) << 1)
+        | ((bits[5] & 0x04) << 3)
+        | ((bits[6] & 0x01) << 6);
+    let salt = ((bits[0] & 0x02) >> 1)
+        | ((bits[2] & 0x01) << 1)
+        | ((bits[4] & 0x01) << 2)
+        | ((bits[5] & 0x02) << 2);
+
+    // Reject codes with stray bits or inconsistent index bits in the rotated part
+    let decoded = (index as usize, percentage as usize, salt);
+    if code_for_level(decoded.0, Some(decoded.1), Some(decoded.2)).ok()? != code_upper {
+        return None;
+    }
+
+    Some(decoded)
+}
+
 #[cfg(test)]
 mod test {
-    use crate::code::code_for_level;
+    use crate::game_data::code::{code_for_level, level_for_code};
 
     #[test]
     fn code_0() {
@@ -90,4 +132,31 @@ mod test {
             "NFOFCKMFJN"
         );
     }
+
+    #[test]
+    fn decode_known_codes() {
+        assert_eq!(level_for_code("CAJJLDLBCS"), Some((0, 100, 0)));
+        assert_eq!(level_for_code("MCANNMDPEM"), Some((46, 100, 0)));
+        assert_eq!(level_for_code("nfofckmfjn"), Some((116, 42, 0xb)));
+    }
+
+    #[test]
+    fn decode_roundtrip() {
+        for index in 0..120 {
+            for (percentage, salt) in [(100, 0), (0, 0), (42, 0xb), (127, 0xf)] {
+                let code = code_for_level(index, Some(percentage), Some(salt)).unwrap();
+
+                assert_eq!(level_for_code(&code), Some((index, percentage, salt)));
+            }
+        }
+    }
+
+    #[test]
+    fn decode_invalid() {
+        assert_eq!(level_for_code(""), None);
+        assert_eq!(level_for_code("CAJJLDLBC"), None);
+        assert_eq!(level_for_code("CAJJLDLBCT"), None);
+        assert_eq!(level_for_code("DAJJLDLBCS"), None);
+        assert_eq!(level_for_code("ÄAJJLDLBC"), None);
+    }
 }
@@ -1,4 +1,4 @@
-use anyhow::{Result, anyhow};
+use crate::game_data::file::error::{GameDataError, GameDataErrorKind, Result};
 
 pub struct Bitstream<'a> {
     buffer: &'a [u8],
@@ -75,13 +75,15 @@ impl<'a> Bitstream<'a> {
                 self.byte_index += 1;
             }
 
-            let current_byte = *self
+            let current_byte = self
                 .buffer
-                .get(self.buffer.len() - self.byte_index - 1)
-                .ok_or(anyhow!("consume: out of bounds"))?;
+                .len()
+                .checked_sub(self.byte_index + 1)
+                .and_then(|index| self.buffer.get(index))
+                .ok_or(GameDataError::new(GameDataErrorKind::OutOfBounds))?;
 
             value <<= 1;
-            value |= (current_byte >> self.bit_index) & 0x01;
+            value |= current_byte.checked_shr(self.bit_index as u32).unwrap_or(0) & 0x01;
 
             self.bit_index += 1;
         }
@@ -95,19 +97,64 @@ impl<'a> Bitstream<'a> {
     }
 
     pub fn remaining(&self) -> usize {
-        (self.buffer.len() * 8 - 8 + self.bits_in_first_byte)
-            - self.bit_index
-            - if self.byte_index > 0 {
-                8 * self.byte_index - 8 + self.bits_in_first_byte
+        let total = match self.buffer.len() {
+            0 => 0,
+            len => 8 * (len - 1) + self.bits_in_first_byte,
+        };
+
+        let consumed = if self.byte_index > 0 {
+            8 * self.byte_index - 8 + self.bits_in_first_byte
+        } else {
+            0
+        } + self.bit_index;
+
+        total.saturating_sub(consumed)
+    }
+}
+
+// Collects bits in the order they are consumed by Bitstream
+#[derive(Default)]
+pub struct BitstreamWriter {
+    bits: Vec<u8>,
+}
+
+impl BitstreamWriter {
+    pub fn push(&mut self, value: usize, count: usize) {
+        for i in (0..count).rev() {
+            self.bits.push(((value >> i) & 0x01) as u8);
+        }
+    }
+
+    // Returns the buffer and the number of bits in the first byte
+    pub fn finish(self) -> (Vec<u8>, usize) {
+        let bits_in_first_byte = match self.bits.len() % 8 {
+            0 => 8,
+            n => n,
+        };
+
+        let mut buffer = vec![0u8; (self.bits.len() + 8 - bits_in_first_byte) / 8];
+        let len = buffer.len();
+
+        for (i, bit) in self.bits.iter().enumerate() {
+            let (byte_index, bit_index) = if i < bits_in_first_byte {
+                (0, i)
             } else {
-                0
-            }
+                (
+                    (i - bits_in_first_byte) / 8 + 1,
+                    (i - bits_in_first_byte) % 8,
+                )
+            };
+
+            buffer[len - 1 - byte_index] |= bit << bit_index;
+        }
+
+        (buffer, bits_in_first_byte)
     }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::game_data::file::encoding::bitstream::Bitstream;
+    use crate::game_data::file::encoding::bitstream::{Bitstream, BitstreamWriter};
 
     #[test]
     fn bitstream_example_3() {
@@ -164,4 +211,21 @@ mod test {
         assert_eq!(bitstream.remaining(), 8);
         assert_eq!(bitstream.consume_or_die(8), 0b10010011);
     }
+
+    #[test]
+    fn bitstream_writer_roundtrip() {
+        let mut writer = BitstreamWriter::default();
+        writer.push(0b01, 2);
+        writer.push(0b10010011, 8);
+        writer.push(0b101, 3);
+
+        let (buffer, bits_in_first_byte) = writer.finish();
+        let mut bitstream = Bitstream::create(&buffer, bits_in_first_byte);
+
+        assert_eq!(bitstream.remaining(), 13);
+        assert_eq!(bitstream.consume_or_die(2), 0b01);
+        assert_eq!(bitstream.consume_or_die(8), 0b10010011);
+        assert_eq!(bitstream.consume_or_die(3), 0b101);
+        assert_eq!(bitstream.remaining(), 0);
+    }
 }
@@ -1,8 +1,9 @@
 use std::{cmp::Ordering, fmt};
 
-use anyhow::{Result, anyhow, bail};
-
 use crate::game_data::file::encoding::bitstream;
+use crate::game_data::file::error::{ErrorLocation, GameDataError, GameDataErrorKind, Result};
+
+pub const HEADER_SIZE: usize = 10;
 
 pub struct Header {
     pub num_bits_in_first_byte: usize,
@@ -26,36 +27,14 @@ pub fn parse(data: &[u8]) -> Result<Content> {
     let mut sections: Vec<Section> = Vec::new();
 
     loop {
-        let (header, o) = read_header(data, offset)?;
-        let checksum = calculate_checksum(&header, data, o)?;
-
-        if checksum != header.checksum {
-            bail!("checksum mismatch");
-        }
+        let section = parse_section(data, offset).in_section(sections.len())?;
 
-        let mut section_data: Vec<u8> = Vec::with_capacity(header.decompressed_data_size);
-        decompress_section(
-            &mut bitstream::Bitstream::create(
-                data.get(o..o + header.compressed_data_size - 10)
-                    .ok_or(anyhow!("out of bounds decompressing section"))?,
-                header.num_bits_in_first_byte,
-            ),
-            &mut section_data,
-        )?;
-
-        if section_data.len() != header.decompressed_data_size {
-            bail!("decompressed section does not match header");
-        }
-
-        offset = o + header.compressed_data_size - 10;
-        sections.push(Section {
-            header,
-            data: section_data,
-        });
+        offset += section.header.compressed_data_size;
+        sections.push(section);
 
         match offset.cmp(&data.len()) {
             Ordering::Equal => break,
-            Ordering::Greater => bail!("bad file"),
+            Ordering::Greater => return Err(GameDataError::out_of_bounds(offset)),
             Ordering::Less => continue,
         };
     }
@@ -63,6 +42,200 @@ pub fn parse(data: &[u8]) -> Result<Content> {
     Ok(Content { sections })
 }
 
+fn parse_section(data: &[u8], offset: usize) -> Result<Section> {
+    let (header, o) = read_header(data, offset)?;
+    let checksum = calculate_checksum(&header, data, o)?;
+
+    if checksum != header.checksum {
+        return Err(GameDataError::at(
+            GameDataErrorKind::ChecksumMismatch {
+                expected: header.checksum,
+                actual: checksum,
+            },
+            offset,
+        ));
+    }
+
+    let mut section_data: Vec<u8> = Vec::with_capacity(header.decompressed_data_size);
+    decompress_section(
+        &mut bitstream::Bitstream::create(
+            data.get(o..o + header.compressed_data_size - HEADER_SIZE)
+                .ok_or(GameDataError::out_of_bounds(o))?,
+            header.num_bits_in_first_byte,
+        ),
+        &mut section_data,
+    )
+    .map_err(|mut err| {
+        err.offset.get_or_insert(offset);
+        err
+    })?;
+
+    if section_data.len() != header.decompressed_data_size {
+        return Err(GameDataError::at(
+            GameDataErrorKind::InvalidSize {
+                expected: header.decompressed_data_size,
+                actual: section_data.len(),
+            },
+            offset,
+        ));
+    }
+
+    Ok(Section {
+        header,
+        data: section_data,
+    })
+}
+
+pub fn encode(sections: &[Vec<u8>]) -> Result<Vec<u8>> {
+    let mut data: Vec<u8> = Vec::new();
+
+    for (i, section) in sections.iter().enumerate() {
+        data.append(&mut encode_section(section).in_section(i)?);
+    }
+
+    Ok(data)
+}
+
+// Store sections as literals only. Much faster than encode, for test fixtures.
+#[cfg(test)]
+pub(crate) fn encode_uncompressed(sections: &[Vec<u8>]) -> Result<Vec<u8>> {
+    let mut data: Vec<u8> = Vec::new();
+
+    for (i, section) in sections.iter().enumerate() {
+        let reversed: Vec<u8> = section.iter().rev().copied().collect();
+        let mut writer = bitstream::BitstreamWriter::default();
+        write_literals(&mut writer, &reversed);
+
+        data.append(&mut finish_section(section, writer).in_section(i)?);
+    }
+
+    Ok(data)
+}
+
+fn encode_section(data: &[u8]) -> Result<Vec<u8>> {
+    finish_section(data, compress_section(data))
+}
+
+fn finish_section(data: &[u8], writer: bitstream::BitstreamWriter) -> Result<Vec<u8>> {
+    let (mut payload, num_bits_in_first_byte) = writer.finish();
+    let compressed_data_size = payload.len() + HEADER_SIZE;
+
+    if data.len() > 0xffff || compressed_data_size > 0xffff {
+        return Err(GameDataError::malformed("section too large"));
+    }
+
+    let mut section = vec![
+        num_bits_in_first_byte as u8,
+        payload.iter().fold(0u8, |acc, x| acc ^ x),
+        0,
+        0,
+        (data.len() >> 8) as u8,
+        data.len() as u8,
+        0,
+        0,
+        (compressed_data_size >> 8) as u8,
+        compressed_data_size as u8,
+    ];
+    section.append(&mut payload);
+
+    Ok(section)
+}
+
+// Greedy LZ compression. The decompressor builds the section back to front, so we compress
+// the reversed data.
+fn compress_section(data: &[u8]) -> bitstream::BitstreamWriter {
+    let reversed: Vec<u8> = data.iter().rev().copied().collect();
+    let mut writer = bitstream::BitstreamWriter::default();
+
+    let mut position = 0;
+    let mut literals_start = 0;
+
+    while position < reversed.len() {
+        match find_reference(&reversed, position) {
+            Some((offset, count)) => {
+                write_literals(&mut writer, &reversed[literals_start..position]);
+                write_reference(&mut writer, offset, count);
+
+                position += count;
+                literals_start = position;
+            }
+            None => position += 1,
+        }
+    }
+
+    write_literals(&mut writer, &reversed[literals_start..]);
+
+    writer
+}
+
+fn reference_cost(offset: usize, length: usize) -> Option<(usize, usize)> {
+    [
+        (length >= 2 && offset < 0x100).then_some((2, 10)),
+        (length >= 3 && offset < 0x200).then_some((3, 12)),
+        (length >= 4 && offset < 0x400).then_some((4, 13)),
+        (length >= 3 && offset < 0x1000).then_some((length.min(0x100), 23)),
+    ]
+    .into_iter()
+    .flatten()
+    .max_by_key(|(count, bits)| 8 * count - bits)
+}
+
+fn find_reference(data: &[u8], position: usize) -> Option<(usize, usize)> {
+    let mut best: Option<(usize, usize, usize)> = None;
+
+    for offset in 0..position.min(0x1000) {
+        let length = (position..data.len().min(position + 0x100))
+            .take_while(|i| data[*i] == data[*i - offset - 1])
+            .count();
+
+        if let Some((count, bits)) = reference_cost(offset, length)
+            && best.is_none_or(|(_, _, savings)| 8 * count - bits > savings)
+        {
+            best = Some((offset, count, 8 * count - bits));
+        }
+    }
+
+    best.map(|(offset, count, _)| (offset, count))
+}
+
+fn write_reference(writer: &mut bitstream::BitstreamWriter, offset: usize, count: usize) {
+    match (count, offset) {
+        (2, 0..0x100) => {
+            writer.push(0b01, 2);
+            writer.push(offset, 8);
+        }
+        (3, 0..0x200) => {
+            writer.push(0b100, 3);
+            writer.push(offset, 9);
+        }
+        (4, 0..0x400) => {
+            writer.push(0b101, 3);
+            writer.push(offset, 10);
+        }
+        _ => {
+            writer.push(0b110, 3);
+            writer.push(count - 1, 8);
+            writer.push(offset, 12);
+        }
+    }
+}
+
+fn write_literals(writer: &mut bitstream::BitstreamWriter, literals: &[u8]) {
+    for chunk in literals.chunks(0x108) {
+        if chunk.len() >= 9 {
+            writer.push(0b111, 3);
+            writer.push(chunk.len() - 9, 8);
+        } else {
+            writer.push(0b00, 2);
+            writer.push(chunk.len() - 1, 3);
+        }
+
+        for byte in chunk {
+            writer.push(*byte as usize, 8);
+        }
+    }
+}
+
 impl fmt::Display for Header {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -79,7 +252,7 @@ compressed size:          {}"#,
     }
 }
 
-fn read_header(buffer: &[u8], offset: usize) -> Result<(Header, usize)> {
+pub(crate) fn read_header(buffer: &[u8], offset: usize) -> Result<(Header, usize)> {
     let (num_bits_in_first_byte, offset) = read_byte(buffer, offset)?;
     let (checksum, offset) = read_byte(buffer, offset)?;
 
@@ -89,8 +262,11 @@ fn read_header(buffer: &[u8], offset: usize) -> Result<(Header, usize)> {
     let offset = offset + 2;
     let (compressed_data_size, offset) = read_word(buffer, offset)?;
 
-    if compressed_data_size < 10 {
-        bail!("compressed data size {} too small", compressed_data_size);
+    if (compressed_data_size as usize) < HEADER_SIZE || num_bits_in_first_byte > 8 {
+        return Err(GameDataError::at(
+            GameDataErrorKind::InvalidHeader,
+            offset - HEADER_SIZE,
+        ));
     }
 
     Ok((
@@ -104,10 +280,10 @@ fn read_header(buffer: &[u8], offset: usize) -> Result<(Header, usize)> {
     ))
 }
 
-fn calculate_checksum(header: &Header, buffer: &[u8], offset: usize) -> Result<u8> {
+pub(crate) fn calculate_checksum(header: &Header, buffer: &[u8], offset: usize) -> Result<u8> {
     buffer
-        .get(offset..header.compressed_data_size - 10 + offset)
-        .ok_or(anyhow!("not enough data in buffer"))
+        .get(offset..header.compressed_data_size - HEADER_SIZE + offset)
+        .ok_or(GameDataError::out_of_bounds(offset))
         .map(|slice| slice.iter().fold(0u8, |acc, x| acc ^ x))
 }
 
@@ -125,59 +301,35 @@ fn decompress_section(bitstream: &mut bitstream::Bitstream, target: &mut Vec<u8>
 
         match opcode {
             7 => {
-                let count = bitstream.consume(8)? + 9;
+                let count = bitstream.consume(8)? as usize + 9;
 
                 for _ in 0..count {
                     target.push(bitstream.consume(8)?)
                 }
             }
             1 => {
-                let offset = bitstream.consume(8)?;
-
-                for _ in 0..2 {
-                    target.push(
-                        *target
-                            .get(target.len() - 1 - offset as usize)
-                            .ok_or(anyhow!("opcode 1: reference out of bounds"))?,
-                    )
-                }
+                let offset = bitstream.consume(8)? as usize;
+
+                copy_reference(target, offset, 2)?;
             }
             4 => {
                 let offset: usize =
                     ((bitstream.consume(8)? as usize) << 1) | (bitstream.consume(1)? as usize);
 
-                for _ in 0..3 {
-                    target.push(
-                        *target
-                            .get(target.len() - 1 - offset)
-                            .ok_or(anyhow!("opcode 4: reference out of bounds"))?,
-                    )
-                }
+                copy_reference(target, offset, 3)?;
             }
             5 => {
                 let offset: usize =
                     ((bitstream.consume(8)? as usize) << 2) | (bitstream.consume(2)? as usize);
 
-                for _ in 0..4 {
-                    target.push(
-                        *target
-                            .get(target.len() - 1 - offset)
-                            .ok_or(anyhow!("opcode 5: reference out of bounds"))?,
-                    )
-                }
+                copy_reference(target, offset, 4)?;
             }
             6 => {
                 let block_size = bitstream.consume(8)? as usize + 1;
                 let offset: usize =
                     ((bitstream.consume(8)? as usize) << 4) | (bitstream.consume(4)? as usize);
 
-                for _ in 0..block_size {
-                    target.push(
-                        *target
-                            .get(target.len() - 1 - offset)
-                            .ok_or(anyhow!("opcode 6: reference out of bounds"))?,
-                    )
-                }
+                copy_reference(target, offset, block_size)?;
             }
             0 => {
                 let count = bitstream.consume(3)? + 1;
@@ -187,7 +339,7 @@ fn decompress_section(bitstream: &mut bitstream::Bitstream, target: &mut Vec<u8>
                 }
             }
 
-            _ => bail!("bad chunk type {}", opcode),
+            _ => return Err(GameDataError::new(GameDataErrorKind::InvalidOpcode(opcode))),
         }
     }
 
@@ -195,6 +347,35 @@ fn decompress_section(bitstream: &mut bitstream::Bitstream, target: &mut Vec<u8>
     Ok(())
 }
 
+fn copy_reference(target: &mut Vec<u8>, offset: usize, count: usize) -> Result<()> {
+    for _ in 0..count {
+        let index = target
+            .len()
+            .checked_sub(offset + 1)
+            .ok_or(GameDataError::new(GameDataErrorKind::InvalidReference))?;
+
+        target.push(target[index]);
+    }
+
+    Ok(())
+}
+
+fn read_byte(buffer: &[u8], offset: usize) -> Result<(u8, usize)> {
+    Ok((
+        *buffer
+            .get(offset)
+            .ok_or(GameDataError::out_of_bounds(offset))?,
+        offset + 1,
+    ))
+}
+
+fn read_word(buffer: &[u8], offset: usize) -> Result<(u16, usize)> {
+    Ok((
+        (read_byte(buffer, offset)?.0 as u16) << 8 | (read_byte(buffer, offset + 1)?.0 as u16),
+        offset + 2,
+    ))
+}
+
 #[cfg(test)]
 mod test_decompress_section {
     use crate::game_data::file::encoding::bitstream::Bitstream;
@@ -278,18 +459,57 @@ mod test_decompress_section {
     }
 }
 
-fn read_byte(buffer: &[u8], offset: usize) -> Result<(u8, usize)> {
-    Ok((
-        *buffer
-            .get(offset)
-            .ok_or(anyhow!("offset {} out of bounds", offset))?,
-        offset + 1,
-    ))
-}
+#[cfg(test)]
+mod test_encode {
+    use crate::game_data::file::encoding::datfile::{encode, parse};
 
-fn read_word(buffer: &[u8], offset: usize) -> Result<(u16, usize)> {
-    Ok((
-        (read_byte(buffer, offset)?.0 as u16) << 8 | (read_byte(buffer, offset + 1)?.0 as u16),
-        offset + 2,
-    ))
+    fn roundtrip(sections: Vec<Vec<u8>>) {
+        let encoded = encode(&sections).expect("encode failed");
+        let decoded = parse(&encoded).expect("parse failed");
+
+        assert_eq!(
+            decoded
+                .sections
+                .into_iter()
+                .map(|section| section.data)
+                .collect::<Vec<_>>(),
+            sections
+        );
+    }
+
+    #[test]
+    fn test_roundtrip_empty() {
+        roundtrip(vec![vec![]]);
+    }
+
+    #[test]
+    fn test_roundtrip_literals() {
+        roundtrip(vec![
+            (0..=255).collect(),
+            vec![0x01],
+            (0..600).map(|x| (x * 7) as u8).collect(),
+        ]);
+    }
+
+    #[test]
+    fn test_roundtrip_references() {
+        let mut data: Vec<u8> = Vec::new();
+
+        for i in 0..3000 {
+            data.push(match i % 300 {
+                0..100 => 0,
+                100..110 => 1,
+                n => (n * 13 % 17) as u8,
+            });
+        }
+
+        roundtrip(vec![data.clone(), data[..2048].to_vec()]);
+    }
+
+    #[test]
+    fn test_compresses() {
+        let data = vec![0x42u8; 2048];
+
+        assert!(encode(&[data]).expect("encode failed").len() < 100);
+    }
 }
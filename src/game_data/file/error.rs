@@ -0,0 +1,141 @@
+use std::{error, fmt, io};
+
+#[derive(Debug)]
+pub enum GameDataErrorKind {
+    Io(io::Error),
+    OutOfBounds,
+    InvalidHeader,
+    ChecksumMismatch { expected: u8, actual: u8 },
+    InvalidOpcode(u8),
+    InvalidReference,
+    InvalidSize { expected: usize, actual: usize },
+    InvalidSectionCount { expected: usize, actual: usize },
+    InvalidBpp(usize),
+    InvalidDimensions { width: u32, height: u32 },
+    Malformed(&'static str),
+    InvalidText { line: usize, reason: String },
+}
+
+// Errors in compressed data point to the header of the offending section. Otherwise,
+// offsets are relative to the decompressed section data, or to the start of the file
+// for uncompressed files.
+#[derive(Debug)]
+pub struct GameDataError {
+    pub file: Option<String>,
+    pub section: Option<usize>,
+    pub offset: Option<usize>,
+    pub kind: GameDataErrorKind,
+}
+
+pub type Result<T> = std::result::Result<T, GameDataError>;
+
+impl GameDataError {
+    pub fn new(kind: GameDataErrorKind) -> Self {
+        Self {
+            file: None,
+            section: None,
+            offset: None,
+            kind,
+        }
+    }
+
+    pub fn at(kind: GameDataErrorKind, offset: usize) -> Self {
+        Self {
+            offset: Some(offset),
+            ..Self::new(kind)
+        }
+    }
+
+    pub fn out_of_bounds(offset: usize) -> Self {
+        Self::at(GameDataErrorKind::OutOfBounds, offset)
+    }
+
+    pub fn malformed(description: &'static str) -> Self {
+        Self::new(GameDataErrorKind::Malformed(description))
+    }
+}
+
+pub(crate) trait ErrorLocation {
+    fn in_file(self, file: &str) -> Self;
+    fn in_section(self, section: usize) -> Self;
+}
+
+impl<T> ErrorLocation for Result<T> {
+    fn in_file(self, file: &str) -> Self {
+        self.map_err(|mut err| {
+            err.file.get_or_insert_with(|| file.to_string());
+            err
+        })
+    }
+
+    fn in_section(self, section: usize) -> Self {
+        self.map_err(|mut err| {
+            err.section.get_or_insert(section);
+            err
+        })
+    }
+}
+
+impl From<io::Error> for GameDataError {
+    fn from(err: io::Error) -> Self {
+        Self::new(GameDataErrorKind::Io(err))
+    }
+}
+
+impl fmt::Display for GameDataErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "{}", err),
+            Self::OutOfBounds => write!(f, "out of bounds"),
+            Self::InvalidHeader => write!(f, "invalid section header"),
+            Self::ChecksumMismatch { expected, actual } => write!(
+                f,
+                "checksum mismatch: expected {:#04x}, got {:#04x}",
+                expected, actual
+            ),
+            Self::InvalidOpcode(opcode) => write!(f, "invalid opcode {}", opcode),
+            Self::InvalidReference => write!(f, "reference out of bounds"),
+            Self::InvalidSize { expected, actual } => {
+                write!(f, "invalid size: expected {}, got {}", expected, actual)
+            }
+            Self::InvalidSectionCount { expected, actual } => write!(
+                f,
+                "invalid section count: expected {}, got {}",
+                expected, actual
+            ),
+            Self::InvalidBpp(bpp) => write!(f, "invalid bpp {}", bpp),
+            Self::InvalidDimensions { width, height } => {
+                write!(f, "invalid dimensions {}x{}", width, height)
+            }
+            Self::Malformed(description) => write!(f, "malformed data: {}", description),
+            Self::InvalidText { line, reason } => write!(f, "line {}: {}", line, reason),
+        }
+    }
+}
+
+impl fmt::Display for GameDataError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{}: ", file)?;
+        }
+
+        if let Some(section) = self.section {
+            write!(f, "section {}: ", section)?;
+        }
+
+        if let Some(offset) = self.offset {
+            write!(f, "offset {:#06x}: ", offset)?;
+        }
+
+        write!(f, "{}", self.kind)
+    }
+}
+
+impl error::Error for GameDataError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match &self.kind {
+            GameDataErrorKind::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
@@ -1,16 +1,21 @@
-use std::{fmt, fs, path::Path};
+use std::fmt;
 
-use anyhow::{Result, bail};
+use serde::Serialize;
 
+use crate::game_data::file::error::{ErrorLocation, GameDataError, GameDataErrorKind, Result};
 use crate::game_data::file::palette::{
     LOWER_PALETTE_FIXED, PALETTE_SIZE, PaletteEntry, read_palette_entry,
 };
 use crate::game_data::file::read::{read_byte, read_word_le};
+use crate::game_data::file::vfs::Vfs;
 
 pub const OBJECTS_PER_TILESET: usize = 16;
 pub const TILES_PER_TILESET: usize = 64;
 
-#[derive(Clone, Default, Copy, PartialEq)]
+const EGA_PALETTES_SIZE: usize = 24;
+
+#[derive(Clone, Default, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum InteractionType {
     #[default]
     None,
@@ -24,7 +29,7 @@ pub enum InteractionType {
 }
 
 impl InteractionType {
-    fn new(trigger_effect: u32, animation_flags: u32) -> Self {
+    pub(crate) fn new(trigger_effect: u32, animation_flags: u32) -> Self {
         match trigger_effect {
             0 => {
                 if animation_flags == 0x03 {
@@ -103,18 +108,25 @@ pub struct Content {
     pub palettes: Palettes,
 }
 
-pub fn read_ground(path: &Path, index: usize) -> Result<Content> {
+pub fn read_ground(vfs: &dyn Vfs, index: usize) -> Result<Content> {
     let filename = format!("ground{}o.dat", index);
     println!("reading {}", &filename);
 
-    let data = fs::read(path.join(&filename).as_os_str())?;
+    let data = vfs
+        .read(&filename)
+        .map_err(GameDataError::from)
+        .in_file(&filename)?;
+
+    parse_ground(&data).in_file(&filename)
+}
 
+pub fn parse_ground(data: &[u8]) -> Result<Content> {
     let mut offset = 0;
     let mut object_info: [ObjectInfo; OBJECTS_PER_TILESET] =
         [(); OBJECTS_PER_TILESET].map(|_| ObjectInfo::default());
 
     for object_info in object_info.iter_mut() {
-        let (value, new_offset) = read_object_info(&data, offset)?;
+        let (value, new_offset) = read_object_info(data, offset)?;
         offset = new_offset;
 
         *object_info = value;
@@ -124,21 +136,19 @@ pub fn read_ground(path: &Path, index: usize) -> Result<Content> {
         [(); TILES_PER_TILESET].map(|_| TerrainInfo::default());
 
     for terrain_info in terrain_info.iter_mut() {
-        let (value, new_offset) = read_terrain_info(&data, offset)?;
+        let (value, new_offset) = read_terrain_info(data, offset)?;
         offset = new_offset;
 
         *terrain_info = value;
     }
 
-    let (palettes, offset) = read_palettes(&data, offset)?;
+    let (palettes, offset) = read_palettes(data, offset)?;
 
     if offset != data.len() {
-        bail!(
-            "extra data left in {}: read {} bytes, but got {} bytes",
-            &filename,
-            offset,
-            data.len()
-        );
+        return Err(GameDataError::new(GameDataErrorKind::InvalidSize {
+            expected: offset,
+            actual: data.len(),
+        }));
     }
 
     Ok(Content {
@@ -228,7 +238,7 @@ fn read_palette(buffer: &[u8], offset: usize) -> Result<([PaletteEntry; 16], usi
 }
 
 fn read_palettes(buffer: &[u8], offset: usize) -> Result<(Palettes, usize)> {
-    let offset = offset + 24;
+    let offset = offset + EGA_PALETTES_SIZE;
     let (custom, offset) = read_palette(buffer, offset)?;
     let (standard, offset) = read_palette(buffer, offset)?;
     let (preview, offset) = read_palette(buffer, offset)?;
@@ -243,6 +253,140 @@ fn read_palettes(buffer: &[u8], offset: usize) -> Result<(Palettes, usize)> {
     ))
 }
 
+// The EGA palettes and the fields we do not parse are written as zero. Errors point
+// to the offset of the offending record.
+pub fn encode_ground(content: &Content) -> Result<Vec<u8>> {
+    let mut data: Vec<u8> = Vec::new();
+
+    for info in &content.object_info {
+        let offset = data.len();
+        encode_object_info(&mut data, info).map_err(|err| GameDataError::at(err.kind, offset))?;
+    }
+
+    for info in &content.terrain_info {
+        let offset = data.len();
+        encode_terrain_info(&mut data, info).map_err(|err| GameDataError::at(err.kind, offset))?;
+    }
+
+    data.extend_from_slice(&[0; EGA_PALETTES_SIZE]);
+
+    for palette in [
+        &content.palettes.custom,
+        &content.palettes.standard,
+        &content.palettes.preview,
+    ] {
+        for (r, g, b) in &palette[8..16] {
+            data.extend_from_slice(&[r >> 2, g >> 2, b >> 2]);
+        }
+    }
+
+    Ok(data)
+}
+
+fn encode_object_info(data: &mut Vec<u8>, info: &ObjectInfo) -> Result<()> {
+    write_word_le(
+        data,
+        encodable(info.animation_flags, "animation flags out of range")?,
+    );
+    data.push(encodable(
+        info.animation_start,
+        "animation start out of range",
+    )?);
+    data.push(encodable(info.animation_end, "animation end out of range")?);
+    data.push(encodable(info.width, "object width out of range")?);
+    data.push(encodable(info.height, "object height out of range")?);
+    write_word_le(
+        data,
+        encodable(info.animation_frame_size, "frame size out of range")?,
+    );
+    write_word_le(
+        data,
+        encodable(info.mask_offset, "mask offset out of range")?,
+    );
+    data.extend_from_slice(&[0; 4]);
+    write_word_le(
+        data,
+        trigger(info.trigger_left as i64, "trigger left out of range")?,
+    );
+    write_word_le(
+        data,
+        trigger(info.trigger_top as i64 + 4, "trigger top out of range")?,
+    );
+    data.push(trigger(
+        info.trigger_width as i64,
+        "trigger width out of range",
+    )?);
+    data.push(trigger(
+        info.trigger_height as i64,
+        "trigger height out of range",
+    )?);
+    data.push(encodable(
+        info.trigger_effect,
+        "trigger effect out of range",
+    )?);
+    write_word_le(
+        data,
+        encodable(info.frames_offset, "frames offset out of range")?,
+    );
+    write_word_le(
+        data,
+        encodable(
+            info.preview_frame_offset,
+            "preview frame offset out of range",
+        )?,
+    );
+    data.extend_from_slice(&[0; 2]);
+    data.push(encodable(
+        info.trap_sound_effect,
+        "trap sound effect out of range",
+    )?);
+
+    Ok(())
+}
+
+fn encode_terrain_info(data: &mut Vec<u8>, info: &TerrainInfo) -> Result<()> {
+    data.push(encodable(info.width, "terrain width out of range")?);
+    data.push(encodable(info.height, "terrain height out of range")?);
+    write_word_le(
+        data,
+        encodable(info.image_offset, "image offset out of range")?,
+    );
+    write_word_le(
+        data,
+        encodable(info.mask_offset, "mask offset out of range")?,
+    );
+    data.extend_from_slice(&[0; 2]);
+
+    Ok(())
+}
+
+fn encodable<V, T: TryFrom<V>>(value: V, description: &'static str) -> Result<T> {
+    T::try_from(value).map_err(|_| GameDataError::malformed(description))
+}
+
+// Trigger areas are stored in units of 4 pixels
+fn trigger<T: TryFrom<i64>>(value: i64, description: &'static str) -> Result<T> {
+    if value % 4 != 0 {
+        return Err(GameDataError::malformed(description));
+    }
+
+    encodable(value / 4, description)
+}
+
+fn write_word_le(data: &mut Vec<u8>, value: u16) {
+    data.extend_from_slice(&value.to_le_bytes());
+}
+
+impl ObjectInfo {
+    // Index of the frame shown in the level preview
+    pub fn preview_frame(&self) -> usize {
+        self.preview_frame_offset
+            .checked_sub(self.frames_offset)
+            .map(|offset| offset / self.animation_frame_size.max(1))
+            .unwrap_or(0)
+    }
+}
+
 impl fmt::Display for ObjectInfo {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -1,36 +1,92 @@
 use std::fmt;
-use std::{fs, path::Path};
 
-use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
 
 use crate::game_data::file::encoding::datfile;
+use crate::game_data::file::error::{ErrorLocation, GameDataError, GameDataErrorKind, Result};
 use crate::game_data::file::read::{read_byte, read_word_be};
+use crate::game_data::file::text::{integer, multiple, word};
+use crate::game_data::file::vfs::Vfs;
 use crate::game_data::skill::{NUM_SKILLS, SKILLS};
 
-const ODDTABLE_ENTRIES: usize = 80;
-const ODDTABLE_ENTRY_SIZE: usize = 0x38;
-const ODDTABLE_FILENAME: &str = "oddtable.dat";
-
-#[derive(Clone)]
+pub const LEVEL_SIZE: usize = 2048;
+pub const ODDTABLE_ENTRIES: usize = 80;
+pub const ODDTABLE_ENTRY_SIZE: usize = 0x38;
+pub const ODDTABLE_FILENAME: &str = "oddtable.dat";
+
+pub const MAX_TERRAIN_TILES: usize = 400;
+pub const MAX_OBJECTS: usize = 32;
+pub const MAX_STEEL_AREAS: usize = 32;
+const NAME_LENGTH: usize = 32;
+
+// Ranges the .dat format can hold. Terrain x is stored in 12 bits and y in 9 signed bits, both
+// with an offset. Steel areas are stored in units of 4 pixels.
+const TERRAIN_X_RANGE: (i64, i64) = (-16, 4079);
+const TERRAIN_Y_RANGE: (i64, i64) = (-260, 251);
+const TERRAIN_ID_MAX: i64 = 63;
+const OBJECT_X_RANGE: (i64, i64) = (i16::MIN as i64 - 16, i16::MAX as i64 - 16);
+const OBJECT_Y_RANGE: (i64, i64) = (i16::MIN as i64, i16::MAX as i64);
+const STEEL_X_RANGE: (i64, i64) = (-16, 2028);
+const STEEL_Y_RANGE: (i64, i64) = (0, 508);
+const STEEL_SIZE_RANGE: (i64, i64) = (4, 64);
+const STEEL_GRID: i64 = 4;
+
+// The serde derives describe the tables of the text format, see level_text.rs
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct TerrainTile {
+    #[serde(deserialize_with = "integer::<_, _, 0, TERRAIN_ID_MAX>")]
+    pub id: u32,
+    #[serde(deserialize_with = "integer::<_, _, { TERRAIN_X_RANGE.0 }, { TERRAIN_X_RANGE.1 }>")]
     pub x: i32,
+    #[serde(deserialize_with = "integer::<_, _, { TERRAIN_Y_RANGE.0 }, { TERRAIN_Y_RANGE.1 }>")]
     pub y: i32,
-    pub id: u32,
+    #[serde(default)]
     pub do_not_overwrite: bool,
+    #[serde(default)]
     pub flip_y: bool,
+    #[serde(default)]
     pub remove_terrain: bool,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct Object {
+    #[serde(deserialize_with = "word")]
+    pub id: u32,
+    #[serde(deserialize_with = "integer::<_, _, { OBJECT_X_RANGE.0 }, { OBJECT_X_RANGE.1 }>")]
     pub x: i32,
+    #[serde(deserialize_with = "integer::<_, _, { OBJECT_Y_RANGE.0 }, { OBJECT_Y_RANGE.1 }>")]
     pub y: i32,
-    pub id: u32,
+    #[serde(default)]
     pub do_not_overwrite: bool,
+    #[serde(default)]
     pub flip_y: bool,
+    #[serde(default)]
     pub draw_only_over_terrain: bool,
 }
 
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SteelArea {
+    #[serde(
+        deserialize_with = "multiple::<_, _, STEEL_GRID, { STEEL_X_RANGE.0 }, { STEEL_X_RANGE.1 }>"
+    )]
+    pub x: i32,
+    #[serde(
+        deserialize_with = "multiple::<_, _, STEEL_GRID, { STEEL_Y_RANGE.0 }, { STEEL_Y_RANGE.1 }>"
+    )]
+    pub y: i32,
+    #[serde(
+        deserialize_with = "multiple::<_, _, STEEL_GRID, { STEEL_SIZE_RANGE.0 }, { STEEL_SIZE_RANGE.1 }>"
+    )]
+    pub width: u32,
+    #[serde(
+        deserialize_with = "multiple::<_, _, STEEL_GRID, { STEEL_SIZE_RANGE.0 }, { STEEL_SIZE_RANGE.1 }>"
+    )]
+    pub height: u32,
+}
+
 #[derive(Clone)]
 pub struct LevelParameters {
     pub release_rate: u32,
@@ -49,6 +105,7 @@ pub struct Level {
     pub extended_graphics_set: u32,
     pub terrain_tiles: Vec<TerrainTile>,
     pub objects: Vec<Object>,
+    pub steel_areas: Vec<SteelArea>,
 }
 
 #[allow(dead_code)]
@@ -58,30 +115,47 @@ pub trait LevelStructure {
     fn get_y(&self) -> i32;
 }
 
-pub fn read_level_file(path: &Path, index: usize) -> Result<Vec<Level>> {
+pub fn read_level_file(vfs: &dyn Vfs, index: usize) -> Result<Vec<Level>> {
     let filename = format!("level00{}.dat", index);
     println!("reading {}", &filename);
 
-    let compressed_level_data = fs::read(path.join(filename).as_os_str())?;
+    let compressed_level_data = vfs
+        .read(&filename)
+        .map_err(GameDataError::from)
+        .in_file(&filename)?;
+
+    parse_level_file(&compressed_level_data).in_file(&filename)
+}
 
-    let decompressed_level_sections = datfile::parse(&compressed_level_data)?;
+pub fn parse_level_file(compressed_level_data: &[u8]) -> Result<Vec<Level>> {
+    let decompressed_level_sections = datfile::parse(compressed_level_data)?;
     let mut levels: Vec<Level> = Vec::new();
 
-    for section in decompressed_level_sections.sections.iter() {
-        let level = decode_level(&section.data)?;
+    for (i, section) in decompressed_level_sections.sections.iter().enumerate() {
+        let level = decode_level(&section.data).in_section(i)?;
         levels.push(level);
     }
 
     Ok(levels)
 }
 
-pub fn read_oddtable(path: &Path) -> Result<Vec<LevelParameters>> {
+pub fn read_oddtable(vfs: &dyn Vfs) -> Result<Vec<LevelParameters>> {
     println!("reading {}", ODDTABLE_FILENAME);
 
-    let oddtable_data = fs::read(path.join(ODDTABLE_FILENAME).as_os_str())?;
+    let oddtable_data = vfs
+        .read(ODDTABLE_FILENAME)
+        .map_err(GameDataError::from)
+        .in_file(ODDTABLE_FILENAME)?;
 
+    parse_oddtable(&oddtable_data).in_file(ODDTABLE_FILENAME)
+}
+
+pub fn parse_oddtable(oddtable_data: &[u8]) -> Result<Vec<LevelParameters>> {
     if oddtable_data.len() != ODDTABLE_ENTRY_SIZE * ODDTABLE_ENTRIES {
-        bail!("invalid {}", ODDTABLE_FILENAME);
+        return Err(GameDataError::new(GameDataErrorKind::InvalidSize {
+            expected: ODDTABLE_ENTRY_SIZE * ODDTABLE_ENTRIES,
+            actual: oddtable_data.len(),
+        }));
     }
 
     let mut oddtable: Vec<LevelParameters> = Vec::with_capacity(ODDTABLE_ENTRIES);
@@ -95,8 +169,11 @@ pub fn read_oddtable(path: &Path) -> Result<Vec<LevelParameters>> {
 }
 
 fn decode_level(data: &[u8]) -> Result<Level> {
-    if data.len() != 2048 {
-        bail!("not a level: invalid length");
+    if data.len() != LEVEL_SIZE {
+        return Err(GameDataError::new(GameDataErrorKind::InvalidSize {
+            expected: LEVEL_SIZE,
+            actual: data.len(),
+        }));
     }
 
     let mut skills = [0u32; NUM_SKILLS];
@@ -105,19 +182,26 @@ fn decode_level(data: &[u8]) -> Result<Level> {
     }
 
     let mut terrain_tiles: Vec<TerrainTile> = Vec::new();
-    for i in 0..400 {
+    for i in 0..MAX_TERRAIN_TILES {
         if let Some(tile) = read_terrain_tile(data, i)? {
             terrain_tiles.push(tile);
         }
     }
 
     let mut objects: Vec<Object> = Vec::new();
-    for i in 0..32 {
+    for i in 0..MAX_OBJECTS {
         if let Some(object) = read_object(data, i)? {
             objects.push(object);
         }
     }
 
+    let mut steel_areas: Vec<SteelArea> = Vec::new();
+    for i in 0..MAX_STEEL_AREAS {
+        if let Some(steel_area) = read_steel_area(data, i)? {
+            steel_areas.push(steel_area);
+        }
+    }
+
     Ok(Level {
         parameters: LevelParameters {
             release_rate: read16(data, 0)? as u32,
@@ -132,6 +216,7 @@ fn decode_level(data: &[u8]) -> Result<Level> {
         extended_graphics_set: read16(data, 0x1c)? as u32,
         terrain_tiles,
         objects,
+        steel_areas,
     })
 }
 
@@ -162,17 +247,17 @@ fn read16(data: &[u8], offset: usize) -> Result<u16> {
 fn read_name(data: &[u8], offset: usize) -> Result<String> {
     let mut name = String::new();
 
-    for i in 0..32 {
+    for i in 0..NAME_LENGTH {
         let charcode = read8(data, offset + i)?;
-        name.push(char::from_u32(charcode as u32).context("invalid level name")?);
+        name.push(char::from(charcode));
     }
 
     Ok(String::from(name.trim()))
 }
 
 fn read_terrain_tile(data: &[u8], index: usize) -> Result<Option<TerrainTile>> {
-    if index >= 400 {
-        bail!("invalid terrain index");
+    if index >= MAX_TERRAIN_TILES {
+        return Err(GameDataError::malformed("invalid terrain index"));
     }
 
     let x_and_flags = read16(data, 0x120 + 4 * index)?;
@@ -194,8 +279,8 @@ fn read_terrain_tile(data: &[u8], index: usize) -> Result<Option<TerrainTile>> {
 }
 
 fn read_object(data: &[u8], index: usize) -> Result<Option<Object>> {
-    if index >= 32 {
-        bail!("invalid object index");
+    if index >= MAX_OBJECTS {
+        return Err(GameDataError::malformed("invalid object index"));
     }
 
     let x = read16(data, 0x20 + 8 * index)? as i16;
@@ -218,6 +303,222 @@ fn read_object(data: &[u8], index: usize) -> Result<Option<Object>> {
     }))
 }
 
+fn read_steel_area(data: &[u8], index: usize) -> Result<Option<SteelArea>> {
+    if index >= MAX_STEEL_AREAS {
+        return Err(GameDataError::malformed("invalid steel area index"));
+    }
+
+    let position = read16(data, 0x760 + 4 * index)? as i32;
+    let size = read8(data, 0x762 + 4 * index)? as u32;
+
+    if position == 0 && size == 0 {
+        return Ok(None);
+    }
+
+    Ok(Some(SteelArea {
+        x: (position >> 7) * 4 - 16,
+        y: (position & 0x7f) * 4,
+        width: (size >> 4) * 4 + 4,
+        height: (size & 0x0f) * 4 + 4,
+    }))
+}
+
+fn encodable(
+    value: i32,
+    (min, max): (i64, i64),
+    step: i64,
+    description: &'static str,
+) -> Result<i32> {
+    let value = value as i64;
+
+    if (min..=max).contains(&value) && value % step == 0 {
+        Ok(value as i32)
+    } else {
+        Err(GameDataError::malformed(description))
+    }
+}
+
+fn encodable_u16(value: u32, description: &'static str) -> Result<u16> {
+    u16::try_from(value).map_err(|_| GameDataError::malformed(description))
+}
+
+pub fn encode_level_file(levels: &[Level]) -> Result<Vec<u8>> {
+    let sections = levels
+        .iter()
+        .enumerate()
+        .map(|(i, level)| encode_level(level).in_section(i))
+        .collect::<Result<Vec<_>>>()?;
+
+    datfile::encode(&sections)
+}
+
+pub fn encode_level(level: &Level) -> Result<Vec<u8>> {
+    if level.terrain_tiles.len() > MAX_TERRAIN_TILES {
+        return Err(GameDataError::malformed("too many terrain tiles"));
+    }
+
+    if level.objects.len() > MAX_OBJECTS {
+        return Err(GameDataError::malformed("too many objects"));
+    }
+
+    if level.steel_areas.len() > MAX_STEEL_AREAS {
+        return Err(GameDataError::malformed("too many steel areas"));
+    }
+
+    if level.parameters.name.len() > NAME_LENGTH || !level.parameters.name.is_ascii() {
+        return Err(GameDataError::malformed("invalid level name"));
+    }
+
+    let mut data = vec![0u8; LEVEL_SIZE];
+    let parameters = &level.parameters;
+
+    write16(
+        &mut data,
+        0,
+        encodable_u16(parameters.release_rate, "release rate out of range")?,
+    );
+    write16(
+        &mut data,
+        0x02,
+        encodable_u16(parameters.released, "released count out of range")?,
+    );
+    write16(
+        &mut data,
+        0x04,
+        encodable_u16(parameters.required, "required count out of range")?,
+    );
+    write16(
+        &mut data,
+        0x06,
+        encodable_u16(parameters.time_limit, "time limit out of range")?,
+    );
+
+    for (i, skill) in parameters.skills.iter().enumerate() {
+        write16(
+            &mut data,
+            0x08 + 2 * i,
+            encodable_u16(*skill, "skill count out of range")?,
+        );
+    }
+
+    write16(
+        &mut data,
+        0x18,
+        encodable_u16(level.start_x, "start x out of range")?,
+    );
+    write16(
+        &mut data,
+        0x1a,
+        encodable_u16(level.graphics_set, "graphics set out of range")?,
+    );
+    write16(
+        &mut data,
+        0x1c,
+        encodable_u16(
+            level.extended_graphics_set,
+            "extended graphics set out of range",
+        )?,
+    );
+
+    for (i, object) in level.objects.iter().enumerate() {
+        let offset = 0x20 + 8 * i;
+
+        let x = encodable(object.x, OBJECT_X_RANGE, 1, "object x out of range")?;
+        let y = encodable(object.y, OBJECT_Y_RANGE, 1, "object y out of range")?;
+
+        write16(&mut data, offset, (x + 16) as u16);
+        write16(&mut data, offset + 2, y as u16);
+        write16(
+            &mut data,
+            offset + 4,
+            encodable_u16(object.id, "object id out of range")?,
+        );
+        data[offset + 6] = if object.do_not_overwrite { 0x80 } else { 0 }
+            | if object.draw_only_over_terrain {
+                0x40
+            } else {
+                0
+            };
+        data[offset + 7] = if object.flip_y { 0x8f } else { 0x0f };
+    }
+
+    data[0x120..0x760].fill(0xff);
+    for (i, tile) in level.terrain_tiles.iter().enumerate() {
+        let offset = 0x120 + 4 * i;
+        let flags = if tile.do_not_overwrite { 0x08 } else { 0 }
+            | if tile.flip_y { 0x04 } else { 0 }
+            | if tile.remove_terrain { 0x02 } else { 0 };
+
+        let x = encodable(tile.x, TERRAIN_X_RANGE, 1, "terrain x out of range")?;
+        let y = encodable(tile.y, TERRAIN_Y_RANGE, 1, "terrain y out of range")?;
+        let id = encodable(
+            tile.id as i32,
+            (0, TERRAIN_ID_MAX),
+            1,
+            "terrain id out of range",
+        )?;
+
+        write16(&mut data, offset, (flags << 12) | (x + 16) as u16);
+        write16(
+            &mut data,
+            offset + 2,
+            ((((y + 4) as u16) & 0x01ff) << 7) | id as u16,
+        );
+    }
+
+    for (i, steel_area) in level.steel_areas.iter().enumerate() {
+        let offset = 0x760 + 4 * i;
+
+        let x = encodable(
+            steel_area.x,
+            STEEL_X_RANGE,
+            STEEL_GRID,
+            "steel area x out of range",
+        )?;
+        let y = encodable(
+            steel_area.y,
+            STEEL_Y_RANGE,
+            STEEL_GRID,
+            "steel area y out of range",
+        )?;
+        let width = encodable(
+            steel_area.width as i32,
+            STEEL_SIZE_RANGE,
+            STEEL_GRID,
+            "steel area width out of range",
+        )?;
+        let height = encodable(
+            steel_area.height as i32,
+            STEEL_SIZE_RANGE,
+            STEEL_GRID,
+            "steel area height out of range",
+        )?;
+
+        // An all-zero entry marks an unused slot, so this area would be lost
+        if (x, y, width, height) == (-16, 0, 4, 4) {
+            return Err(GameDataError::malformed(
+                "steel area at -16,0 of size 4x4 cannot be encoded",
+            ));
+        }
+
+        write16(
+            &mut data,
+            offset,
+            ((((x + 16) / 4) as u16) << 7) | (y / 4) as u16,
+        );
+        data[offset + 2] = ((((width - 4) / 4) << 4) | ((height - 4) / 4)) as u8;
+    }
+
+    let name = format!("{:<width$}", parameters.name, width = NAME_LENGTH);
+    data[0x7e0..].copy_from_slice(name.as_bytes());
+
+    Ok(data)
+}
+
+fn write16(data: &mut [u8], offset: usize, value: u16) {
+    data[offset..offset + 2].copy_from_slice(&value.to_be_bytes());
+}
+
 impl LevelStructure for Object {
     fn get_id(&self) -> u32 {
         self.id
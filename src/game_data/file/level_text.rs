@@ -0,0 +1,319 @@
+// Text representation of levels, see doc/level_format.md for a description.
+
+use serde::{Deserialize, Serialize};
+
+use crate::game_data::file::error::Result;
+use crate::game_data::file::level::{Level, LevelParameters, Object, SteelArea, TerrainTile};
+use crate::game_data::file::text::{from_text, to_text, word};
+use crate::game_data::skill::NUM_SKILLS;
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LevelText {
+    name: String,
+    #[serde(deserialize_with = "word")]
+    release_rate: u32,
+    #[serde(deserialize_with = "word")]
+    released: u32,
+    #[serde(deserialize_with = "word")]
+    required: u32,
+    #[serde(deserialize_with = "word")]
+    time_limit: u32,
+    #[serde(deserialize_with = "word")]
+    start_x: u32,
+    #[serde(deserialize_with = "word")]
+    graphics_set: u32,
+    #[serde(default, deserialize_with = "word")]
+    extended_graphics_set: u32,
+    #[serde(default)]
+    skills: Skills,
+    #[serde(default, rename = "object")]
+    objects: Vec<Object>,
+    #[serde(default, rename = "terrain")]
+    terrain_tiles: Vec<TerrainTile>,
+    #[serde(default, rename = "steel")]
+    steel_areas: Vec<SteelArea>,
+}
+
+// Skill counts by name, in the order of the skill panel. Missing skills default to 0.
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct Skills {
+    #[serde(deserialize_with = "word")]
+    climber: u32,
+    #[serde(deserialize_with = "word")]
+    floater: u32,
+    #[serde(deserialize_with = "word")]
+    bomber: u32,
+    #[serde(deserialize_with = "word")]
+    blocker: u32,
+    #[serde(deserialize_with = "word")]
+    builder: u32,
+    #[serde(deserialize_with = "word")]
+    basher: u32,
+    #[serde(deserialize_with = "word")]
+    miner: u32,
+    #[serde(deserialize_with = "word")]
+    digger: u32,
+}
+
+impl From<[u32; NUM_SKILLS]> for Skills {
+    fn from(
+        [
+            climber,
+            floater,
+            bomber,
+            blocker,
+            builder,
+            basher,
+            miner,
+            digger,
+        ]: [u32; NUM_SKILLS],
+    ) -> Self {
+        Self {
+            climber,
+            floater,
+            bomber,
+            blocker,
+            builder,
+            basher,
+            miner,
+            digger,
+        }
+    }
+}
+
+impl From<Skills> for [u32; NUM_SKILLS] {
+    fn from(skills: Skills) -> Self {
+        [
+            skills.climber,
+            skills.floater,
+            skills.bomber,
+            skills.blocker,
+            skills.builder,
+            skills.basher,
+            skills.miner,
+            skills.digger,
+        ]
+    }
+}
+
+pub fn format_level(level: &Level) -> String {
+    let parameters = &level.parameters;
+
+    to_text(&LevelText {
+        name: parameters.name.clone(),
+        release_rate: parameters.release_rate,
+        released: parameters.released,
+        required: parameters.required,
+        time_limit: parameters.time_limit,
+        start_x: level.start_x,
+        graphics_set: level.graphics_set,
+        extended_graphics_set: level.extended_graphics_set,
+        skills: parameters.skills.into(),
+        objects: level.objects.clone(),
+        terrain_tiles: level.terrain_tiles.clone(),
+        steel_areas: level.steel_areas.clone(),
+    })
+}
+
+pub fn parse_level(text: &str) -> Result<Level> {
+    let level: LevelText = from_text(text)?;
+
+    Ok(Level {
+        parameters: LevelParameters {
+            release_rate: level.release_rate,
+            released: level.released,
+            required: level.required,
+            time_limit: level.time_limit,
+            skills: level.skills.into(),
+            name: level.name,
+        },
+        start_x: level.start_x,
+        graphics_set: level.graphics_set,
+        extended_graphics_set: level.extended_graphics_set,
+        terrain_tiles: level.terrain_tiles,
+        objects: level.objects,
+        steel_areas: level.steel_areas,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::game_data::file::error::GameDataErrorKind;
+    use crate::game_data::file::level::{
+        Level, LevelParameters, Object, SteelArea, TerrainTile, encode_level, encode_level_file,
+        parse_level_file,
+    };
+    use crate::game_data::file::level_text::{format_level, parse_level};
+
+    fn example_level() -> Level {
+        Level {
+            parameters: LevelParameters {
+                release_rate: 50,
+                released: 10,
+                required: 1,
+                time_limit: 5,
+                skills: [0, 0, 0, 0, 0, 0, 0, 10],
+                name: "Just \"dig\"! # \\o/".to_string(),
+            },
+            start_x: 0,
+            graphics_set: 0,
+            extended_graphics_set: 0,
+            terrain_tiles: vec![
+                TerrainTile {
+                    x: -16,
+                    y: 100,
+                    id: 12,
+                    do_not_overwrite: false,
+                    flip_y: true,
+                    remove_terrain: false,
+                },
+                TerrainTile {
+                    x: 1200,
+                    y: -4,
+                    id: 3,
+                    do_not_overwrite: true,
+                    flip_y: false,
+                    remove_terrain: true,
+                },
+            ],
+            objects: vec![Object {
+                x: 320,
+                y: -8,
+                id: 1,
+                do_not_overwrite: false,
+                flip_y: false,
+                draw_only_over_terrain: true,
+            }],
+            steel_areas: vec![SteelArea {
+                x: 96,
+                y: 40,
+                width: 16,
+                height: 8,
+            }],
+        }
+    }
+
+    #[test]
+    fn roundtrip() {
+        let level = example_level();
+        let text = format_level(&level);
+
+        assert_eq!(
+            format_level(&parse_level(&text).expect("parse failed")),
+            text
+        );
+        assert_eq!(
+            encode_level(&parse_level(&text).expect("parse failed")).expect("encode failed"),
+            encode_level(&level).expect("encode failed")
+        );
+    }
+
+    #[test]
+    fn dat_roundtrip() {
+        let level = example_level();
+        let encoded = encode_level_file(&[level.clone(), level.clone()]).expect("encode failed");
+        let decoded = parse_level_file(&encoded).expect("parse failed");
+
+        assert_eq!(decoded.len(), 2);
+        assert_eq!(format_level(&decoded[1]), format_level(&level));
+    }
+
+    #[test]
+    fn encode_rejects_out_of_range() {
+        let mut level = example_level();
+        level.terrain_tiles[0].id = 70;
+        assert!(encode_level(&level).is_err());
+
+        let mut level = example_level();
+        level.terrain_tiles[1].x = 5000;
+        assert!(encode_level(&level).is_err());
+
+        let mut level = example_level();
+        level.steel_areas[0].width = 18;
+        assert!(encode_level(&level).is_err());
+
+        let mut level = example_level();
+        level.objects[0].x = 40000;
+        assert!(encode_level(&level).is_err());
+    }
+
+    #[test]
+    fn steel_area_at_origin_roundtrip() {
+        let mut level = example_level();
+        level.steel_areas[0] = SteelArea {
+            x: -16,
+            y: 0,
+            width: 4,
+            height: 4,
+        };
+        assert!(encode_level(&level).is_err());
+
+        level.steel_areas[0].y = 4;
+        let encoded = encode_level_file(&[level.clone()]).expect("encode failed");
+        let decoded = parse_level_file(&encoded).expect("parse failed");
+        assert_eq!(format_level(&decoded[0]), format_level(&level));
+    }
+
+    #[test]
+    fn defaults_and_comments() {
+        let level = parse_level(
+            r#"
+# a comment
+name = "test" # trailing comment
+release_rate = 1
+released = 2
+required = 3
+time_limit = 4
+start_x = 5
+graphics_set = 1
+
+[skills]
+digger = 3
+
+[[object]]
+id = 2
+x = 10
+y = 20
+"#,
+        )
+        .expect("parse failed");
+
+        assert_eq!(level.parameters.name, "test");
+        assert_eq!(level.parameters.skills, [0, 0, 0, 0, 0, 0, 0, 3]);
+        assert_eq!(level.extended_graphics_set, 0);
+        assert_eq!(level.objects.len(), 1);
+        assert!(!level.objects[0].flip_y);
+    }
+
+    #[test]
+    fn errors_report_line() {
+        let text = format_level(&example_level());
+
+        for (replace, with, line) in [
+            ("released = 10", "released = -1", 3),
+            ("released = 10", "released = yes", 3),
+            ("released = 10\n", "", 1),
+            ("[skills]\n", "[skills]\nlemming = 1\n", 11),
+            ("[[steel]]", "[[steal]]", 44),
+            ("x = 1200", "x = 5000", 38),
+            ("id = 3", "id = 70", 37),
+            ("y = 100", "y = 300", 31),
+            ("width = 16", "width = 18", 47),
+            ("height = 8", "height = 68", 48),
+            ("x = 320", "x = 40000", 22),
+        ] {
+            let err = parse_level(&text.replacen(replace, with, 1))
+                .err()
+                .expect("parse should fail");
+
+            assert!(
+                matches!(err.kind, GameDataErrorKind::InvalidText { line: l, .. } if l == line),
+                "{}: {}",
+                with,
+                err
+            );
+        }
+    }
+}
@@ -1,16 +1,24 @@
-use std::{convert::TryInto, fs, path::Path};
-
-use anyhow::{Result, anyhow, bail};
+use std::convert::TryInto;
 
 use crate::game_data::Bitmap;
 use crate::game_data::file::encoding::datfile;
+use crate::game_data::file::error::{ErrorLocation, GameDataError, GameDataErrorKind, Result};
+use crate::game_data::file::palette::{MENU_PALETTE, PALETTE_SIZE, PaletteEntry};
 use crate::game_data::file::sprite::{Sprite, TransparencyEncoding};
+use crate::game_data::file::vfs::Vfs;
 
 pub const NUM_LEMMING_SPRITES: usize = 30;
 
 pub const FONT_SKILL_PANEL_SKILLS_SIZE: usize = 11;
 pub const FONT_SKILL_PANEL_SIZE: usize = 39;
 
+pub const NUM_COUNTDOWN_DIGITS: usize = 5;
+pub const NUM_DIFFICULTY_SIGNS: usize = 4;
+pub const FONT_MENU_SIZE: usize = 94;
+
+// Sections in main.dat. The last two are not used by the engine.
+pub const MAIN_SECTIONS: usize = 7;
+
 const COLOR_WHITE: u8 = 0x03;
 const COLOR_BLACK: u8 = 0x00;
 const COLOR_DARK_GREEN: u8 = 0x02;
@@ -84,40 +92,86 @@ pub enum LemmingSprite {
     Explosion = 29,
 }
 
+#[derive(Clone)]
+pub struct Masks {
+    pub bash_right: Sprite,
+    pub bash_left: Sprite,
+    pub mine_right: Sprite,
+    pub mine_left: Sprite,
+    pub explosion: Bitmap,
+}
+
+#[derive(Clone)]
+pub struct MainMenu {
+    pub palette: [PaletteEntry; PALETTE_SIZE],
+    pub background: Bitmap,
+    pub logo: Bitmap,
+    pub sign_play: Bitmap,
+    pub sign_level_code: Bitmap,
+    pub sign_music: Bitmap,
+    pub sign_difficulty: Bitmap,
+    pub sign_exit: Bitmap,
+    pub sign_music_note: Bitmap,
+    pub sign_fx: Bitmap,
+    pub font: Sprite,
+    pub blink: Sprite,
+    pub scroller_left: Sprite,
+    pub scroller_right: Sprite,
+    pub reel: Bitmap,
+    pub difficulty_signs: [Bitmap; NUM_DIFFICULTY_SIGNS],
+}
+
 pub struct Content {
     pub lemming_sprites: [Sprite; NUM_LEMMING_SPRITES],
     pub skill_panel: Bitmap,
     pub font_skill_panel_skills: Sprite,
     pub font_skill_panel: Sprite,
+    pub masks: Masks,
+    pub countdown_digits: Sprite,
+    pub main_menu: MainMenu,
 }
 
-pub fn read_main(path: &Path) -> Result<Content> {
+pub fn read_main(vfs: &dyn Vfs) -> Result<Content> {
     println!("reading main.dat");
-    let maindata = fs::read(path.join("main.dat").as_os_str())?;
+    let maindata = vfs
+        .read("main.dat")
+        .map_err(GameDataError::from)
+        .in_file("main.dat")?;
+
+    parse_main(&maindata).in_file("main.dat")
+}
 
-    let datfile::Content { sections } = datfile::parse(&maindata)?;
-    if sections.len() < 3 {
-        bail!("invalid main.dat");
+pub fn parse_main(maindata: &[u8]) -> Result<Content> {
+    let datfile::Content { sections } = datfile::parse(maindata)?;
+    if sections.len() < MAIN_SECTIONS {
+        return Err(GameDataError::new(GameDataErrorKind::InvalidSectionCount {
+            expected: MAIN_SECTIONS,
+            actual: sections.len(),
+        }));
     }
 
     let mut lemming_sprites: Vec<Sprite> = Vec::new();
     let mut offset = 0;
 
     for (frame_count, width, height, bpp) in LEMMING_SPRITE_LAYOUT {
-        lemming_sprites.push(Sprite::read_planar(
-            frame_count,
-            width,
-            height,
-            bpp,
-            &sections[0].data,
-            &mut offset,
-            (width as usize * height as usize * bpp) / 8,
-            TransparencyEncoding::Black,
-        )?);
+        lemming_sprites.push(
+            Sprite::read_planar(
+                frame_count,
+                width,
+                height,
+                bpp,
+                &sections[0].data,
+                &mut offset,
+                (width as usize * height as usize * bpp) / 8,
+                TransparencyEncoding::Black,
+            )
+            .in_section(0)?,
+        );
     }
 
     let skill_panel =
-        Bitmap::read_planar(320, 40, 4, &sections[2].data, TransparencyEncoding::Black)?;
+        Bitmap::read_planar(320, 40, 4, &sections[2].data, TransparencyEncoding::Black)
+            .in_section(2)?;
 
     let mut font_skill_panel_skills = Sprite::blank(4, 8, FONT_SKILL_PANEL_SKILLS_SIZE);
     let mut font_skill_panel = Sprite::blank(8, 16, FONT_SKILL_PANEL_SIZE);
@@ -130,10 +184,12 @@ pub fn read_main(path: &Path) -> Result<Content> {
             sections[2]
                 .data
                 .get(0x1908 + i * 0x10..)
-                .ok_or(anyhow!("skill font data out of bounds"))?,
+                .ok_or(GameDataError::out_of_bounds(0x1908 + i * 0x10))
+                .in_section(2)?,
             TransparencyEncoding::Opaque,
             |x| if x == 0 { COLOR_BLACK } else { COLOR_WHITE },
-        )?
+        )
+        .in_section(2)?
         .sub(0, 0, 4, 8)?;
 
         font_skill_panel_skills.add_frame(&font_bitmap)?;
@@ -149,7 +205,8 @@ pub fn read_main(path: &Path) -> Result<Content> {
             sections[2]
                 .data
                 .get((0x19a0 + i * 0x30)..)
-                .ok_or(anyhow!("skill panel font data out of bounds"))?,
+                .ok_or(GameDataError::out_of_bounds(0x19a0 + i * 0x30))
+                .in_section(2)?,
             TransparencyEncoding::Opaque,
             |x| match x {
                 0x05 => COLOR_LIGHT_GREEN,
@@ -157,21 +214,153 @@ pub fn read_main(path: &Path) -> Result<Content> {
                 0x02 => COLOR_DARK_GREEN,
                 _ => 0x00,
             },
-        )?;
+        )
+        .in_section(2)?;
 
         font_skill_panel.add_frame(&font_bitmap)?;
     }
 
     font_skill_panel.add_frame(&Bitmap::filled(8, 16, COLOR_BLACK, false))?;
 
+    let (masks, countdown_digits) = read_masks(&sections[1].data).in_section(1)?;
+    let main_menu = read_main_menu(&sections[3].data, &sections[4].data)?;
+
     Ok(Content {
         lemming_sprites: lemming_sprites
             .try_into()
-            .map_err(|_| ())
-            .expect("internal error"),
+            .map_err(|_| GameDataError::malformed("unexpected number of lemming sprites"))?,
         skill_panel,
         font_skill_panel_skills,
         font_skill_panel,
+        masks,
+        countdown_digits,
+        main_menu,
+    })
+}
+
+fn read_masks(data: &[u8]) -> Result<(Masks, Sprite)> {
+    let mut offset = 0;
+    let mut read_mask = |frame_count: usize, width: u32, height: u32| {
+        Sprite::read_planar(
+            frame_count,
+            width,
+            height,
+            1,
+            data,
+            &mut offset,
+            (width * height) as usize / 8,
+            TransparencyEncoding::Black,
+        )
+    };
+
+    let masks = Masks {
+        bash_right: read_mask(4, 16, 10)?,
+        bash_left: read_mask(4, 16, 10)?,
+        mine_right: read_mask(2, 16, 13)?,
+        mine_left: read_mask(2, 16, 13)?,
+        explosion: read_mask(1, 16, 22)?.frames.remove(0),
+    };
+
+    let mut countdown_digits = Sprite::blank(8, 8, NUM_COUNTDOWN_DIGITS);
+    for i in 0..NUM_COUNTDOWN_DIGITS {
+        let digit_offset = offset + 8 * i;
+
+        countdown_digits.add_frame(&Bitmap::read_planar_mapped(
+            8,
+            8,
+            1,
+            data.get(digit_offset..)
+                .ok_or(GameDataError::out_of_bounds(digit_offset))?,
+            TransparencyEncoding::Black,
+            |x| if x == 0 { COLOR_BLACK } else { COLOR_WHITE },
+        )?)?;
+    }
+
+    Ok((masks, countdown_digits))
+}
+
+fn read_main_menu(data_static: &[u8], data_animated: &[u8]) -> Result<MainMenu> {
+    let mut offset = 0;
+    let mut read_static = |width: u32, height: u32, bpp: usize, transparency| {
+        Sprite::read_planar(
+            1,
+            width,
+            height,
+            bpp,
+            data_static,
+            &mut offset,
+            (width * height) as usize * bpp / 8,
+            transparency,
+        )
+        .map(|mut sprite| sprite.frames.remove(0))
+        .in_section(3)
+    };
+
+    let background = read_static(320, 104, 2, TransparencyEncoding::Opaque)?;
+    let logo = read_static(632, 94, 4, TransparencyEncoding::Black)?;
+    let sign_play = read_static(120, 61, 4, TransparencyEncoding::Black)?;
+    let sign_level_code = read_static(120, 61, 4, TransparencyEncoding::Black)?;
+    let sign_music = read_static(120, 61, 4, TransparencyEncoding::Black)?;
+    let sign_difficulty = read_static(120, 61, 4, TransparencyEncoding::Black)?;
+    let sign_exit = read_static(120, 61, 4, TransparencyEncoding::Black)?;
+    let sign_music_note = read_static(64, 31, 4, TransparencyEncoding::Black)?;
+    let sign_fx = read_static(64, 31, 4, TransparencyEncoding::Black)?;
+
+    let font = Sprite::read_planar(
+        FONT_MENU_SIZE,
+        16,
+        16,
+        3,
+        data_static,
+        &mut offset,
+        16 * 16 * 3 / 8,
+        TransparencyEncoding::Black,
+    )
+    .in_section(3)?;
+
+    let mut offset = 0;
+    let mut read_animated = |frame_count: usize, width: u32, height: u32| {
+        Sprite::read_planar(
+            frame_count,
+            width,
+            height,
+            4,
+            data_animated,
+            &mut offset,
+            (width * height) as usize / 2,
+            TransparencyEncoding::Black,
+        )
+        .in_section(4)
+    };
+
+    let blink = read_animated(8, 32, 12)?;
+    let scroller_left = read_animated(16, 48, 16)?;
+    let scroller_right = read_animated(16, 48, 16)?;
+    let reel = read_animated(1, 16, 16)?.frames.remove(0);
+
+    // The signs are stored from mayhem to fun
+    let mut difficulty_signs = read_animated(NUM_DIFFICULTY_SIGNS, 72, 27)?.frames;
+    difficulty_signs.reverse();
+
+    Ok(MainMenu {
+        palette: MENU_PALETTE,
+        background,
+        logo,
+        sign_play,
+        sign_level_code,
+        sign_music,
+        sign_difficulty,
+        sign_exit,
+        sign_music_note,
+        sign_fx,
+        font,
+        blink,
+        scroller_left,
+        scroller_right,
+        reel,
+        difficulty_signs: difficulty_signs
+            .try_into()
+            .map_err(|_| GameDataError::malformed("unexpected number of difficulty signs"))?,
     })
 }
 
@@ -184,6 +373,13 @@ pub fn resolve_skill_panel_skill_font_index(c: char) -> usize {
     }
 }
 
+pub fn resolve_menu_font_index(c: char) -> Option<usize> {
+    match c {
+        '!'..='~' => Some(c as usize - '!' as usize),
+        _ => None,
+    }
+}
+
 pub fn resolve_skill_panel_font_index(c: char) -> usize {
     match c {
         '%' => 0,
@@ -1,10 +1,20 @@
 pub mod encoding;
+pub mod error;
 pub mod ground;
 pub mod level;
+pub mod level_text;
 pub mod main;
+pub mod nxlv;
 pub mod palette;
+pub mod path;
+pub mod profile;
 pub mod sprite;
+pub mod vfs;
 pub mod vgagr;
 pub mod vgaspec;
 
 mod read;
+pub(crate) mod text;
+
+#[cfg(test)]
+mod robustness_test;
@@ -0,0 +1,191 @@
+// Export to the NeoLemmix .nxlv level format, see doc/level_format.md.
+
+use std::fmt::Write;
+
+use crate::game_data::file::ground::ObjectInfo;
+use crate::game_data::file::level::Level;
+use crate::game_data::skill::SKILLS;
+use crate::game_data::{LEVEL_HEIGHT, LEVEL_WIDTH, SCREEN_WIDTH, VGASPEC_POSITION};
+
+// NeoLemmix styles that contain the tilesets of the DOS version, indexed by graphics set
+const STYLES: [&str; 5] = [
+    "orig_dirt",
+    "orig_fire",
+    "orig_marble",
+    "orig_pillar",
+    "orig_crystal",
+];
+
+// Style and piece of the special backgrounds, indexed by extended graphics set - 1
+const SPECIAL_PIECES: [(&str, &str); 4] = [
+    ("orig_awesome", "awesome"),
+    ("orig_menacing", "menacing"),
+    ("orig_beasti", "beasti"),
+    ("orig_beastii", "beastii"),
+];
+
+// `object_info` is the object info of the level's tileset and only used to annotate the objects
+pub fn format_nxlv(level: &Level, object_info: &[ObjectInfo]) -> String {
+    let mut text = String::new();
+    let parameters = &level.parameters;
+    let style = STYLES
+        .get(level.graphics_set as usize)
+        .copied()
+        .unwrap_or(STYLES[0]);
+
+    // Writing to a string is infallible
+    let _ = writeln!(text, "# NeoLemmix Level");
+    let _ = writeln!(text, " TITLE {}", parameters.name.trim_end());
+    let _ = writeln!(text, " AUTHOR DMA Design");
+    let _ = writeln!(text, " THEME {}", style);
+    let _ = writeln!(text, " WIDTH {}", LEVEL_WIDTH);
+    let _ = writeln!(text, " HEIGHT {}", LEVEL_HEIGHT);
+    // NeoLemmix positions the center of the screen
+    let _ = writeln!(text, " START_X {}", level.start_x + SCREEN_WIDTH / 2);
+    let _ = writeln!(text, " START_Y {}", LEVEL_HEIGHT / 2);
+    let _ = writeln!(text);
+    let _ = writeln!(text, " LEMMINGS {}", parameters.released);
+    let _ = writeln!(text, " SAVE_REQUIREMENT {}", parameters.required);
+    let _ = writeln!(text, " TIME_LIMIT {}", parameters.time_limit * 60);
+    let _ = writeln!(
+        text,
+        " MAX_SPAWN_INTERVAL {}",
+        53u32.saturating_sub(parameters.release_rate / 2)
+    );
+
+    let _ = writeln!(text, "\n $SKILLSET");
+    for skill in SKILLS {
+        let count = parameters.skills[skill as usize];
+
+        if count > 0 {
+            let _ = writeln!(text, "   {} {}", skill.to_string().to_uppercase(), count);
+        }
+    }
+    let _ = writeln!(text, " $END");
+
+    for object in &level.objects {
+        let _ = writeln!(text);
+
+        if let Some(info) = object_info.get(object.id as usize) {
+            let _ = writeln!(text, " # {}", info.interaction_type);
+        }
+
+        let _ = writeln!(text, " $GADGET");
+        let _ = writeln!(text, "   STYLE {}", style);
+        let _ = writeln!(text, "   PIECE {}", object.id);
+        let _ = writeln!(text, "   X {}", object.x);
+        let _ = writeln!(text, "   Y {}", object.y);
+
+        if object.do_not_overwrite {
+            let _ = writeln!(text, "   NO_OVERWRITE");
+        }
+        if object.draw_only_over_terrain {
+            let _ = writeln!(text, "   ONLY_ON_TERRAIN");
+        }
+        if object.flip_y {
+            let _ = writeln!(text, "   FLIP_VERTICAL");
+        }
+
+        let _ = writeln!(text, " $END");
+    }
+
+    if let Some((special_style, piece)) = (level.extended_graphics_set as usize)
+        .checked_sub(1)
+        .and_then(|i| SPECIAL_PIECES.get(i))
+    {
+        let _ = writeln!(text, "\n $TERRAIN");
+        let _ = writeln!(text, "   STYLE {}", special_style);
+        let _ = writeln!(text, "   PIECE {}", piece);
+        let _ = writeln!(text, "   X {}", VGASPEC_POSITION);
+        let _ = writeln!(text, "   Y 0");
+        let _ = writeln!(text, " $END");
+    }
+
+    for tile in &level.terrain_tiles {
+        let _ = writeln!(text, "\n $TERRAIN");
+        let _ = writeln!(text, "   STYLE {}", style);
+        let _ = writeln!(text, "   PIECE {}", tile.id);
+        let _ = writeln!(text, "   X {}", tile.x);
+        let _ = writeln!(text, "   Y {}", tile.y);
+
+        if tile.do_not_overwrite {
+            let _ = writeln!(text, "   NO_OVERWRITE");
+        }
+        if tile.remove_terrain {
+            let _ = writeln!(text, "   ERASE");
+        }
+        if tile.flip_y {
+            let _ = writeln!(text, "   FLIP_VERTICAL");
+        }
+
+        let _ = writeln!(text, " $END");
+    }
+
+    text
+}
+
+#[cfg(test)]
+mod test {
+    use crate::game_data::file::ground::{InteractionType, ObjectInfo};
+    use crate::game_data::file::level::{Level, LevelParameters, Object, TerrainTile};
+    use crate::game_data::file::nxlv::format_nxlv;
+
+    #[test]
+    fn format() {
+        let level = Level {
+            parameters: LevelParameters {
+                release_rate: 50,
+                released: 10,
+                required: 1,
+                time_limit: 5,
+                skills: [0, 0, 0, 0, 0, 0, 0, 10],
+                name: "Just dig!                       ".to_string(),
+            },
+            start_x: 0,
+            graphics_set: 1,
+            extended_graphics_set: 0,
+            terrain_tiles: vec![TerrainTile {
+                x: 304,
+                y: 96,
+                id: 12,
+                do_not_overwrite: false,
+                flip_y: true,
+                remove_terrain: true,
+            }],
+            objects: vec![Object {
+                x: 320,
+                y: 72,
+                id: 0,
+                do_not_overwrite: true,
+                flip_y: false,
+                draw_only_over_terrain: false,
+            }],
+            steel_areas: Vec::new(),
+        };
+
+        let object_info = [ObjectInfo {
+            interaction_type: InteractionType::Exit,
+            ..Default::default()
+        }];
+
+        let text = format_nxlv(&level, &object_info);
+
+        for expected in [
+            " TITLE Just dig!\n",
+            " THEME orig_fire\n",
+            " START_X 160\n",
+            " TIME_LIMIT 300\n",
+            " MAX_SPAWN_INTERVAL 28\n",
+            " $SKILLSET\n   DIGGER 10\n $END\n",
+            " # exit\n $GADGET\n   STYLE orig_fire\n   PIECE 0\n   X 320\n   Y 72\n   NO_OVERWRITE\n $END\n",
+            " $TERRAIN\n   STYLE orig_fire\n   PIECE 12\n   X 304\n   Y 96\n   ERASE\n   FLIP_VERTICAL\n $END\n",
+        ] {
+            assert!(
+                text.contains(expected),
+                "missing {:?} in\n{}",
+                expected,
+                text
+            );
+        }
+    }
+}
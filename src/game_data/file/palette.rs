@@ -1,5 +1,4 @@
-use anyhow::Result;
-
+use crate::game_data::file::error::Result;
 use crate::game_data::file::read::read_byte;
 
 pub const PALETTE_SIZE: usize = 16;
@@ -42,3 +41,22 @@ pub const UPPER_PALETTE_SKILL_PANEL: [PaletteEntry; 8] = [
     expand_rgb6_to8(0x3f, 0x15, 0x3f),
     expand_rgb6_to8(0x2a, 0x00, 0x2a),
 ];
+
+pub const MENU_PALETTE: [PaletteEntry; PALETTE_SIZE] = [
+    (0x00, 0x00, 0x00),
+    (0x80, 0x40, 0x20),
+    (0x60, 0x30, 0x20),
+    (0x30, 0x00, 0x10),
+    (0x20, 0x08, 0x7c),
+    (0x40, 0x2c, 0x90),
+    (0x68, 0x58, 0xa4),
+    (0x98, 0x8c, 0xbc),
+    (0x00, 0x50, 0x00),
+    (0x00, 0x60, 0x10),
+    (0x00, 0x70, 0x20),
+    (0x00, 0x80, 0x40),
+    (0xd0, 0xd0, 0xd0),
+    (0xb0, 0xb0, 0x00),
+    (0x40, 0x50, 0xb0),
+    (0xe0, 0x80, 0x90),
+];
@@ -0,0 +1,55 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// DOS file names are case-insensitive, and copies of the original files are often uppercase.
+// Returns the file in `directory` that matches `name` regardless of case, or `directory/name` if
+// there is none.
+pub fn resolve_path(directory: &Path, name: &str) -> PathBuf {
+    let exact = directory.join(name);
+    if exact.exists() {
+        return exact;
+    }
+
+    let listed = if directory.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        directory
+    };
+
+    fs::read_dir(listed)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .find(|entry| {
+            entry
+                .file_name()
+                .to_string_lossy()
+                .eq_ignore_ascii_case(name)
+        })
+        .map(|entry| directory.join(entry.file_name()))
+        .unwrap_or(exact)
+}
+
+#[cfg(test)]
+mod test {
+    use std::{env, fs, process};
+
+    use crate::game_data::file::path::resolve_path;
+
+    #[test]
+    fn resolve() {
+        let path = env::temp_dir().join(format!("rustlings-resolve-path-{}", process::id()));
+        fs::create_dir_all(&path).unwrap();
+        fs::write(path.join("LEVEL000.DAT"), b"").unwrap();
+        fs::write(path.join("main.dat"), b"").unwrap();
+
+        let upper = resolve_path(&path, "level000.dat");
+        let lower = resolve_path(&path, "MAIN.DAT");
+        let missing = resolve_path(&path, "oddtable.dat");
+        fs::remove_dir_all(&path).unwrap();
+
+        assert_eq!(upper, path.join("LEVEL000.DAT"));
+        assert_eq!(lower, path.join("main.dat"));
+        assert_eq!(missing, path.join("oddtable.dat"));
+    }
+}
@@ -0,0 +1,333 @@
+// Player profile with progress and settings, stored in the same text format as levels
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use toml::Spanned;
+
+use crate::game_data::file::error::Result;
+use crate::game_data::file::text::{from_text, integer, line_at, syntax_error, to_text, word};
+use crate::game_data::{LEVELS_PER_DIFFICULTY, NUM_LEVELS, decode_level_index};
+
+pub const NUM_RATINGS: usize = NUM_LEVELS / LEVELS_PER_DIFFICULTY;
+
+const LAST_LEVEL: i64 = NUM_LEVELS as i64 - 1;
+const LAST_LEVEL_NUMBER: i64 = LEVELS_PER_DIFFICULTY as i64;
+
+// Best result of a level
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct LevelRecord {
+    pub rescued: u32,
+    pub percentage: u32,
+    pub time_remaining_seconds: u32,
+    pub skills_used: u32,
+}
+
+impl LevelRecord {
+    // More lemmings saved wins, then more time left, then fewer skills used
+    pub fn is_better_than(&self, other: &LevelRecord) -> bool {
+        (
+            self.percentage,
+            self.time_remaining_seconds,
+            u32::MAX - self.skills_used,
+        ) > (
+            other.percentage,
+            other.time_remaining_seconds,
+            u32::MAX - other.skills_used,
+        )
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Profile {
+    pub current_level: usize,
+    pub lock_levels: bool,
+    pub fullscreen: bool,
+
+    // Furthest level number reached in each rating, starting at 1
+    pub unlocked: [usize; NUM_RATINGS],
+    pub records: BTreeMap<usize, LevelRecord>,
+
+    // Key bindings replacing the defaults, by action name. The game interprets them.
+    pub keys: BTreeMap<String, String>,
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Self {
+            current_level: 0,
+            lock_levels: true,
+            fullscreen: false,
+            unlocked: [1; NUM_RATINGS],
+            records: BTreeMap::new(),
+            keys: BTreeMap::new(),
+        }
+    }
+}
+
+impl Profile {
+    pub fn is_unlocked(&self, level_index: usize) -> bool {
+        let (rating, number) = decode_level_index(level_index);
+        !self.lock_levels || number <= self.unlocked[rating as usize]
+    }
+
+    pub fn unlock(&mut self, level_index: usize) {
+        let (rating, number) = decode_level_index(level_index);
+        let unlocked = &mut self.unlocked[rating as usize];
+        *unlocked = (*unlocked).max(number);
+    }
+
+    // Returns true if the record is a new best for the level
+    pub fn record(&mut self, level_index: usize, record: LevelRecord) -> bool {
+        match self.records.get(&level_index) {
+            Some(best) if !record.is_better_than(best) => false,
+            _ => {
+                self.records.insert(level_index, record);
+                true
+            }
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ProfileText {
+    #[serde(deserialize_with = "integer::<_, _, 0, LAST_LEVEL>")]
+    current_level: usize,
+    lock_levels: bool,
+    fullscreen: bool,
+    unlocked: Unlocked,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    keys: BTreeMap<String, String>,
+    #[serde(rename = "level")]
+    levels: Vec<Spanned<LevelRecordText>>,
+}
+
+impl Default for ProfileText {
+    fn default() -> Self {
+        Self::from(&Profile::default())
+    }
+}
+
+impl From<&Profile> for ProfileText {
+    fn from(profile: &Profile) -> Self {
+        Self {
+            current_level: profile.current_level,
+            lock_levels: profile.lock_levels,
+            fullscreen: profile.fullscreen,
+            unlocked: profile.unlocked.into(),
+            keys: profile.keys.clone(),
+            levels: profile
+                .records
+                .iter()
+                .map(|(&index, record)| {
+                    Spanned::new(
+                        0..0,
+                        LevelRecordText {
+                            index,
+                            rescued: record.rescued,
+                            percentage: record.percentage,
+                            time_remaining: record.time_remaining_seconds,
+                            skills_used: record.skills_used,
+                        },
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+// Furthest level number reached in each rating
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Unlocked {
+    #[serde(
+        default = "first_level",
+        deserialize_with = "integer::<_, _, 1, LAST_LEVEL_NUMBER>"
+    )]
+    fun: usize,
+    #[serde(
+        default = "first_level",
+        deserialize_with = "integer::<_, _, 1, LAST_LEVEL_NUMBER>"
+    )]
+    tricky: usize,
+    #[serde(
+        default = "first_level",
+        deserialize_with = "integer::<_, _, 1, LAST_LEVEL_NUMBER>"
+    )]
+    taxing: usize,
+    #[serde(
+        default = "first_level",
+        deserialize_with = "integer::<_, _, 1, LAST_LEVEL_NUMBER>"
+    )]
+    mayhem: usize,
+}
+
+impl From<[usize; NUM_RATINGS]> for Unlocked {
+    fn from([fun, tricky, taxing, mayhem]: [usize; NUM_RATINGS]) -> Self {
+        Self {
+            fun,
+            tricky,
+            taxing,
+            mayhem,
+        }
+    }
+}
+
+impl From<Unlocked> for [usize; NUM_RATINGS] {
+    fn from(unlocked: Unlocked) -> Self {
+        [
+            unlocked.fun,
+            unlocked.tricky,
+            unlocked.taxing,
+            unlocked.mayhem,
+        ]
+    }
+}
+
+fn first_level() -> usize {
+    1
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LevelRecordText {
+    #[serde(deserialize_with = "integer::<_, _, 0, LAST_LEVEL>")]
+    index: usize,
+    #[serde(deserialize_with = "word")]
+    rescued: u32,
+    #[serde(deserialize_with = "integer::<_, _, 0, 100>")]
+    percentage: u32,
+    #[serde(deserialize_with = "word")]
+    time_remaining: u32,
+    #[serde(deserialize_with = "word")]
+    skills_used: u32,
+}
+
+pub fn format_profile(profile: &Profile) -> String {
+    to_text(&ProfileText::from(profile))
+}
+
+pub fn parse_profile(text: &str) -> Result<Profile> {
+    let profile: ProfileText = from_text(text)?;
+    let mut records = BTreeMap::new();
+
+    for level in profile.levels {
+        let line = line_at(text, level.span());
+        let level = level.into_inner();
+        let record = LevelRecord {
+            rescued: level.rescued,
+            percentage: level.percentage,
+            time_remaining_seconds: level.time_remaining,
+            skills_used: level.skills_used,
+        };
+
+        if records.insert(level.index, record).is_some() {
+            return Err(syntax_error(
+                line,
+                format!("duplicate record for level {}", level.index),
+            ));
+        }
+    }
+
+    Ok(Profile {
+        current_level: profile.current_level,
+        lock_levels: profile.lock_levels,
+        fullscreen: profile.fullscreen,
+        unlocked: profile.unlocked.into(),
+        records,
+        keys: profile.keys,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::game_data::file::error::GameDataErrorKind;
+    use crate::game_data::file::profile::{LevelRecord, Profile, format_profile, parse_profile};
+
+    fn record(percentage: u32, time_remaining_seconds: u32, skills_used: u32) -> LevelRecord {
+        LevelRecord {
+            rescued: percentage / 2,
+            percentage,
+            time_remaining_seconds,
+            skills_used,
+        }
+    }
+
+    #[test]
+    fn roundtrip() {
+        let mut profile = Profile {
+            current_level: 42,
+            fullscreen: true,
+            ..Default::default()
+        };
+        profile.unlock(42);
+        profile.record(3, record(80, 100, 7));
+        profile.record(42, record(100, 5, 20));
+        profile
+            .keys
+            .insert("pause".to_string(), "P, \"Pause\"".to_string());
+
+        assert_eq!(parse_profile(&format_profile(&profile)).unwrap(), profile);
+    }
+
+    #[test]
+    fn defaults() {
+        assert_eq!(parse_profile("").unwrap(), Profile::default());
+        assert_eq!(
+            parse_profile("[unlocked]\ntricky = 5").unwrap().unlocked,
+            [1, 5, 1, 1]
+        );
+    }
+
+    #[test]
+    fn keys() {
+        let profile =
+            parse_profile("[keys]\nnext_level = \"Ctrl+N\"\nfast_forward = \"F\"").unwrap();
+
+        assert_eq!(profile.keys["next_level"], "Ctrl+N");
+        assert_eq!(profile.keys["fast_forward"], "F");
+        assert!(parse_profile("[keys]\npause = 1").is_err());
+    }
+
+    #[test]
+    fn errors_report_line() {
+        let error = parse_profile("current_level = 0\n\n[unlocked]\nfun = 31").unwrap_err();
+
+        assert!(matches!(
+            error.kind,
+            GameDataErrorKind::InvalidText { line: 4, .. }
+        ));
+    }
+
+    #[test]
+    fn locked_levels() {
+        let mut profile = Profile::default();
+
+        assert!(profile.is_unlocked(0));
+        assert!(profile.is_unlocked(30));
+        assert!(!profile.is_unlocked(1));
+
+        profile.unlock(35);
+        assert!(profile.is_unlocked(34));
+        assert!(profile.is_unlocked(35));
+        assert!(!profile.is_unlocked(36));
+        assert!(!profile.is_unlocked(2));
+
+        profile.lock_levels = false;
+        assert!(profile.is_unlocked(119));
+    }
+
+    #[test]
+    fn best_records() {
+        let mut profile = Profile::default();
+
+        assert!(profile.record(0, record(50, 60, 10)));
+        assert!(!profile.record(0, record(40, 200, 0)));
+        assert!(profile.record(0, record(50, 70, 10)));
+        assert!(profile.record(0, record(50, 70, 9)));
+        assert!(!profile.record(0, record(50, 70, 9)));
+
+        assert_eq!(profile.records[&0], record(50, 70, 9));
+    }
+}
@@ -1,11 +1,11 @@
-use anyhow::{Result, anyhow};
+use crate::game_data::file::error::{GameDataError, Result};
 
 pub fn read_byte<T: From<u8>>(buffer: &[u8], offset: usize) -> Result<(T, usize)> {
     Ok((
         T::from(
             *buffer
                 .get(offset)
-                .ok_or(anyhow!("offset {} out of bounds", offset))?,
+                .ok_or(GameDataError::out_of_bounds(offset))?,
         ),
         offset + 1,
     ))
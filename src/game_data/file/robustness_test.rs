@@ -0,0 +1,157 @@
+use crate::game_data::file::encoding::datfile;
+use crate::game_data::file::error::GameDataErrorKind;
+use crate::game_data::file::ground::{OBJECTS_PER_TILESET, ObjectInfo, TerrainInfo, parse_ground};
+use crate::game_data::file::level::{parse_level_file, parse_oddtable};
+use crate::game_data::file::main::parse_main;
+use crate::game_data::file::vgagr::parse_vgagr;
+use crate::game_data::file::vgaspec::parse_vgaspec;
+
+struct Random(u64);
+
+impl Random {
+    fn next(&mut self) -> u8 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+
+        (self.0 >> 56) as u8
+    }
+
+    fn bytes(&mut self, len: usize) -> Vec<u8> {
+        (0..len).map(|_| self.next()).collect()
+    }
+}
+
+fn compress_section(data: &[u8]) -> Vec<u8> {
+    datfile::encode_uncompressed(&[data.to_vec()]).expect("encode failed")
+}
+
+fn parse_all(data: &[u8]) {
+    let object_info: Vec<ObjectInfo> = vec![Default::default(); OBJECTS_PER_TILESET];
+    let terrain_info: Vec<TerrainInfo> = vec![Default::default(); 1];
+
+    let _ = datfile::parse(data);
+    let _ = parse_level_file(data);
+    let _ = parse_oddtable(data);
+    let _ = parse_main(data);
+    let _ = parse_vgaspec(data);
+    let _ = parse_vgagr(data, &object_info, &terrain_info);
+
+    if let Ok(ground) = parse_ground(data) {
+        let _ = parse_vgagr(data, &ground.object_info, &ground.terrain_info);
+    }
+}
+
+#[test]
+fn compressed_section_roundtrip() {
+    let mut random = Random(1);
+    let data = random.bytes(100);
+
+    let content = datfile::parse(&compress_section(&data)).expect("parse failed");
+
+    assert_eq!(content.sections[0].data, data);
+}
+
+#[test]
+fn random_data_does_not_panic() {
+    let mut random = Random(42);
+
+    for len in [0, 1, 9, 10, 11, 100, 1056, 2048, 4480] {
+        for _ in 0..50 {
+            parse_all(&random.bytes(len));
+        }
+    }
+}
+
+#[test]
+fn random_sections_do_not_panic() {
+    let mut random = Random(23);
+
+    for len in [0, 1, 24, 40, 100, 1056, 2048, 0x2000] {
+        for section_count in 1..=8 {
+            let mut data: Vec<u8> = Vec::new();
+
+            for _ in 0..section_count {
+                data.append(&mut compress_section(&random.bytes(len)));
+            }
+
+            parse_all(&data);
+        }
+    }
+
+    for _ in 0..200 {
+        let ground = random.bytes(1056);
+        let len = random.next() as usize * 16;
+
+        let vgagr = [
+            compress_section(&random.bytes(len)),
+            compress_section(&random.bytes(len)),
+        ]
+        .concat();
+
+        if let Ok(ground) = parse_ground(&ground) {
+            let _ = parse_vgagr(&vgagr, &ground.object_info, &ground.terrain_info);
+        }
+    }
+}
+
+#[test]
+fn corrupted_sections_do_not_panic() {
+    let mut random = Random(7);
+    let section = compress_section(&random.bytes(300));
+
+    for i in 0..section.len() {
+        for value in [0x00, 0x01, 0x7f, 0x80, 0xff] {
+            let mut data = section.clone();
+            data[i] = value;
+
+            parse_all(&data);
+            parse_all(&data[..i]);
+        }
+    }
+}
+
+#[test]
+fn checksum_mismatch_reports_section() {
+    let mut random = Random(3);
+
+    let mut data = [
+        compress_section(&random.bytes(10)),
+        compress_section(&random.bytes(10)),
+    ]
+    .concat();
+
+    let last = data.len() - 1;
+    data[last] ^= 0x01;
+
+    let err = datfile::parse(&data).err().expect("parse should fail");
+
+    assert_eq!(err.section, Some(1));
+    assert!(matches!(
+        err.kind,
+        GameDataErrorKind::ChecksumMismatch { .. }
+    ));
+}
+
+#[test]
+fn level_size_reports_section() {
+    let mut random = Random(5);
+
+    let data = [
+        compress_section(&random.bytes(2048)),
+        compress_section(&random.bytes(2047)),
+    ]
+    .concat();
+
+    let err = parse_level_file(&data).err().expect("parse should fail");
+
+    assert_eq!(err.section, Some(1));
+    assert!(matches!(
+        err.kind,
+        GameDataErrorKind::InvalidSize {
+            expected: 2048,
+            actual: 2047
+        }
+    ));
+}
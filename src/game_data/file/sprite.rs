@@ -1,6 +1,6 @@
 use std::fmt::Display;
 
-use anyhow::{Result, anyhow, bail};
+use crate::game_data::file::error::{GameDataError, GameDataErrorKind, Result};
 
 #[derive(Clone, Default)]
 pub struct Bitmap {
@@ -80,14 +80,18 @@ impl Bitmap {
         mapping: Option<T>,
     ) -> Result<Bitmap> {
         if bpp > 8 {
-            bail!("bad bpp {}", bpp);
+            return Err(GameDataError::new(GameDataErrorKind::InvalidBpp(bpp)));
         }
 
-        let pixel_count = (width * height) as usize;
-
-        if !pixel_count.is_multiple_of(8) {
-            bail!("bad dimensions {}x{}", width, height);
-        }
+        let pixel_count = match width.checked_mul(height) {
+            Some(pixel_count) if pixel_count.is_multiple_of(8) => pixel_count as usize,
+            _ => {
+                return Err(GameDataError::new(GameDataErrorKind::InvalidDimensions {
+                    width,
+                    height,
+                }));
+            }
+        };
 
         let plane_size = pixel_count / 8;
 
@@ -102,15 +106,11 @@ impl Bitmap {
             let base = plane_size * iplane;
 
             let mut i: usize = 0;
-            for y in 0..height {
-                for x in 0..width {
-                    let byte = *data.get(base + i / 8).ok_or(anyhow!(
-                        "read_planar: out of bounds {} {} {} {}",
-                        x,
-                        y,
-                        i,
-                        data.len()
-                    ))?;
+            for _ in 0..height {
+                for _ in 0..width {
+                    let byte = *data
+                        .get(base + i / 8)
+                        .ok_or(GameDataError::out_of_bounds(base + i / 8))?;
 
                     bitmap.data[i] |= ((byte >> (7 - (i % 8))) & 0x01) << iplane;
 
@@ -132,7 +132,8 @@ impl Bitmap {
 
         let effective_transparency_encoding = match transparency_encoding {
             TransparencyEncoding::PlanarOffset(offset) => TransparencyEncoding::PlanarAt(
-                data.get(offset..).ok_or(anyhow!("unable to obtain mask"))?,
+                data.get(offset..)
+                    .ok_or(GameDataError::out_of_bounds(offset))?,
             ),
             _ => transparency_encoding,
         };
@@ -153,7 +154,7 @@ impl Bitmap {
                     for _ in 0..width {
                         let byte = *transparency_data
                             .get(i / 8)
-                            .ok_or(anyhow!("read_planar: transparency: out of bounds"))?;
+                            .ok_or(GameDataError::out_of_bounds(i / 8))?;
 
                         bitmap.transparency[i] = ((byte >> (7 - (i % 8))) & 0x01) == 0x00;
                         i += 1;
@@ -166,9 +167,57 @@ impl Bitmap {
         Ok(bitmap)
     }
 
+    pub fn write_planar(&self, bpp: usize, target: &mut [u8]) -> Result<()> {
+        let plane_size = self.plane_size()?;
+
+        for iplane in 0..bpp {
+            let base = plane_size * iplane;
+
+            for (i, pixel) in self.data.iter().enumerate() {
+                let byte = target
+                    .get_mut(base + i / 8)
+                    .ok_or(GameDataError::out_of_bounds(base + i / 8))?;
+
+                *byte |= ((pixel >> iplane) & 0x01) << (7 - (i % 8));
+            }
+        }
+
+        Ok(())
+    }
+
+    // Writes a plane with bits set for opaque pixels
+    pub fn write_planar_mask(&self, target: &mut [u8]) -> Result<()> {
+        self.plane_size()?;
+
+        for (i, transparent) in self.transparency.iter().enumerate() {
+            let byte = target
+                .get_mut(i / 8)
+                .ok_or(GameDataError::out_of_bounds(i / 8))?;
+
+            if !transparent {
+                *byte |= 0x80 >> (i % 8);
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn plane_size(&self) -> Result<usize> {
+        match self.width.checked_mul(self.height) {
+            Some(pixel_count) if pixel_count.is_multiple_of(8) => Ok(pixel_count as usize / 8),
+            _ => Err(GameDataError::new(GameDataErrorKind::InvalidDimensions {
+                width: self.width,
+                height: self.height,
+            })),
+        }
+    }
+
     pub fn sub(&self, x: u32, y: u32, width: u32, height: u32) -> Result<Self> {
-        if x + width > self.width || y + height > self.height {
-            bail!("invalid dimensions");
+        if x.saturating_add(width) > self.width || y.saturating_add(height) > self.height {
+            return Err(GameDataError::new(GameDataErrorKind::InvalidDimensions {
+                width,
+                height,
+            }));
         }
 
         let size = (width * height) as usize;
@@ -246,7 +295,7 @@ impl Sprite {
                 height,
                 bpp,
                 data.get(base..base + frame_size)
-                    .ok_or(anyhow!("Sprite::read_planar: out of bounds"))?,
+                    .ok_or(GameDataError::out_of_bounds(base))?,
                 transparency_encoding,
             )?);
         }
@@ -256,6 +305,21 @@ impl Sprite {
         Ok(sprite)
     }
 
+    // All frames stacked vertically
+    pub fn sheet(&self) -> Bitmap {
+        let mut sheet = Bitmap::filled(self.width, self.height * self.frames.len() as u32, 0, true);
+
+        for (i, frame) in self.frames.iter().enumerate() {
+            let offset = i * frame.data.len();
+
+            sheet.data[offset..offset + frame.data.len()].copy_from_slice(&frame.data);
+            sheet.transparency[offset..offset + frame.data.len()]
+                .copy_from_slice(&frame.transparency);
+        }
+
+        sheet
+    }
+
     pub fn blank(width: u32, height: u32, capacity: usize) -> Self {
         Self {
             width,
@@ -266,7 +330,10 @@ impl Sprite {
 
     pub fn add_frame(&mut self, frame: &Bitmap) -> Result<()> {
         if self.width != frame.width || self.height != frame.height {
-            bail!("invalid dimensions");
+            return Err(GameDataError::new(GameDataErrorKind::InvalidDimensions {
+                width: frame.width,
+                height: frame.height,
+            }));
         }
 
         self.frames.push(frame.clone());
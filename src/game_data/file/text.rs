@@ -0,0 +1,73 @@
+// Helpers for the text formats, which are TOML documents read and written with serde. Errors
+// report the line of the offending key or value.
+
+use std::ops::Range;
+
+use serde::de::{DeserializeOwned, Error, Unexpected};
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::game_data::file::error::{GameDataError, GameDataErrorKind, Result};
+
+pub(crate) fn from_text<T: DeserializeOwned>(text: &str) -> Result<T> {
+    toml::from_str(text).map_err(|err| {
+        syntax_error(
+            err.span().map_or(1, |span| line_at(text, span)),
+            err.message().to_string(),
+        )
+    })
+}
+
+pub(crate) fn to_text<T: Serialize>(value: &T) -> String {
+    toml::to_string(value).expect("text formats only contain tables and plain values")
+}
+
+pub(crate) fn syntax_error(line: usize, reason: String) -> GameDataError {
+    GameDataError::new(GameDataErrorKind::InvalidText { line, reason })
+}
+
+pub(crate) fn line_at(text: &str, span: Range<usize>) -> usize {
+    text[..span.start.min(text.len())].matches('\n').count() + 1
+}
+
+// For `deserialize_with`: an integer between MIN and MAX
+pub(crate) fn integer<'de, D, T, const MIN: i64, const MAX: i64>(
+    deserializer: D,
+) -> std::result::Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: TryFrom<i64>,
+{
+    multiple::<D, T, 1, MIN, MAX>(deserializer)
+}
+
+// For `deserialize_with`: a multiple of STEP between MIN and MAX
+pub(crate) fn multiple<'de, D, T, const STEP: i64, const MIN: i64, const MAX: i64>(
+    deserializer: D,
+) -> std::result::Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: TryFrom<i64>,
+{
+    let value = i64::deserialize(deserializer)?;
+
+    if (MIN..=MAX).contains(&value) && value % STEP == 0 {
+        T::try_from(value).map_err(|_| D::Error::custom("integer out of range"))
+    } else if STEP == 1 {
+        Err(D::Error::invalid_value(
+            Unexpected::Signed(value),
+            &format!("an integer between {} and {}", MIN, MAX).as_str(),
+        ))
+    } else {
+        Err(D::Error::invalid_value(
+            Unexpected::Signed(value),
+            &format!("a multiple of {} between {} and {}", STEP, MIN, MAX).as_str(),
+        ))
+    }
+}
+
+// For `deserialize_with`: a 16 bit value stored in a wider field
+pub(crate) fn word<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<u32, D::Error> {
+    u16::deserialize(deserializer).map(u32::from)
+}
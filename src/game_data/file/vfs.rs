@@ -0,0 +1,184 @@
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, BufReader, Read, Seek};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use zip::ZipArchive;
+
+use crate::game_data::file::error::{ErrorLocation, GameDataError, Result};
+use crate::game_data::file::path::resolve_path;
+
+// Source of the game data files, addressed by their DOS file names
+pub trait Vfs: Send + Sync {
+    fn read(&self, name: &str) -> io::Result<Vec<u8>>;
+}
+
+// File names are matched case-insensitively
+pub struct DirectoryVfs {
+    path: PathBuf,
+}
+
+impl DirectoryVfs {
+    pub fn new(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+        }
+    }
+}
+
+impl Vfs for DirectoryVfs {
+    fn read(&self, name: &str) -> io::Result<Vec<u8>> {
+        fs::read(resolve_path(&self.path, name))
+    }
+}
+
+// Files are looked up by their name regardless of the directory they are in inside the archive,
+// and names are matched case-insensitively
+pub struct ZipVfs<R> {
+    archive: Mutex<ZipArchive<R>>,
+    entries: HashMap<String, usize>,
+}
+
+impl<R: Read + Seek> ZipVfs<R> {
+    pub fn new(reader: R) -> io::Result<Self> {
+        let archive = ZipArchive::new(reader)?;
+        let mut entries: HashMap<String, usize> = HashMap::new();
+
+        for index in 0..archive.len() {
+            let Some(name) = archive.name_for_index(index) else {
+                continue;
+            };
+
+            if name.ends_with('/') {
+                continue;
+            }
+
+            let file_name = name.rsplit(['/', '\\']).next().unwrap_or(name);
+            entries
+                .entry(file_name.to_ascii_lowercase())
+                .or_insert(index);
+        }
+
+        Ok(Self {
+            archive: Mutex::new(archive),
+            entries,
+        })
+    }
+}
+
+impl<R: Read + Seek + Send> Vfs for ZipVfs<R> {
+    fn read(&self, name: &str) -> io::Result<Vec<u8>> {
+        let index = *self
+            .entries
+            .get(&name.to_ascii_lowercase())
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, name.to_string()))?;
+
+        let mut archive = self
+            .archive
+            .lock()
+            .map_err(|_| io::Error::other("zip archive lock poisoned"))?;
+        let mut file = archive.by_index(index)?;
+
+        let mut data = Vec::with_capacity(file.size() as usize);
+        file.read_to_end(&mut data)?;
+
+        Ok(data)
+    }
+}
+
+// Files present in the overlay, e.g. the levels of a level pack, replace those of the base
+pub struct OverlayVfs {
+    overlay: Arc<dyn Vfs>,
+    base: Arc<dyn Vfs>,
+}
+
+impl OverlayVfs {
+    pub fn new(overlay: Arc<dyn Vfs>, base: Arc<dyn Vfs>) -> Self {
+        Self { overlay, base }
+    }
+}
+
+impl Vfs for OverlayVfs {
+    fn read(&self, name: &str) -> io::Result<Vec<u8>> {
+        match self.overlay.read(name) {
+            Err(err) if err.kind() == io::ErrorKind::NotFound => self.base.read(name),
+            result => result,
+        }
+    }
+}
+
+// A directory, or a zip archive if the path points to a file
+pub fn open_vfs(path: &Path) -> Result<Arc<dyn Vfs>> {
+    if path.is_file() {
+        let location = path.display().to_string();
+        let file = File::open(path)
+            .map_err(GameDataError::from)
+            .in_file(&location)?;
+
+        let vfs = ZipVfs::new(BufReader::new(file))
+            .map_err(GameDataError::from)
+            .in_file(&location)?;
+
+        Ok(Arc::new(vfs))
+    } else {
+        Ok(Arc::new(DirectoryVfs::new(path)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::{Cursor, ErrorKind, Write};
+    use std::sync::Arc;
+
+    use zip::{ZipWriter, write::SimpleFileOptions};
+
+    use crate::game_data::file::vfs::{OverlayVfs, Vfs, ZipVfs};
+
+    fn zip_vfs(files: &[(&str, &[u8])]) -> ZipVfs<Cursor<Vec<u8>>> {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        let options = SimpleFileOptions::default();
+
+        for (name, data) in files {
+            writer.start_file(*name, options).unwrap();
+            writer.write_all(data).unwrap();
+        }
+
+        ZipVfs::new(writer.finish().unwrap()).expect("invalid archive")
+    }
+
+    #[test]
+    fn overlay_lookup() {
+        let base = zip_vfs(&[("LEVEL000.DAT", b"original"), ("MAIN.DAT", b"main")]);
+        let pack = zip_vfs(&[("level000.dat", b"replaced")]);
+        let vfs = OverlayVfs::new(Arc::new(pack), Arc::new(base));
+
+        assert_eq!(vfs.read("LEVEL000.DAT").unwrap(), b"replaced");
+        assert_eq!(vfs.read("MAIN.DAT").unwrap(), b"main");
+        assert_eq!(
+            vfs.read("LEVEL001.DAT").unwrap_err().kind(),
+            ErrorKind::NotFound
+        );
+    }
+
+    #[test]
+    fn zip_lookup() {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        let options = SimpleFileOptions::default();
+
+        writer.add_directory("LEMMINGS/", options).unwrap();
+        writer.start_file("LEMMINGS/LEVEL000.DAT", options).unwrap();
+        writer.write_all(b"level").unwrap();
+        writer.start_file("main.dat", options).unwrap();
+        writer.write_all(b"main").unwrap();
+
+        let vfs = ZipVfs::new(writer.finish().unwrap()).expect("invalid archive");
+
+        assert_eq!(vfs.read("level000.dat").unwrap(), b"level");
+        assert_eq!(vfs.read("MAIN.DAT").unwrap(), b"main");
+        assert_eq!(
+            vfs.read("level001.dat").unwrap_err().kind(),
+            ErrorKind::NotFound
+        );
+    }
+}
@@ -1,12 +1,10 @@
-use std::{fs, path::Path};
-
-use anyhow::{Result, anyhow, bail};
-
 use crate::game_data::file::encoding::datfile;
+use crate::game_data::file::error::{ErrorLocation, GameDataError, GameDataErrorKind, Result};
 use crate::game_data::file::ground::{
     OBJECTS_PER_TILESET, ObjectInfo, TILES_PER_TILESET, TerrainInfo,
 };
 use crate::game_data::file::sprite::{Bitmap, Sprite, TransparencyEncoding};
+use crate::game_data::file::vfs::Vfs;
 
 pub struct Content {
     pub object_sprites: [Option<Sprite>; OBJECTS_PER_TILESET],
@@ -14,7 +12,7 @@ pub struct Content {
 }
 
 pub fn read_vgagr(
-    path: &Path,
+    vfs: &dyn Vfs,
     index: usize,
     object_info: &[ObjectInfo],
     terrain_info: &[TerrainInfo],
@@ -22,11 +20,25 @@ pub fn read_vgagr(
     let filename = format!("vgagr{}.dat", index);
     println!("reading {}", &filename);
 
-    let data = fs::read(path.join(&filename).as_os_str())?;
+    let data = vfs
+        .read(&filename)
+        .map_err(GameDataError::from)
+        .in_file(&filename)?;
 
-    let datfile::Content { sections } = datfile::parse(&data)?;
+    parse_vgagr(&data, object_info, terrain_info).in_file(&filename)
+}
+
+pub fn parse_vgagr(
+    data: &[u8],
+    object_info: &[ObjectInfo],
+    terrain_info: &[TerrainInfo],
+) -> Result<Content> {
+    let datfile::Content { sections } = datfile::parse(data)?;
     if sections.len() != 2 {
-        bail!("bad tileset {}", filename);
+        return Err(GameDataError::new(GameDataErrorKind::InvalidSectionCount {
+            expected: 2,
+            actual: sections.len(),
+        }));
     }
 
     let object_data = &sections[1].data;
@@ -35,24 +47,25 @@ pub fn read_vgagr(
     let mut object_sprites: [Option<Sprite>; OBJECTS_PER_TILESET] =
         [SPRITE_NONE; OBJECTS_PER_TILESET];
 
-    for i in 0..OBJECTS_PER_TILESET {
-        let info = &object_info[i];
-
-        if info.width * info.height == 0 {
+    for (i, info) in object_info.iter().take(OBJECTS_PER_TILESET).enumerate() {
+        if info.width == 0 || info.height == 0 {
             continue;
         }
 
         let mut offset = info.frames_offset;
-        object_sprites[i] = Some(Sprite::read_planar(
-            info.animation_end,
-            info.width,
-            info.height,
-            4,
-            object_data,
-            &mut offset,
-            info.animation_frame_size,
-            TransparencyEncoding::PlanarOffset(info.mask_offset),
-        )?);
+        object_sprites[i] = Some(
+            Sprite::read_planar(
+                info.animation_end,
+                info.width,
+                info.height,
+                4,
+                object_data,
+                &mut offset,
+                info.animation_frame_size,
+                TransparencyEncoding::PlanarOffset(info.mask_offset),
+            )
+            .in_section(1)?,
+        );
     }
 
     let tileset_data = &sections[0].data;
@@ -60,26 +73,12 @@ pub fn read_vgagr(
     const BITMAP_NONE: Option<Bitmap> = None;
     let mut tiles: [Option<Bitmap>; TILES_PER_TILESET] = [BITMAP_NONE; TILES_PER_TILESET];
 
-    for i in 0..TILES_PER_TILESET {
-        let info = &terrain_info[i];
-
-        if info.width * info.height == 0 {
+    for (i, info) in terrain_info.iter().take(TILES_PER_TILESET).enumerate() {
+        if info.width == 0 || info.height == 0 {
             continue;
         }
 
-        tiles[i] = Some(Bitmap::read_planar(
-            info.width,
-            info.height,
-            4,
-            tileset_data
-                .get(info.image_offset..)
-                .ok_or(anyhow!("tile data out of bounds"))?,
-            TransparencyEncoding::PlanarAt(
-                tileset_data
-                    .get(info.mask_offset..)
-                    .ok_or(anyhow!("tile data out of bounds"))?,
-            ),
-        )?);
+        tiles[i] = Some(read_tile(tileset_data, info).in_section(0)?);
     }
 
     Ok(Content {
@@ -87,3 +86,264 @@ pub fn read_vgagr(
         tiles,
     })
 }
+
+// Assign the offsets of all tiles and object frames in sequence. Frames are stored as
+// four color planes followed by the mask.
+pub fn layout_vgagr(object_info: &mut [ObjectInfo], terrain_info: &mut [TerrainInfo]) {
+    let mut offset = 0;
+    for info in terrain_info.iter_mut().take(TILES_PER_TILESET) {
+        if info.width == 0 || info.height == 0 {
+            continue;
+        }
+
+        let plane_size = (info.width * info.height) as usize / 8;
+
+        info.image_offset = offset;
+        info.mask_offset = offset + 4 * plane_size;
+        offset += 5 * plane_size;
+    }
+
+    let mut offset = 0;
+    for info in object_info.iter_mut().take(OBJECTS_PER_TILESET) {
+        if info.width == 0 || info.height == 0 {
+            continue;
+        }
+
+        let plane_size = (info.width * info.height) as usize / 8;
+        let preview_frame = info.preview_frame();
+
+        info.animation_frame_size = 5 * plane_size;
+        info.mask_offset = 4 * plane_size;
+        info.frames_offset = offset;
+        info.preview_frame_offset = offset + preview_frame * info.animation_frame_size;
+        offset += info.animation_end * info.animation_frame_size;
+    }
+}
+
+pub fn encode_vgagr(
+    object_info: &[ObjectInfo],
+    terrain_info: &[TerrainInfo],
+    object_sprites: &[Option<Sprite>],
+    tiles: &[Option<Bitmap>],
+) -> Result<Vec<u8>> {
+    let mut tileset_data: Vec<u8> = Vec::new();
+
+    for (i, info) in terrain_info.iter().take(TILES_PER_TILESET).enumerate() {
+        if info.width == 0 || info.height == 0 {
+            continue;
+        }
+
+        let tile = tiles
+            .get(i)
+            .and_then(|tile| tile.as_ref())
+            .filter(|tile| tile.width == info.width && tile.height == info.height)
+            .ok_or(GameDataError::malformed("tile does not match terrain info"))?;
+
+        let plane_size = tile.plane_size()?;
+        let end = (info.image_offset + 4 * plane_size).max(info.mask_offset + plane_size);
+        if tileset_data.len() < end {
+            tileset_data.resize(end, 0);
+        }
+
+        tile.write_planar(4, &mut tileset_data[info.image_offset..])?;
+        tile.write_planar_mask(&mut tileset_data[info.mask_offset..])?;
+    }
+
+    let mut object_data: Vec<u8> = Vec::new();
+
+    for (i, info) in object_info.iter().take(OBJECTS_PER_TILESET).enumerate() {
+        if info.width == 0 || info.height == 0 {
+            continue;
+        }
+
+        let sprite = object_sprites
+            .get(i)
+            .and_then(|sprite| sprite.as_ref())
+            .filter(|sprite| {
+                sprite.width == info.width
+                    && sprite.height == info.height
+                    && sprite.frames.len() >= info.animation_end
+            })
+            .ok_or(GameDataError::malformed(
+                "object does not match object info",
+            ))?;
+
+        for (iframe, frame) in sprite.frames.iter().take(info.animation_end).enumerate() {
+            let plane_size = frame.plane_size()?;
+            let base = info.frames_offset + iframe * info.animation_frame_size;
+            let end = (base + 4 * plane_size).max(base + info.mask_offset + plane_size);
+            if object_data.len() < end {
+                object_data.resize(end, 0);
+            }
+
+            frame.write_planar(4, &mut object_data[base..])?;
+            frame.write_planar_mask(&mut object_data[base + info.mask_offset..])?;
+        }
+    }
+
+    datfile::encode(&[tileset_data, object_data])
+}
+
+fn read_tile(tileset_data: &[u8], info: &TerrainInfo) -> Result<Bitmap> {
+    Bitmap::read_planar(
+        info.width,
+        info.height,
+        4,
+        tileset_data
+            .get(info.image_offset..)
+            .ok_or(GameDataError::out_of_bounds(info.image_offset))?,
+        TransparencyEncoding::PlanarAt(
+            tileset_data
+                .get(info.mask_offset..)
+                .ok_or(GameDataError::out_of_bounds(info.mask_offset))?,
+        ),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use crate::game_data::file::ground::{
+        self, ObjectInfo, Palettes, TerrainInfo, encode_ground, parse_ground,
+    };
+    use crate::game_data::file::sprite::{Bitmap, Sprite};
+    use crate::game_data::file::vgagr::{encode_vgagr, layout_vgagr, parse_vgagr};
+
+    fn pattern(width: u32, height: u32, seed: usize) -> Bitmap {
+        let mut bitmap = Bitmap::filled(width, height, 0, false);
+
+        for i in 0..bitmap.data.len() {
+            bitmap.data[i] = ((i * 7 + seed) % 16) as u8;
+            bitmap.transparency[i] = (i + seed).is_multiple_of(5);
+        }
+
+        bitmap
+    }
+
+    #[test]
+    fn tileset_roundtrip() {
+        let mut object_info: Vec<ObjectInfo> = vec![Default::default(); 16];
+        let mut terrain_info: Vec<TerrainInfo> = vec![Default::default(); 64];
+        let mut object_sprites: Vec<Option<Sprite>> = vec![None; 16];
+        let mut tiles: Vec<Option<Bitmap>> = vec![None; 64];
+
+        for (i, (width, height)) in [(16, 8), (32, 13), (8, 1)].into_iter().enumerate() {
+            terrain_info[2 * i] = TerrainInfo {
+                width,
+                height,
+                ..Default::default()
+            };
+            tiles[2 * i] = Some(pattern(width, height, i));
+        }
+
+        let mut sprite = Sprite::blank(24, 10, 3);
+        for i in 0..3 {
+            sprite.add_frame(&pattern(24, 10, i)).unwrap();
+        }
+
+        object_info[3] = ObjectInfo {
+            width: 24,
+            height: 10,
+            animation_end: 3,
+            animation_frame_size: 1,
+            preview_frame_offset: 2,
+            trigger_left: 8,
+            trigger_top: -4,
+            trigger_width: 12,
+            trigger_height: 4,
+            trigger_effect: 1,
+            ..Default::default()
+        };
+        object_sprites[3] = Some(sprite);
+
+        layout_vgagr(&mut object_info, &mut terrain_info);
+
+        let vgagr = encode_vgagr(&object_info, &terrain_info, &object_sprites, &tiles)
+            .expect("encode failed");
+        let ground = encode_ground(&ground::Content {
+            object_info: object_info.clone().try_into().ok().expect("invalid count"),
+            terrain_info: terrain_info.clone().try_into().ok().expect("invalid count"),
+            palettes: Palettes::default(),
+        })
+        .expect("encode failed");
+
+        assert_eq!(ground.len(), 1056);
+
+        let ground = parse_ground(&ground).expect("parse failed");
+        let content =
+            parse_vgagr(&vgagr, &ground.object_info, &ground.terrain_info).expect("parse failed");
+
+        for (decoded, original) in content.tiles.iter().zip(&tiles) {
+            assert_eq!(
+                decoded
+                    .as_ref()
+                    .map(|tile| (&tile.data, &tile.transparency)),
+                original
+                    .as_ref()
+                    .map(|tile| (&tile.data, &tile.transparency))
+            );
+        }
+
+        let decoded = content.object_sprites[3].as_ref().expect("object missing");
+        let original = object_sprites[3].as_ref().unwrap();
+        for (decoded, original) in decoded.frames.iter().zip(&original.frames) {
+            assert_eq!(decoded.data, original.data);
+            assert_eq!(decoded.transparency, original.transparency);
+        }
+
+        let info = &ground.object_info[3];
+        assert_eq!(info.preview_frame(), 2);
+        assert_eq!(
+            (
+                info.trigger_left,
+                info.trigger_top,
+                info.trigger_width,
+                info.trigger_height
+            ),
+            (8, -4, 12, 4)
+        );
+    }
+
+    #[test]
+    fn ground_rejects_unencodable_values() {
+        let content = |info: ObjectInfo| ground::Content {
+            object_info: std::array::from_fn(|i| {
+                if i == 1 {
+                    info.clone()
+                } else {
+                    Default::default()
+                }
+            }),
+            terrain_info: std::array::from_fn(|_| Default::default()),
+            palettes: Palettes::default(),
+        };
+
+        for info in [
+            ObjectInfo {
+                trigger_top: -8,
+                ..Default::default()
+            },
+            ObjectInfo {
+                trigger_left: 2,
+                ..Default::default()
+            },
+            ObjectInfo {
+                trigger_width: 1024,
+                ..Default::default()
+            },
+            ObjectInfo {
+                width: 256,
+                ..Default::default()
+            },
+        ] {
+            let err = encode_ground(&content(info)).expect_err("encode succeeded");
+            assert_eq!(err.offset, Some(28));
+        }
+
+        let info = ObjectInfo {
+            trigger_top: -4,
+            trigger_left: 4,
+            ..Default::default()
+        };
+        assert!(encode_ground(&content(info)).is_ok());
+    }
+}
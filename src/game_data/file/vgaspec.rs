@@ -1,13 +1,11 @@
-use std::{fs, path::Path};
-
-use anyhow::{Result, anyhow, bail};
-
 use crate::game_data::file::encoding;
+use crate::game_data::file::error::{ErrorLocation, GameDataError, GameDataErrorKind, Result};
 use crate::game_data::file::palette::{
     LOWER_PALETTE_FIXED, PALETTE_SIZE, PaletteEntry, expand_rgb6_to8, read_palette_entry,
 };
 use crate::game_data::file::read::read_byte;
 use crate::game_data::file::sprite::{Bitmap, TransparencyEncoding};
+use crate::game_data::file::vfs::Vfs;
 
 const SECTION_SIZE: usize = 14400;
 const VGASPEC_BITMAP_WIDTH: u32 = 960;
@@ -20,13 +18,16 @@ pub struct Content {
     pub bitmap: Bitmap,
 }
 
-pub fn read_vgaspec(path: &Path, index: usize) -> Result<Content> {
+pub fn read_vgaspec(vfs: &dyn Vfs, index: usize) -> Result<Content> {
     let filename = format!("vgaspec{}.dat", index);
     println!("reading {}", &filename);
 
-    let data = fs::read(path.join(&filename).as_os_str())?;
+    let data = vfs
+        .read(&filename)
+        .map_err(GameDataError::from)
+        .in_file(&filename)?;
 
-    read_compressed_data(&data)
+    parse_vgaspec(&data).in_file(&filename)
 }
 
 fn read8(data: &[u8], offset: usize) -> Result<u8> {
@@ -45,10 +46,13 @@ fn read_bitmap_section(src: &[u8], index: usize, dest: &mut [u8]) -> Result<usiz
             let len = block_tag as usize + 1;
 
             dest.get_mut(i_dest..i_dest + len)
-                .ok_or(anyhow!("read vgaspec section: section overflow"))?
+                .ok_or(GameDataError::at(
+                    GameDataErrorKind::Malformed("vgaspec bitmap overflow"),
+                    i_source,
+                ))?
                 .copy_from_slice(
                     src.get(i_source..i_source + len)
-                        .ok_or(anyhow!("read vgaspec section: source overflow"))?,
+                        .ok_or(GameDataError::out_of_bounds(i_source))?,
                 );
 
             i_source += len;
@@ -61,7 +65,10 @@ fn read_bitmap_section(src: &[u8], index: usize, dest: &mut [u8]) -> Result<usiz
             i_source += 1;
 
             dest.get_mut(i_dest..i_dest + len)
-                .ok_or(anyhow!("read vgaspec section: section overflow"))?
+                .ok_or(GameDataError::at(
+                    GameDataErrorKind::Malformed("vgaspec bitmap overflow"),
+                    i_source,
+                ))?
                 .fill(byte);
 
             i_dest += len;
@@ -71,18 +78,29 @@ fn read_bitmap_section(src: &[u8], index: usize, dest: &mut [u8]) -> Result<usiz
     if i_dest == SECTION_SIZE {
         Ok(i_source)
     } else {
-        bail!("invalid vgaspec section");
+        Err(GameDataError::at(
+            GameDataErrorKind::InvalidSize {
+                expected: SECTION_SIZE,
+                actual: i_dest,
+            },
+            i_source,
+        ))
     }
 }
 
-fn read_compressed_data(compressed_data: &[u8]) -> Result<Content> {
+pub fn parse_vgaspec(compressed_data: &[u8]) -> Result<Content> {
     let decompressed_sections = encoding::datfile::parse(compressed_data)?;
     if decompressed_sections.sections.len() != 1 {
-        bail!("vgaspec: too man sections");
+        return Err(GameDataError::new(GameDataErrorKind::InvalidSectionCount {
+            expected: 1,
+            actual: decompressed_sections.sections.len(),
+        }));
     }
 
-    let data = &decompressed_sections.sections[0].data;
+    decode_vgaspec(&decompressed_sections.sections[0].data).in_section(0)
+}
 
+fn decode_vgaspec(data: &[u8]) -> Result<Content> {
     let mut palette: [PaletteEntry; PALETTE_SIZE] = [(0, 0, 0); 16];
 
     for i in 0..8 {
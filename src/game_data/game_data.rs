@@ -1,15 +1,28 @@
+use std::sync::{Arc, OnceLock};
+
 use anyhow::{Result, anyhow, bail};
 
+pub use crate::game_data::file::error::{GameDataError, GameDataErrorKind};
+use crate::game_data::file::ground::InteractionType;
 pub use crate::game_data::file::ground::{
     OBJECTS_PER_TILESET, ObjectInfo, Palettes, TILES_PER_TILESET, TerrainInfo,
 };
 pub use crate::game_data::file::level::{
-    Level, LevelParameters, LevelStructure, Object, TerrainTile,
+    Level, LevelParameters, LevelStructure, Object, SteelArea, TerrainTile, encode_level,
+    encode_level_file,
+};
+pub use crate::game_data::file::level_text::{format_level, parse_level};
+pub use crate::game_data::file::main::{
+    FONT_MENU_SIZE, MainMenu, Masks, NUM_COUNTDOWN_DIGITS, NUM_DIFFICULTY_SIGNS,
+    NUM_LEMMING_SPRITES, resolve_menu_font_index,
 };
-pub use crate::game_data::file::main::NUM_LEMMING_SPRITES;
+pub use crate::game_data::file::nxlv::format_nxlv;
 use crate::game_data::file::palette::{LOWER_PALETTE_FIXED, UPPER_PALETTE_SKILL_PANEL};
 pub use crate::game_data::file::palette::{PALETTE_SIZE, PaletteEntry};
+pub use crate::game_data::file::path::resolve_path;
 pub use crate::game_data::file::sprite::{Bitmap, Sprite};
+pub use crate::game_data::file::vfs::{DirectoryVfs, OverlayVfs, Vfs, ZipVfs, open_vfs};
+use crate::game_data::reader::{read_special_background, read_tileset};
 use crate::game_data::{Cursors, LEVEL_HEIGHT, LEVEL_WIDTH, SkillPanel, VGASPEC_POSITION};
 
 const LEVEL_TABLE: [u8; 120] = [
@@ -39,19 +52,56 @@ pub struct TileSet {
     pub tiles: [Option<Bitmap>; TILES_PER_TILESET],
 }
 
+// Tilesets and special backgrounds are decoded on first use if the game data was read lazily
 #[derive(Clone)]
 pub struct GameData {
+    pub(crate) vfs: Arc<dyn Vfs>,
     pub levels: Vec<Level>,
     pub oddtable: Vec<LevelParameters>,
-    pub tilesets: Vec<TileSet>,
-    pub special_backgrounds: Vec<Image>,
+    pub(crate) tilesets: Vec<OnceLock<TileSet>>,
+    pub(crate) special_backgrounds: Vec<OnceLock<Image>>,
     pub skill_panel: SkillPanel,
     pub lemming_sprites: [Sprite; NUM_LEMMING_SPRITES],
+    pub masks: Masks,
+    pub countdown_digits: Sprite,
+    pub main_menu: MainMenu,
     pub static_palette: [PaletteEntry; PALETTE_SIZE],
     pub cursors: Cursors,
 }
 
 impl GameData {
+    pub fn tileset(&self, index: usize) -> Result<&TileSet> {
+        let cell = self
+            .tilesets
+            .get(index)
+            .ok_or(anyhow!("invalid tileset {}", index))?;
+
+        if let Some(tileset) = cell.get() {
+            return Ok(tileset);
+        }
+
+        let tileset = read_tileset(self.vfs.as_ref(), index)?;
+        Ok(cell.get_or_init(|| tileset))
+    }
+
+    pub fn special_background(&self, index: usize) -> Result<&Image> {
+        let cell = self
+            .special_backgrounds
+            .get(index)
+            .ok_or(anyhow!("invalid special background {}", index))?;
+
+        if let Some(special_background) = cell.get() {
+            return Ok(special_background);
+        }
+
+        let special_background = read_special_background(self.vfs.as_ref(), index)?;
+        Ok(cell.get_or_init(|| special_background))
+    }
+
+    pub fn num_tilesets(&self) -> usize {
+        self.tilesets.len()
+    }
+
     pub fn resolve_level(&self, index: usize) -> Result<Level> {
         if index >= LEVEL_TABLE.len() {
             bail!("no level with index {}", index);
@@ -82,45 +132,36 @@ impl GameData {
     }
 
     pub fn resolve_object(&self, object_id: usize, tileset_id: usize) -> Result<&ObjectInfo> {
-        self.tilesets
-            .get(tileset_id)
-            .ok_or(anyhow!("invalid tileset {}", tileset_id))?
+        self.tileset(tileset_id)?
             .object_info
             .get(object_id)
             .ok_or(anyhow!("invalid object ID {}", object_id))
     }
 
-    pub fn resolve_skill_panel_palette(&self, tileset: usize) -> [PaletteEntry; PALETTE_SIZE] {
+    pub fn resolve_skill_panel_palette(
+        &self,
+        tileset: usize,
+    ) -> Result<[PaletteEntry; PALETTE_SIZE]> {
         let mut palette: [PaletteEntry; PALETTE_SIZE] = [(0, 0, 0); PALETTE_SIZE];
+        let custom = self.tileset(tileset)?.palettes.custom;
 
         for i in 0..PALETTE_SIZE {
             palette[i] = match i {
                 0..7 => LOWER_PALETTE_FIXED[i],
-                7 => self
-                    .tilesets
-                    .get(tileset)
-                    .map(|tileset| tileset.palettes.custom[i])
-                    .unwrap_or((0, 0, 0)),
+                7 => custom[i],
                 8.. => UPPER_PALETTE_SKILL_PANEL[i - 8],
             };
         }
 
-        palette
+        Ok(palette)
     }
 
     pub fn resolve_palette(&self, level: &Level) -> Result<[PaletteEntry; PALETTE_SIZE]> {
         if level.extended_graphics_set > 0 {
-            self.special_backgrounds
-                .get(level.extended_graphics_set as usize - 1)
-                .ok_or(anyhow!(
-                    "invalid extended graphics set {}",
-                    level.extended_graphics_set
-                ))
+            self.special_background(level.extended_graphics_set as usize - 1)
                 .map(|x| x.palette)
         } else {
-            self.tilesets
-                .get(level.graphics_set as usize)
-                .ok_or(anyhow!("invalid graphics set {}", level.graphics_set))
+            self.tileset(level.graphics_set as usize)
                 .map(|x| x.palettes.custom)
         }
     }
@@ -131,13 +172,8 @@ impl GameData {
         let mut transparency: Vec<bool> = vec![true; level_size];
 
         if level.extended_graphics_set > 0 {
-            let special_background = self
-                .special_backgrounds
-                .get(level.extended_graphics_set as usize - 1)
-                .ok_or(anyhow!(
-                    "bad extended graphics set {}",
-                    level.extended_graphics_set
-                ))?;
+            let special_background =
+                self.special_background(level.extended_graphics_set as usize - 1)?;
 
             for y in 0..special_background.bitmap.height {
                 for x in 0..special_background.bitmap.width {
@@ -157,9 +193,7 @@ impl GameData {
 
         for tile in &level.terrain_tiles {
             let bitmap_optional = self
-                .tilesets
-                .get(level.graphics_set as usize)
-                .ok_or(anyhow!("bad graphics set {}", level.graphics_set))?
+                .tileset(level.graphics_set as usize)?
                 .tiles
                 .get(tile.id as usize)
                 .and_then(|x| x.as_ref());
@@ -179,6 +213,96 @@ impl GameData {
             transparency,
         })
     }
+
+    // Terrain plus all objects in the state they are in when the level starts
+    pub fn compose_level(&self, level: &Level) -> Result<Bitmap> {
+        let mut terrain = self.compose_terrain(level)?;
+        let tileset = self.tileset(level.graphics_set as usize)?;
+
+        let start_frame = |object: &Object| -> Option<&Bitmap> {
+            let info = tileset.object_info.get(object.id as usize)?;
+            let sprite = tileset.object_sprites.get(object.id as usize)?.as_ref()?;
+
+            let index = if info.interaction_type == InteractionType::Entrance {
+                info.animation_start
+            } else {
+                0
+            };
+
+            sprite.frames.get(index)
+        };
+
+        for object in level.objects.iter().filter(|o| o.draw_only_over_terrain) {
+            if let Some(frame) = start_frame(object) {
+                compose_object(object, frame, &mut terrain, true);
+            }
+        }
+
+        let mut level_bitmap = Bitmap::filled(LEVEL_WIDTH, LEVEL_HEIGHT, 0, true);
+
+        for object in level
+            .objects
+            .iter()
+            .filter(|o| o.do_not_overwrite && !o.draw_only_over_terrain)
+        {
+            if let Some(frame) = start_frame(object) {
+                compose_object(object, frame, &mut level_bitmap, false);
+            }
+        }
+
+        for (i, transparent) in terrain.transparency.iter().enumerate() {
+            if !transparent {
+                level_bitmap.data[i] = terrain.data[i];
+                level_bitmap.transparency[i] = false;
+            }
+        }
+
+        for object in level
+            .objects
+            .iter()
+            .filter(|o| !o.do_not_overwrite && !o.draw_only_over_terrain)
+        {
+            if let Some(frame) = start_frame(object) {
+                compose_object(object, frame, &mut level_bitmap, false);
+            }
+        }
+
+        Ok(level_bitmap)
+    }
+}
+
+fn compose_object(object: &Object, frame: &Bitmap, target: &mut Bitmap, only_over_opaque: bool) {
+    for y in 0..frame.height {
+        for x in 0..frame.width {
+            let y_transformed = if object.flip_y {
+                frame.height - 1 - y
+            } else {
+                y
+            };
+
+            let x_dest = object.x + x as i32;
+            let y_dest = object.y + y as i32;
+            if x_dest < 0
+                || x_dest >= target.width as i32
+                || y_dest < 0
+                || y_dest >= target.height as i32
+            {
+                continue;
+            }
+
+            let src_index = (y_transformed * frame.width + x) as usize;
+            let dest_index = (y_dest * target.width as i32 + x_dest) as usize;
+
+            if frame.transparency[src_index]
+                || (only_over_opaque && target.transparency[dest_index])
+            {
+                continue;
+            }
+
+            target.data[dest_index] = frame.data[src_index];
+            target.transparency[dest_index] = false;
+        }
+    }
 }
 
 fn compose_tile_onto_background(
@@ -0,0 +1,148 @@
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter},
+    path::Path,
+};
+
+use anyhow::{Context, Result, bail};
+use png::{BitDepth, ColorType, Decoder, Encoder};
+
+use crate::game_data::file::palette::{PALETTE_SIZE, PaletteEntry};
+use crate::game_data::file::sprite::Bitmap;
+
+// Palette index used for transparent pixels in exported images
+pub const TRANSPARENT_INDEX: u8 = PALETTE_SIZE as u8;
+
+pub struct IndexedImage {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+    pub palette: Vec<PaletteEntry>,
+    pub transparent: Vec<bool>,
+}
+
+pub fn read_indexed_png(path: &Path) -> Result<IndexedImage> {
+    let file = File::open(path).with_context(|| format!("failed to open {:?}", path))?;
+    let mut reader = Decoder::new(BufReader::new(file))
+        .read_info()
+        .with_context(|| format!("failed to decode {:?}", path))?;
+
+    let info = reader.info();
+    if info.color_type != ColorType::Indexed {
+        bail!("{:?} is not an indexed PNG", path);
+    }
+
+    let bit_depth = match info.bit_depth {
+        BitDepth::One => 1,
+        BitDepth::Two => 2,
+        BitDepth::Four => 4,
+        BitDepth::Eight => 8,
+        BitDepth::Sixteen => bail!("{:?}: invalid bit depth", path),
+    };
+
+    let palette: Vec<PaletteEntry> = info
+        .palette
+        .as_deref()
+        .unwrap_or_default()
+        .chunks_exact(3)
+        .map(|rgb| (rgb[0], rgb[1], rgb[2]))
+        .collect();
+
+    let trns = info.trns.as_deref().unwrap_or_default();
+    let transparent: Vec<bool> = (0..palette.len())
+        .map(|i| trns.get(i) == Some(&0))
+        .collect();
+
+    let mut buffer = vec![
+        0;
+        reader
+            .output_buffer_size()
+            .ok_or_else(|| anyhow::anyhow!("{:?} is too large", path))?
+    ];
+    let frame = reader
+        .next_frame(&mut buffer)
+        .with_context(|| format!("failed to decode {:?}", path))?;
+
+    let mut pixels: Vec<u8> = Vec::with_capacity((frame.width * frame.height) as usize);
+    for y in 0..frame.height as usize {
+        let line = &buffer[y * frame.line_size..(y + 1) * frame.line_size];
+
+        for x in 0..frame.width as usize {
+            let bit = x * bit_depth;
+            let byte = line[bit / 8];
+
+            pixels.push((byte >> (8 - bit_depth - bit % 8)) & ((1u16 << bit_depth) - 1) as u8);
+        }
+    }
+
+    Ok(IndexedImage {
+        width: frame.width,
+        height: frame.height,
+        pixels,
+        palette,
+        transparent,
+    })
+}
+
+pub fn write_indexed_png(
+    path: &Path,
+    bitmap: &Bitmap,
+    palette: &[PaletteEntry; PALETTE_SIZE],
+) -> Result<()> {
+    let file = File::create(path).with_context(|| format!("failed to create {:?}", path))?;
+    let mut encoder = Encoder::new(BufWriter::new(file), bitmap.width, bitmap.height);
+
+    let mut png_palette: Vec<u8> = palette.iter().flat_map(|(r, g, b)| [*r, *g, *b]).collect();
+    png_palette.extend_from_slice(&[0, 0, 0]);
+
+    let mut trns = vec![0xff; PALETTE_SIZE];
+    trns.push(0);
+
+    encoder.set_color(ColorType::Indexed);
+    encoder.set_depth(BitDepth::Eight);
+    encoder.set_palette(png_palette);
+    encoder.set_trns(trns);
+
+    let pixels: Vec<u8> = bitmap
+        .data
+        .iter()
+        .zip(&bitmap.transparency)
+        .map(|(pixel, transparent)| {
+            if *transparent {
+                TRANSPARENT_INDEX
+            } else {
+                *pixel
+            }
+        })
+        .collect();
+
+    let mut writer = encoder
+        .write_header()
+        .with_context(|| format!("failed to write {:?}", path))?;
+    writer
+        .write_image_data(&pixels)
+        .with_context(|| format!("failed to write {:?}", path))?;
+
+    Ok(())
+}
+
+impl IndexedImage {
+    // Transparent pixels are stored as color 0 unless their index is a valid color
+    pub fn to_bitmap(&self, colors: usize) -> Result<Bitmap> {
+        let mut bitmap = Bitmap::filled(self.width, self.height, 0, false);
+
+        for (i, pixel) in self.pixels.iter().enumerate() {
+            let transparent = self.transparent.get(*pixel as usize) == Some(&true);
+
+            if (*pixel as usize) < colors {
+                bitmap.data[i] = *pixel;
+            } else if !transparent {
+                bail!("color index {} is out of range", pixel);
+            }
+
+            bitmap.transparency[i] = transparent;
+        }
+
+        Ok(bitmap)
+    }
+}
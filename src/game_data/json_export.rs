@@ -0,0 +1,265 @@
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::game_data::file::ground::{InteractionType, ObjectInfo};
+use crate::game_data::file::level_text::Skills;
+use crate::game_data::{GameData, Level, NUM_LEVELS, code_for_level, decode_level_index};
+
+// All levels in game order and the object info of all tilesets, written with serde_json
+#[derive(Serialize)]
+pub struct JsonExport {
+    levels: Vec<LevelJson>,
+    tilesets: Vec<TilesetJson>,
+}
+
+#[derive(Serialize)]
+struct LevelJson {
+    index: usize,
+    rating: String,
+    number: usize,
+    code: String,
+    name: String,
+    release_rate: u32,
+    released: u32,
+    required: u32,
+    time_limit: u32,
+    skills: Skills,
+    start_x: u32,
+    graphics_set: u32,
+    extended_graphics_set: u32,
+    terrain: Vec<TerrainJson>,
+    objects: Vec<ObjectJson>,
+    steel: Vec<SteelJson>,
+}
+
+#[derive(Serialize)]
+struct TerrainJson {
+    id: u32,
+    x: i32,
+    y: i32,
+    do_not_overwrite: bool,
+    flip_y: bool,
+    remove_terrain: bool,
+}
+
+// Trigger boxes are given in level coordinates
+#[derive(Serialize)]
+struct ObjectJson {
+    id: u32,
+    x: i32,
+    y: i32,
+    do_not_overwrite: bool,
+    flip_y: bool,
+    draw_only_over_terrain: bool,
+    interaction_type: Option<InteractionType>,
+    trigger: Option<AreaJson>,
+}
+
+#[derive(Serialize)]
+struct AreaJson {
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+}
+
+#[derive(Serialize)]
+struct SteelJson {
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+}
+
+#[derive(Serialize)]
+struct TilesetJson {
+    index: usize,
+    objects: Vec<ObjectInfoJson>,
+}
+
+#[derive(Serialize)]
+struct ObjectInfoJson {
+    id: usize,
+    width: u32,
+    height: u32,
+    frames: usize,
+    interaction_type: InteractionType,
+    trigger: Option<TriggerJson>,
+    trap_sound_effect: u32,
+}
+
+#[derive(Serialize)]
+struct TriggerJson {
+    left: i32,
+    top: i32,
+    width: u32,
+    height: u32,
+}
+
+pub fn export_json(game_data: &GameData) -> Result<JsonExport> {
+    let levels = (0..NUM_LEVELS)
+        .map(|index| level_json(game_data, index))
+        .collect::<Result<Vec<LevelJson>>>()?;
+
+    let tilesets = (0..game_data.num_tilesets())
+        .map(|index| {
+            let tileset = game_data.tileset(index)?;
+
+            Ok(TilesetJson {
+                index,
+                objects: tileset
+                    .object_info
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, info)| info.width > 0 && info.height > 0)
+                    .map(|(id, info)| object_info_json(id, info))
+                    .collect(),
+            })
+        })
+        .collect::<Result<Vec<TilesetJson>>>()?;
+
+    Ok(JsonExport { levels, tilesets })
+}
+
+fn level_json(game_data: &GameData, index: usize) -> Result<LevelJson> {
+    let level = game_data.resolve_level(index)?;
+    let parameters = &level.parameters;
+    let (rating, number) = decode_level_index(index);
+
+    Ok(LevelJson {
+        index,
+        rating: rating.to_string(),
+        number,
+        code: code_for_level(index, None, None)?,
+        name: parameters.name.trim_end().to_string(),
+        release_rate: parameters.release_rate,
+        released: parameters.released,
+        required: parameters.required,
+        time_limit: parameters.time_limit,
+        skills: parameters.skills.into(),
+        start_x: level.start_x,
+        graphics_set: level.graphics_set,
+        extended_graphics_set: level.extended_graphics_set,
+        terrain: level
+            .terrain_tiles
+            .iter()
+            .map(|tile| TerrainJson {
+                id: tile.id,
+                x: tile.x,
+                y: tile.y,
+                do_not_overwrite: tile.do_not_overwrite,
+                flip_y: tile.flip_y,
+                remove_terrain: tile.remove_terrain,
+            })
+            .collect(),
+        objects: objects_json(game_data, &level),
+        steel: level
+            .steel_areas
+            .iter()
+            .map(|steel_area| SteelJson {
+                x: steel_area.x,
+                y: steel_area.y,
+                width: steel_area.width,
+                height: steel_area.height,
+            })
+            .collect(),
+    })
+}
+
+fn objects_json(game_data: &GameData, level: &Level) -> Vec<ObjectJson> {
+    level
+        .objects
+        .iter()
+        .map(|object| {
+            let info = game_data
+                .resolve_object(object.id as usize, level.graphics_set as usize)
+                .ok();
+
+            ObjectJson {
+                id: object.id,
+                x: object.x,
+                y: object.y,
+                do_not_overwrite: object.do_not_overwrite,
+                flip_y: object.flip_y,
+                draw_only_over_terrain: object.draw_only_over_terrain,
+                interaction_type: info.map(|info| info.interaction_type),
+                trigger: info.filter(|info| has_trigger(info)).map(|info| AreaJson {
+                    x: object.x + info.trigger_left,
+                    y: object.y + info.trigger_top,
+                    width: info.trigger_width,
+                    height: info.trigger_height,
+                }),
+            }
+        })
+        .collect()
+}
+
+fn object_info_json(id: usize, info: &ObjectInfo) -> ObjectInfoJson {
+    ObjectInfoJson {
+        id,
+        width: info.width,
+        height: info.height,
+        frames: info.animation_end,
+        interaction_type: info.interaction_type,
+        trigger: has_trigger(info).then_some(TriggerJson {
+            left: info.trigger_left,
+            top: info.trigger_top,
+            width: info.trigger_width,
+            height: info.trigger_height,
+        }),
+        trap_sound_effect: info.trap_sound_effect,
+    }
+}
+
+fn has_trigger(info: &ObjectInfo) -> bool {
+    !matches!(
+        info.interaction_type,
+        InteractionType::None | InteractionType::Entrance
+    ) && info.trigger_width > 0
+        && info.trigger_height > 0
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use crate::game_data::file::ground::{InteractionType, ObjectInfo};
+    use crate::game_data::json_export::object_info_json;
+
+    #[test]
+    fn object_info() {
+        let info = ObjectInfo {
+            interaction_type: InteractionType::OneWayLeft,
+            width: 16,
+            height: 8,
+            animation_end: 4,
+            trigger_left: 4,
+            trigger_top: -4,
+            trigger_width: 8,
+            trigger_height: 12,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            serde_json::to_value(object_info_json(3, &info)).unwrap(),
+            json!({
+                "id": 3,
+                "width": 16,
+                "height": 8,
+                "frames": 4,
+                "interaction_type": "one_way_left",
+                "trigger": { "left": 4, "top": -4, "width": 8, "height": 12 },
+                "trap_sound_effect": 0
+            })
+        );
+
+        let info = ObjectInfo {
+            interaction_type: InteractionType::Entrance,
+            ..info
+        };
+        assert_eq!(
+            serde_json::to_value(object_info_json(3, &info)).unwrap()["trigger"],
+            json!(null)
+        );
+    }
+}
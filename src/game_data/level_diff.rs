@@ -0,0 +1,373 @@
+use crate::game_data::file::level::{Level, LevelStructure, Object, TerrainTile};
+use crate::game_data::file::palette::{PALETTE_SIZE, PaletteEntry};
+use crate::game_data::file::sprite::Bitmap;
+use crate::game_data::skill::SKILLS;
+
+pub const DIFF_COLOR_UNCHANGED: u8 = 1;
+pub const DIFF_COLOR_REMOVED: u8 = 2;
+pub const DIFF_COLOR_ADDED: u8 = 3;
+pub const DIFF_COLOR_CHANGED: u8 = 4;
+
+// Palette for the bitmap returned by `diff_terrain`
+pub const DIFF_PALETTE: [PaletteEntry; PALETTE_SIZE] = {
+    let mut palette = [(0, 0, 0); PALETTE_SIZE];
+    palette[DIFF_COLOR_UNCHANGED as usize] = (64, 64, 64);
+    palette[DIFF_COLOR_REMOVED as usize] = (255, 0, 0);
+    palette[DIFF_COLOR_ADDED as usize] = (0, 255, 0);
+    palette[DIFF_COLOR_CHANGED as usize] = (255, 255, 0);
+    palette
+};
+
+// Human readable list of the differences between two versions of a level, empty if the levels
+// are the same
+pub fn diff_level(a: &Level, b: &Level) -> Vec<String> {
+    let mut changes: Vec<String> = Vec::new();
+    let (pa, pb) = (&a.parameters, &b.parameters);
+
+    let mut compare = |name: &str, a: u32, b: u32| {
+        if a != b {
+            changes.push(format!("{}: {} -> {}", name, a, b));
+        }
+    };
+
+    compare("release rate", pa.release_rate, pb.release_rate);
+    compare("released", pa.released, pb.released);
+    compare("required", pa.required, pb.required);
+    compare("time limit", pa.time_limit, pb.time_limit);
+    for skill in SKILLS {
+        compare(
+            &skill.to_string(),
+            pa.skills[skill as usize],
+            pb.skills[skill as usize],
+        );
+    }
+    compare("start x", a.start_x, b.start_x);
+    compare("graphics set", a.graphics_set, b.graphics_set);
+    compare(
+        "extended graphics set",
+        a.extended_graphics_set,
+        b.extended_graphics_set,
+    );
+
+    if pa.name.trim_end() != pb.name.trim_end() {
+        changes.push(format!(
+            "name: '{}' -> '{}'",
+            pa.name.trim_end(),
+            pb.name.trim_end()
+        ));
+    }
+
+    diff_structures(
+        "tile",
+        &a.terrain_tiles,
+        &b.terrain_tiles,
+        tile_flags,
+        &mut changes,
+    );
+    diff_structures("object", &a.objects, &b.objects, object_flags, &mut changes);
+
+    changes
+}
+
+// For each level in A, the index of the same level in B or None if it was removed. Levels are
+// matched by position, unless the level was moved to another position. Each level in B is
+// matched at most once, levels in B without a match were added.
+pub fn match_levels(levels_a: &[Level], levels_b: &[Level]) -> Vec<Option<usize>> {
+    let name = |level: &Level| level.parameters.name.trim_end().to_string();
+    let mut matches: Vec<Option<usize>> = vec![None; levels_a.len()];
+    let mut matched_b = vec![false; levels_b.len()];
+
+    for (i, level_a) in levels_a.iter().enumerate() {
+        if levels_b
+            .get(i)
+            .is_some_and(|level_b| name(level_b) == name(level_a))
+        {
+            matches[i] = Some(i);
+            matched_b[i] = true;
+        }
+    }
+
+    for (i, level_a) in levels_a.iter().enumerate() {
+        if matches[i].is_some() {
+            continue;
+        }
+
+        if let Some(j) =
+            (0..levels_b.len()).find(|j| !matched_b[*j] && name(&levels_b[*j]) == name(level_a))
+        {
+            matches[i] = Some(j);
+            matched_b[j] = true;
+        }
+    }
+
+    // Levels that were neither kept nor moved are compared in place, as renamed
+    for i in 0..levels_a.len() {
+        if matches[i].is_none() && i < levels_b.len() && !matched_b[i] {
+            matches[i] = Some(i);
+            matched_b[i] = true;
+        }
+    }
+
+    matches
+}
+
+fn tile_flags(tile: &TerrainTile) -> [bool; 3] {
+    [tile.do_not_overwrite, tile.flip_y, tile.remove_terrain]
+}
+
+fn object_flags(object: &Object) -> [bool; 3] {
+    [
+        object.do_not_overwrite,
+        object.flip_y,
+        object.draw_only_over_terrain,
+    ]
+}
+
+// Identical entries are matched first, the remaining entries with the same id are considered
+// moved in the order they appear in the level
+fn diff_structures<T: LevelStructure>(
+    kind: &str,
+    a: &[T],
+    b: &[T],
+    flags: fn(&T) -> [bool; 3],
+    changes: &mut Vec<String>,
+) {
+    let same = |x: &T, y: &T| {
+        x.get_id() == y.get_id()
+            && x.get_x() == y.get_x()
+            && x.get_y() == y.get_y()
+            && flags(x) == flags(y)
+    };
+
+    let mut unmatched_b: Vec<&T> = b.iter().collect();
+    let mut unmatched_a: Vec<&T> = Vec::new();
+
+    for x in a {
+        match unmatched_b.iter().position(|y| same(x, y)) {
+            Some(i) => {
+                unmatched_b.remove(i);
+            }
+            None => unmatched_a.push(x),
+        }
+    }
+
+    for x in unmatched_a {
+        let Some(i) = unmatched_b.iter().position(|y| y.get_id() == x.get_id()) else {
+            changes.push(format!(
+                "{} {} removed at ({}, {})",
+                kind,
+                x.get_id(),
+                x.get_x(),
+                x.get_y()
+            ));
+            continue;
+        };
+
+        let y = unmatched_b.remove(i);
+
+        if (x.get_x(), x.get_y()) == (y.get_x(), y.get_y()) {
+            changes.push(format!(
+                "{} {} at ({}, {}) changed flags",
+                kind,
+                x.get_id(),
+                x.get_x(),
+                x.get_y()
+            ));
+        } else {
+            changes.push(format!(
+                "{} {} moved from ({}, {}) to ({}, {})",
+                kind,
+                x.get_id(),
+                x.get_x(),
+                x.get_y(),
+                y.get_x(),
+                y.get_y()
+            ));
+        }
+    }
+
+    for y in unmatched_b {
+        changes.push(format!(
+            "{} {} added at ({}, {})",
+            kind,
+            y.get_id(),
+            y.get_x(),
+            y.get_y()
+        ));
+    }
+}
+
+// Number of differing pixels of two composed terrains and a bitmap highlighting them, using
+// `DIFF_PALETTE`
+pub fn diff_terrain(a: &Bitmap, b: &Bitmap) -> (usize, Bitmap) {
+    let size = a.data.len().min(b.data.len());
+    let mut data: Vec<u8> = vec![0; size];
+    let mut transparency: Vec<bool> = vec![true; size];
+    let mut count: usize = 0;
+
+    for i in 0..size {
+        let color = match (a.transparency[i], b.transparency[i]) {
+            (true, true) => continue,
+            (false, true) => DIFF_COLOR_REMOVED,
+            (true, false) => DIFF_COLOR_ADDED,
+            (false, false) if a.data[i] != b.data[i] => DIFF_COLOR_CHANGED,
+            (false, false) => DIFF_COLOR_UNCHANGED,
+        };
+
+        if color != DIFF_COLOR_UNCHANGED {
+            count += 1;
+        }
+
+        data[i] = color;
+        transparency[i] = false;
+    }
+
+    (
+        count,
+        Bitmap {
+            width: a.width,
+            height: a.height,
+            data,
+            transparency,
+        },
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use crate::game_data::file::level::{Level, LevelParameters, TerrainTile};
+    use crate::game_data::file::sprite::Bitmap;
+    use crate::game_data::level_diff::{
+        DIFF_COLOR_ADDED, DIFF_COLOR_CHANGED, DIFF_COLOR_REMOVED, DIFF_COLOR_UNCHANGED, diff_level,
+        diff_terrain, match_levels,
+    };
+
+    fn tile(id: u32, x: i32, y: i32) -> TerrainTile {
+        TerrainTile {
+            x,
+            y,
+            id,
+            do_not_overwrite: false,
+            flip_y: false,
+            remove_terrain: false,
+        }
+    }
+
+    fn level(terrain_tiles: Vec<TerrainTile>) -> Level {
+        Level {
+            parameters: LevelParameters {
+                release_rate: 50,
+                released: 10,
+                required: 1,
+                time_limit: 5,
+                skills: [0; 8],
+                name: "Just dig!".to_string(),
+            },
+            start_x: 0,
+            graphics_set: 0,
+            extended_graphics_set: 0,
+            terrain_tiles,
+            objects: Vec::new(),
+            steel_areas: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn levels() {
+        let a = level(vec![tile(1, 0, 0), tile(2, 10, 10), tile(3, 20, 20)]);
+        let mut b = level(vec![tile(2, 10, 10), tile(1, 5, 0), tile(4, 30, 30)]);
+        b.parameters.skills[7] = 10;
+        b.start_x = 320;
+
+        assert!(diff_level(&a, &a).is_empty());
+        assert_eq!(
+            diff_level(&a, &b),
+            [
+                "Digger: 0 -> 10",
+                "start x: 0 -> 320",
+                "tile 1 moved from (0, 0) to (5, 0)",
+                "tile 3 removed at (20, 20)",
+                "tile 4 added at (30, 30)",
+            ]
+        );
+    }
+
+    fn named(names: &[&str]) -> Vec<Level> {
+        names
+            .iter()
+            .map(|name| {
+                let mut level = level(Vec::new());
+                level.parameters.name = format!("{:32}", name);
+                level
+            })
+            .collect()
+    }
+
+    #[test]
+    fn match_unchanged() {
+        let levels = named(&["a", "b", "c"]);
+
+        assert_eq!(match_levels(&levels, &levels), [Some(0), Some(1), Some(2)]);
+    }
+
+    #[test]
+    fn match_moved() {
+        let a = named(&["a", "b", "c"]);
+        let b = named(&["b", "c", "a"]);
+
+        assert_eq!(match_levels(&a, &b), [Some(2), Some(0), Some(1)]);
+    }
+
+    #[test]
+    fn match_same_name_once() {
+        let a = named(&["a", "x", "x"]);
+        let b = named(&["x", "b", "c"]);
+
+        // The second x has no counterpart left and is compared in place, a was removed
+        assert_eq!(match_levels(&a, &b), [None, Some(0), Some(2)]);
+
+        let a = named(&["x", "x"]);
+        let b = named(&["x", "y", "z"]);
+
+        assert_eq!(match_levels(&a, &b), [Some(0), Some(1)]);
+    }
+
+    #[test]
+    fn match_renamed() {
+        let a = named(&["a", "b", "c"]);
+        let b = named(&["a", "renamed", "c"]);
+
+        assert_eq!(match_levels(&a, &b), [Some(0), Some(1), Some(2)]);
+
+        // Renamed in place, but its position is taken by a moved level
+        let a = named(&["a", "b", "c"]);
+        let b = named(&["a", "c", "d"]);
+
+        assert_eq!(match_levels(&a, &b), [Some(0), None, Some(1)]);
+    }
+
+    #[test]
+    fn terrain() {
+        let bitmap = |data: Vec<u8>, transparency: Vec<bool>| Bitmap {
+            width: 2,
+            height: 2,
+            data,
+            transparency,
+        };
+
+        let a = bitmap(vec![1, 1, 0, 2], vec![false, false, true, false]);
+        let b = bitmap(vec![1, 0, 3, 4], vec![false, true, false, false]);
+        let (count, diff) = diff_terrain(&a, &b);
+
+        assert_eq!(count, 3);
+        assert_eq!(
+            diff.data,
+            [
+                DIFF_COLOR_UNCHANGED,
+                DIFF_COLOR_REMOVED,
+                DIFF_COLOR_ADDED,
+                DIFF_COLOR_CHANGED
+            ]
+        );
+    }
+}
@@ -1,17 +1,33 @@
 pub mod file;
 
+mod code;
 mod cursors;
 mod game_data;
+mod indexed_png;
+mod json_export;
 mod level;
+mod level_diff;
 mod other_constants;
 mod reader;
 mod skill;
 mod skill_panel;
+mod text_render;
+mod tileset_png;
+mod verify;
+mod writer;
 
+pub use code::*;
 pub use cursors::*;
 pub use game_data::*;
+pub use indexed_png::*;
+pub use json_export::*;
 pub use level::*;
+pub use level_diff::*;
 pub use other_constants::*;
 pub use reader::*;
 pub use skill::*;
 pub use skill_panel::*;
+pub use text_render::*;
+pub use tileset_png::*;
+pub use verify::*;
+pub use writer::*;
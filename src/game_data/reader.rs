@@ -1,88 +1,85 @@
 use std::path::Path;
+use std::sync::{Arc, OnceLock};
 use std::thread::{self, ScopedJoinHandle};
 
-use anyhow::Result;
-
 use crate::game_data::SkillPanel;
+use crate::game_data::file::error::Result;
 use crate::game_data::file::ground::read_ground;
 use crate::game_data::file::level::{Level, read_level_file, read_oddtable};
 use crate::game_data::file::main::read_main;
 use crate::game_data::file::palette::{LOWER_PALETTE_FIXED, PALETTE_SIZE, PaletteEntry};
+use crate::game_data::file::vfs::{Vfs, open_vfs};
 use crate::game_data::file::vgagr::read_vgagr;
 use crate::game_data::file::vgaspec::read_vgaspec;
 use crate::game_data::{Cursors, GameData, Image, TileSet};
 
-const NUM_LEVELS_FILES: usize = 10;
-const LEVELS_PER_FILE: usize = 8;
-const NUM_TILESETS: usize = 5;
-const NUM_SPECIAL_BACKGROUND: usize = 4;
+pub const NUM_LEVELS_FILES: usize = 10;
+pub const LEVELS_PER_FILE: usize = 8;
+pub const NUM_TILESETS: usize = 5;
+pub const NUM_SPECIAL_BACKGROUND: usize = 4;
+
+#[derive(Clone, Copy, PartialEq, Default, Debug)]
+pub enum Loading {
+    // Decode everything up front, tilesets and special backgrounds in parallel
+    #[default]
+    Eager,
+    // Decode tilesets and special backgrounds on first use
+    Lazy,
+}
 
 pub fn read_game_data(path: &Path) -> Result<GameData> {
+    read_game_data_with(path, Loading::Eager)
+}
+
+pub fn read_game_data_with(path: &Path, loading: Loading) -> Result<GameData> {
+    read_game_data_from(open_vfs(path)?, loading)
+}
+
+pub fn read_game_data_from(vfs: Arc<dyn Vfs>, loading: Loading) -> Result<GameData> {
+    let files = vfs.as_ref();
+
     thread::scope(|s| {
-        let tileset_handles: Vec<ScopedJoinHandle<Result<TileSet>>> = (0..NUM_TILESETS)
-            .map(|i| {
-                s.spawn(move || -> Result<TileSet> {
-                    let ground_dat = read_ground(path, i)?;
-                    let vgagr =
-                        read_vgagr(path, i, &ground_dat.object_info, &ground_dat.terrain_info)?;
-
-                    Ok(TileSet {
-                        object_info: ground_dat.object_info,
-                        terrain_info: ground_dat.terrain_info,
-                        palettes: ground_dat.palettes,
-                        object_sprites: vgagr.object_sprites,
-                        tiles: vgagr.tiles,
-                    })
-                })
-            })
-            .collect();
-
-        let special_background_handles: Vec<ScopedJoinHandle<Result<Image>>> = (0
-            ..NUM_SPECIAL_BACKGROUND)
-            .map(|i| {
-                s.spawn(move || -> Result<Image> {
-                    let vgaspec = read_vgaspec(path, i)?;
-
-                    Ok(Image {
-                        palette: vgaspec.palette,
-                        bitmap: vgaspec.bitmap,
-                    })
-                })
-            })
-            .collect();
-
-        let main_handle = s.spawn(|| read_main(path));
+        let tileset_handles: Vec<ScopedJoinHandle<Result<TileSet>>> = match loading {
+            Loading::Eager => (0..NUM_TILESETS)
+                .map(|i| s.spawn(move || read_tileset(files, i)))
+                .collect(),
+            Loading::Lazy => Vec::new(),
+        };
+
+        let special_background_handles: Vec<ScopedJoinHandle<Result<Image>>> = match loading {
+            Loading::Eager => (0..NUM_SPECIAL_BACKGROUND)
+                .map(|i| s.spawn(move || read_special_background(files, i)))
+                .collect(),
+            Loading::Lazy => Vec::new(),
+        };
+
+        let main_handle = s.spawn(|| read_main(files));
 
         let levels: Vec<Level> = (0..NUM_LEVELS_FILES).try_fold::<_, _, Result<Vec<Level>>>(
             Vec::with_capacity(LEVELS_PER_FILE * NUM_LEVELS_FILES),
             |mut acc, i| {
-                acc.append(&mut read_level_file(path, i)?);
+                acc.append(&mut read_level_file(files, i)?);
                 Ok(acc)
             },
         )?;
 
-        let oddtable = read_oddtable(path)?;
+        let oddtable = read_oddtable(files)?;
 
         let mut static_palette: [PaletteEntry; PALETTE_SIZE] = [(0, 0, 0); PALETTE_SIZE];
         for i in 0..PALETTE_SIZE {
             static_palette[i] = LOWER_PALETTE_FIXED[i % LOWER_PALETTE_FIXED.len()];
         }
 
-        let tilesets: Vec<TileSet> = tileset_handles
-            .into_iter()
-            .map(|h| h.join().unwrap())
-            .collect::<Result<Vec<TileSet>>>()?;
-
-        let special_backgrounds: Vec<Image> = special_background_handles
-            .into_iter()
-            .map(|h| h.join().unwrap())
-            .collect::<Result<Vec<Image>>>()?;
+        let tilesets = join_into_cells(tileset_handles, NUM_TILESETS)?;
+        let special_backgrounds =
+            join_into_cells(special_background_handles, NUM_SPECIAL_BACKGROUND)?;
 
         let main = main_handle.join().unwrap()?;
 
         println!();
 
         Ok(GameData {
+            vfs: vfs.clone(),
             levels,
             oddtable,
             tilesets,
@@ -94,7 +91,51 @@ pub fn read_game_data(path: &Path) -> Result<GameData> {
                 main.font_skill_panel_skills,
             ),
             lemming_sprites: main.lemming_sprites,
+            masks: main.masks,
+            countdown_digits: main.countdown_digits,
+            main_menu: main.main_menu,
             cursors: Cursors::new(),
         })
     })
 }
+
+// Cells without a handle stay empty and are filled on first use
+fn join_into_cells<T>(
+    handles: Vec<ScopedJoinHandle<Result<T>>>,
+    count: usize,
+) -> Result<Vec<OnceLock<T>>> {
+    let mut cells: Vec<OnceLock<T>> = (0..count).map(|_| OnceLock::new()).collect();
+
+    for (cell, handle) in cells.iter_mut().zip(handles) {
+        *cell = OnceLock::from(handle.join().unwrap()?);
+    }
+
+    Ok(cells)
+}
+
+pub(crate) fn read_tileset(vfs: &dyn Vfs, index: usize) -> Result<TileSet> {
+    let ground_dat = read_ground(vfs, index)?;
+    let vgagr = read_vgagr(
+        vfs,
+        index,
+        &ground_dat.object_info,
+        &ground_dat.terrain_info,
+    )?;
+
+    Ok(TileSet {
+        object_info: ground_dat.object_info,
+        terrain_info: ground_dat.terrain_info,
+        palettes: ground_dat.palettes,
+        object_sprites: vgagr.object_sprites,
+        tiles: vgagr.tiles,
+    })
+}
+
+pub(crate) fn read_special_background(vfs: &dyn Vfs, index: usize) -> Result<Image> {
+    let vgaspec = read_vgaspec(vfs, index)?;
+
+    Ok(Image {
+        palette: vgaspec.palette,
+        bitmap: vgaspec.bitmap,
+    })
+}
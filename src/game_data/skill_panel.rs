@@ -1,7 +1,7 @@
 pub use crate::game_data::file::main::{
     resolve_skill_panel_font_index, resolve_skill_panel_skill_font_index,
 };
-use crate::game_data::{Bitmap, SCREEN_WIDTH, Sprite};
+use crate::game_data::{Bitmap, LEVEL_HEIGHT, LEVEL_WIDTH, SCREEN_WIDTH, Sprite};
 
 pub const SKILL_PANEL_HEIGHT: u32 = 40;
 
@@ -78,3 +78,116 @@ fn create_frame(width: u32, height: u32) -> Bitmap {
         transparency,
     }
 }
+
+// Minimap pixel covering a level position, clamped to the level. The in-game renderer uses
+// this to place lemmings and the view frame on the minimap.
+pub fn minimap_position(x: i32, y: i32) -> (u32, u32) {
+    (
+        x.clamp(0, LEVEL_WIDTH as i32 - 1) as u32 * MINIMAP_VIEW_WIDTH / LEVEL_WIDTH,
+        y.clamp(0, LEVEL_HEIGHT as i32 - 1) as u32 * MINIMAP_VIEW_HEIGHT / LEVEL_HEIGHT,
+    )
+}
+
+// Level position sampled for a minimap pixel, the center of the area it covers
+pub fn minimap_sample(x: u32, y: u32) -> (u32, u32) {
+    (
+        (2 * x + 1) * LEVEL_WIDTH / (2 * MINIMAP_VIEW_WIDTH),
+        (2 * y + 1) * LEVEL_HEIGHT / (2 * MINIMAP_VIEW_HEIGHT),
+    )
+}
+
+// Downsamples a level from compose_terrain or compose_level to the size of the minimap, taking
+// the level pixel from minimap_sample. Palette indices are kept so the thumbnail uses the
+// palette of the level.
+pub fn render_minimap(level: &Bitmap) -> Bitmap {
+    let size = (MINIMAP_VIEW_WIDTH * MINIMAP_VIEW_HEIGHT) as usize;
+    let mut data: Vec<u8> = Vec::with_capacity(size);
+    let mut transparency: Vec<bool> = Vec::with_capacity(size);
+
+    for y in 0..MINIMAP_VIEW_HEIGHT {
+        for x in 0..MINIMAP_VIEW_WIDTH {
+            let (source_x, source_y) = minimap_sample(x, y);
+            let i = (source_x.min(level.width - 1) + source_y.min(level.height - 1) * level.width)
+                as usize;
+
+            data.push(level.data[i]);
+            transparency.push(level.transparency[i]);
+        }
+    }
+
+    Bitmap {
+        width: MINIMAP_VIEW_WIDTH,
+        height: MINIMAP_VIEW_HEIGHT,
+        data,
+        transparency,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::game_data::{
+        Bitmap, LEVEL_HEIGHT, LEVEL_WIDTH, MINIMAP_VIEW_HEIGHT, MINIMAP_VIEW_WIDTH,
+        minimap_position, minimap_sample, render_minimap,
+    };
+
+    #[test]
+    fn minimap() {
+        let size = (LEVEL_WIDTH * LEVEL_HEIGHT) as usize;
+        let mut level = Bitmap {
+            width: LEVEL_WIDTH,
+            height: LEVEL_HEIGHT,
+            data: vec![0; size],
+            transparency: vec![true; size],
+        };
+
+        // The center of the first minimap pixel is (8, 4), the last one is sampled at (1592, 155)
+        for (x, y, color) in [(8, 4, 1), (7, 4, 2), (1592, 155, 3)] {
+            let i = (x + y * LEVEL_WIDTH) as usize;
+            level.data[i] = color;
+            level.transparency[i] = false;
+        }
+
+        let minimap = render_minimap(&level);
+        let last = (MINIMAP_VIEW_WIDTH * MINIMAP_VIEW_HEIGHT - 1) as usize;
+
+        assert_eq!(minimap.width, MINIMAP_VIEW_WIDTH);
+        assert_eq!(minimap.height, MINIMAP_VIEW_HEIGHT);
+        assert_eq!((minimap.data[0], minimap.transparency[0]), (1, false));
+        assert_eq!((minimap.data[last], minimap.transparency[last]), (3, false));
+        assert_eq!(minimap.transparency.iter().filter(|&&t| !t).count(), 2);
+    }
+
+    #[test]
+    fn minimap_matches_in_game_positions() {
+        // Each minimap pixel covers 16 level columns and 8 or 9 level rows
+        assert_eq!(minimap_position(15, 8), (0, 0));
+        assert_eq!(minimap_position(16, 9), (1, 1));
+        assert_eq!(minimap_position(-5, 500), (0, MINIMAP_VIEW_HEIGHT - 1));
+        assert_eq!(
+            minimap_position(LEVEL_WIDTH as i32, 0),
+            (MINIMAP_VIEW_WIDTH - 1, 0)
+        );
+
+        let size = (LEVEL_WIDTH * LEVEL_HEIGHT) as usize;
+        let level = Bitmap {
+            width: LEVEL_WIDTH,
+            height: LEVEL_HEIGHT,
+            data: (0..size).map(|i| (i % 251) as u8).collect(),
+            transparency: vec![false; size],
+        };
+        let minimap = render_minimap(&level);
+
+        // Each thumbnail pixel shows the colour of a level pixel that a lemming standing there
+        // would be drawn on in the game
+        for y in 0..MINIMAP_VIEW_HEIGHT {
+            for x in 0..MINIMAP_VIEW_WIDTH {
+                let (source_x, source_y) = minimap_sample(x, y);
+                assert_eq!(minimap_position(source_x as i32, source_y as i32), (x, y));
+                assert_eq!(
+                    minimap.data[(x + y * MINIMAP_VIEW_WIDTH) as usize],
+                    level.data[(source_x + source_y * LEVEL_WIDTH) as usize]
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,178 @@
+use std::{fmt::Write, ops::Range};
+
+use crate::game_data::file::ground::InteractionType;
+use crate::game_data::file::palette::{PALETTE_SIZE, PaletteEntry};
+
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum TextPixel {
+    Empty,
+    Terrain(u8),
+    Steel,
+    Trigger(InteractionType),
+}
+
+impl TextPixel {
+    fn ascii(self) -> char {
+        match self {
+            Self::Empty => '.',
+            Self::Terrain(_) => '#',
+            Self::Steel => 'S',
+            Self::Trigger(interaction_type) => match interaction_type {
+                InteractionType::Exit => 'E',
+                InteractionType::Trap => 'T',
+                InteractionType::Drown => '~',
+                InteractionType::Disintegrate => '^',
+                InteractionType::OneWayLeft => '<',
+                InteractionType::OneWayRight => '>',
+                InteractionType::Entrance | InteractionType::None => '.',
+            },
+        }
+    }
+
+    // Colour index in the xterm 256 colour palette
+    fn ansi(self, colors: &[u8; PALETTE_SIZE]) -> Option<u8> {
+        match self {
+            Self::Empty => None,
+            Self::Terrain(color) => Some(colors[color as usize % PALETTE_SIZE]),
+            Self::Steel => Some(250),
+            Self::Trigger(interaction_type) => match interaction_type {
+                InteractionType::Exit => Some(46),
+                InteractionType::Trap => Some(196),
+                InteractionType::Drown => Some(21),
+                InteractionType::Disintegrate => Some(208),
+                InteractionType::OneWayLeft => Some(201),
+                InteractionType::OneWayRight => Some(226),
+                InteractionType::Entrance | InteractionType::None => None,
+            },
+        }
+    }
+}
+
+// One character per pixel, rows separated by newlines
+pub fn render_ascii(
+    columns: Range<u32>,
+    height: u32,
+    pixel: impl Fn(u32, u32) -> TextPixel,
+) -> String {
+    let mut text = String::new();
+
+    for y in 0..height {
+        text.extend(columns.clone().map(|x| pixel(x, y).ascii()));
+        text.push('\n');
+    }
+
+    text
+}
+
+// Each character covers two rows, using upper and lower half blocks with 256 colour ANSI
+// escape codes
+pub fn render_ansi(
+    columns: Range<u32>,
+    height: u32,
+    palette: &[PaletteEntry; PALETTE_SIZE],
+    pixel: impl Fn(u32, u32) -> TextPixel,
+) -> String {
+    let colors = palette.map(ansi_color);
+    let mut text = String::new();
+
+    // Writing to a string is infallible
+    for y in (0..height).step_by(2) {
+        for x in columns.clone() {
+            let top = pixel(x, y).ansi(&colors);
+            let bottom = if y + 1 < height {
+                pixel(x, y + 1).ansi(&colors)
+            } else {
+                None
+            };
+
+            let _ = match (top, bottom) {
+                (None, None) => write!(text, "\x1b[0m "),
+                (Some(top), None) => write!(text, "\x1b[0;38;5;{}m\u{2580}", top),
+                (None, Some(bottom)) => write!(text, "\x1b[0;38;5;{}m\u{2584}", bottom),
+                (Some(top), Some(bottom)) => {
+                    write!(text, "\x1b[38;5;{};48;5;{}m\u{2580}", top, bottom)
+                }
+            };
+        }
+
+        text.push_str("\x1b[0m\n");
+    }
+
+    text
+}
+
+// Closest match in the colour cube or the grayscale ramp
+fn ansi_color((r, g, b): PaletteEntry) -> u8 {
+    let distance = |(r2, g2, b2): (u8, u8, u8)| {
+        [(r, r2), (g, g2), (b, b2)]
+            .iter()
+            .map(|&(c1, c2)| (c1 as i32 - c2 as i32).pow(2))
+            .sum::<i32>()
+    };
+
+    let cube_index = |c: u8| {
+        (0..CUBE_LEVELS.len())
+            .min_by_key(|&i| (CUBE_LEVELS[i] as i32 - c as i32).abs())
+            .unwrap_or(0)
+    };
+
+    let (ri, gi, bi) = (cube_index(r), cube_index(g), cube_index(b));
+    let cube = (CUBE_LEVELS[ri], CUBE_LEVELS[gi], CUBE_LEVELS[bi]);
+
+    let gray_index = ((r as u32 + g as u32 + b as u32) / 3).saturating_sub(3) / 10;
+    let gray_index = gray_index.min(23) as u8;
+    let gray = 8 + gray_index * 10;
+
+    if distance((gray, gray, gray)) < distance(cube) {
+        232 + gray_index
+    } else {
+        16 + (36 * ri + 6 * gi + bi) as u8
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::game_data::file::ground::InteractionType;
+    use crate::game_data::text_render::{TextPixel, ansi_color, render_ansi, render_ascii};
+
+    fn pixel(x: u32, y: u32) -> TextPixel {
+        match (x, y) {
+            (0, _) => TextPixel::Trigger(InteractionType::Exit),
+            (1, 0) => TextPixel::Steel,
+            (_, 1) => TextPixel::Terrain(3),
+            _ => TextPixel::Empty,
+        }
+    }
+
+    #[test]
+    fn ascii() {
+        assert_eq!(render_ascii(0..3, 3, pixel), "ES.\nE##\nE..\n");
+        assert_eq!(render_ascii(1..3, 2, pixel), "S.\n##\n");
+    }
+
+    #[test]
+    fn ansi() {
+        let mut palette = [(0, 0, 0); 16];
+        palette[3] = (255, 0, 0);
+
+        let text = render_ansi(1..3, 3, &palette, pixel);
+        let lines: Vec<&str> = text.lines().collect();
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(
+            lines[0],
+            "\x1b[38;5;250;48;5;196m\u{2580}\x1b[0;38;5;196m\u{2584}\x1b[0m"
+        );
+        assert_eq!(lines[1], "\x1b[0m \x1b[0m \x1b[0m");
+    }
+
+    #[test]
+    fn colors() {
+        assert_eq!(ansi_color((0, 0, 0)), 16);
+        assert_eq!(ansi_color((255, 255, 255)), 231);
+        assert_eq!(ansi_color((0, 0, 255)), 21);
+        assert_eq!(ansi_color((128, 128, 128)), 244);
+    }
+}
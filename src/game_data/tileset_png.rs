@@ -0,0 +1,441 @@
+use std::{fs, path::Path};
+
+use anyhow::{Context, Result, anyhow, bail};
+use serde::{Deserialize, Serialize};
+use toml::Spanned;
+
+use crate::game_data::TileSet;
+use crate::game_data::file::ground::{
+    InteractionType, OBJECTS_PER_TILESET, ObjectInfo, Palettes, TILES_PER_TILESET, TerrainInfo,
+};
+use crate::game_data::file::palette::{LOWER_PALETTE_FIXED, PALETTE_SIZE, PaletteEntry};
+use crate::game_data::file::path::resolve_path;
+use crate::game_data::file::sprite::{Bitmap, Sprite};
+use crate::game_data::file::text::{from_text, integer, line_at, syntax_error, to_text, word};
+use crate::game_data::indexed_png::{IndexedImage, read_indexed_png, write_indexed_png};
+
+pub const TILESET_METADATA_FILENAME: &str = "tileset.txt";
+
+const TILESET_COLORS: usize = 16;
+
+const LAST_TILE: i64 = TILES_PER_TILESET as i64 - 1;
+const LAST_OBJECT: i64 = OBJECTS_PER_TILESET as i64 - 1;
+
+// Trigger areas have a resolution of 4 pixels, values in between are rounded down
+const TRIGGER_MAX: i64 = 1020;
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct TilesetText {
+    palettes: PalettesText,
+    #[serde(rename = "terrain")]
+    tiles: Vec<Spanned<TileText>>,
+    #[serde(rename = "object")]
+    objects: Vec<Spanned<ObjectText>>,
+}
+
+// Eight colors in hex notation for palette entries 8 to 15
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct PalettesText {
+    custom: Option<String>,
+    standard: Option<String>,
+    preview: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TileText {
+    #[serde(deserialize_with = "integer::<_, _, 0, LAST_TILE>")]
+    index: usize,
+    file: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ObjectText {
+    #[serde(deserialize_with = "integer::<_, _, 0, LAST_OBJECT>")]
+    index: usize,
+    file: String,
+    #[serde(deserialize_with = "integer::<_, _, 1, 255>")]
+    frames: usize,
+    #[serde(default, deserialize_with = "word")]
+    animation_flags: u32,
+    #[serde(default, deserialize_with = "integer::<_, _, 0, 255>")]
+    animation_start: usize,
+    #[serde(default, deserialize_with = "integer::<_, _, 0, 254>")]
+    preview_frame: usize,
+    #[serde(default, deserialize_with = "integer::<_, _, 0, 255>")]
+    trigger_effect: u32,
+    #[serde(default, deserialize_with = "integer::<_, _, 0, TRIGGER_MAX>")]
+    trigger_left: i32,
+    #[serde(default, deserialize_with = "integer::<_, _, -4, { TRIGGER_MAX - 4 }>")]
+    trigger_top: i32,
+    #[serde(default, deserialize_with = "integer::<_, _, 0, TRIGGER_MAX>")]
+    trigger_width: u32,
+    #[serde(default, deserialize_with = "integer::<_, _, 0, TRIGGER_MAX>")]
+    trigger_height: u32,
+    #[serde(default, deserialize_with = "integer::<_, _, 0, 255>")]
+    trap_sound_effect: u32,
+}
+
+// Builds a tileset from a directory of indexed PNGs described by tileset.txt, see
+// doc/tileset_format.md
+pub fn import_tileset(path: &Path) -> Result<TileSet> {
+    let metadata_path = resolve_path(path, TILESET_METADATA_FILENAME);
+    let metadata = fs::read_to_string(&metadata_path)
+        .with_context(|| format!("failed to read {:?}", metadata_path))?;
+
+    let mut tileset = TileSet {
+        object_info: [(); OBJECTS_PER_TILESET].map(|_| ObjectInfo::default()),
+        terrain_info: [(); TILES_PER_TILESET].map(|_| TerrainInfo::default()),
+        palettes: Palettes::default(),
+        object_sprites: [(); OBJECTS_PER_TILESET].map(|_| None),
+        tiles: [(); TILES_PER_TILESET].map(|_| None),
+    };
+
+    let mut image_palette: Option<Vec<PaletteEntry>> = None;
+
+    let metadata_text: TilesetText =
+        from_text(&metadata).context("failed to parse tileset metadata")?;
+
+    for tile in metadata_text.tiles {
+        let line = line_at(&metadata, tile.span());
+        let tile = tile.into_inner();
+        let image = read_image(path, &tile.file)?;
+
+        if tileset.tiles[tile.index].is_some() {
+            return Err(syntax_error(line, format!("duplicate terrain {}", tile.index)).into());
+        }
+
+        let bitmap = image.to_bitmap(TILESET_COLORS)?;
+        check_dimensions(&bitmap)?;
+
+        tileset.terrain_info[tile.index] = TerrainInfo {
+            width: bitmap.width,
+            height: bitmap.height,
+            ..Default::default()
+        };
+        tileset.tiles[tile.index] = Some(bitmap);
+        image_palette.get_or_insert(image.palette);
+    }
+
+    for object in metadata_text.objects {
+        let line = line_at(&metadata, object.span());
+        let object = object.into_inner();
+        let index = object.index;
+        let image = read_image(path, &object.file)?;
+        let (info, sprite) =
+            read_object(&object, &image).with_context(|| format!("invalid object {}", index))?;
+
+        if tileset.object_sprites[index].is_some() {
+            return Err(syntax_error(line, format!("duplicate object {}", index)).into());
+        }
+
+        tileset.object_info[index] = info;
+        tileset.object_sprites[index] = Some(sprite);
+        image_palette.get_or_insert(image.palette);
+    }
+
+    let mut default_palette = [(0, 0, 0); PALETTE_SIZE];
+    for (i, entry) in image_palette
+        .unwrap_or_default()
+        .into_iter()
+        .take(PALETTE_SIZE)
+        .enumerate()
+    {
+        default_palette[i] = entry;
+    }
+
+    let palette = |colors: &Option<String>, key: &str| -> Result<[PaletteEntry; PALETTE_SIZE]> {
+        let mut palette = match colors {
+            Some(colors) => {
+                parse_palette(colors).with_context(|| format!("invalid palette '{}'", key))?
+            }
+            None => default_palette,
+        };
+
+        // Matches the palette layout produced by the ground reader
+        palette[..7].copy_from_slice(&LOWER_PALETTE_FIXED);
+        palette[7] = palette[8];
+
+        Ok(palette)
+    };
+
+    let palettes = &metadata_text.palettes;
+    tileset.palettes = Palettes {
+        custom: palette(&palettes.custom, "custom")?,
+        standard: palette(&palettes.standard, "standard")?,
+        preview: palette(&palettes.preview, "preview")?,
+    };
+
+    Ok(tileset)
+}
+
+// Writes the tileset in the layout expected by import_tileset
+pub fn export_tileset(path: &Path, tileset: &TileSet) -> Result<()> {
+    fs::create_dir_all(path).with_context(|| format!("failed to create {:?}", path))?;
+
+    let palettes = &tileset.palettes;
+    let colors = |palette: &[PaletteEntry; PALETTE_SIZE]| {
+        let colors: Vec<String> = palette[8..]
+            .iter()
+            .map(|(r, g, b)| format!("{:02x}{:02x}{:02x}", r, g, b))
+            .collect();
+
+        Some(colors.join(" "))
+    };
+
+    let mut metadata = TilesetText {
+        palettes: PalettesText {
+            custom: colors(&palettes.custom),
+            standard: colors(&palettes.standard),
+            preview: colors(&palettes.preview),
+        },
+        ..Default::default()
+    };
+
+    for (i, tile) in tileset.tiles.iter().enumerate() {
+        let Some(tile) = tile else { continue };
+        let file = format!("terrain{:02}.png", i);
+
+        write_indexed_png(&path.join(&file), tile, &palettes.custom)?;
+        metadata
+            .tiles
+            .push(Spanned::new(0..0, TileText { index: i, file }));
+    }
+
+    for (i, sprite) in tileset.object_sprites.iter().enumerate() {
+        let Some(sprite) = sprite else { continue };
+        let info = &tileset.object_info[i];
+        let file = format!("object{:02}.png", i);
+
+        write_indexed_png(&path.join(&file), &sprite.sheet(), &palettes.custom)?;
+        metadata.objects.push(Spanned::new(
+            0..0,
+            ObjectText {
+                index: i,
+                file,
+                frames: sprite.frames.len(),
+                animation_flags: info.animation_flags,
+                animation_start: info.animation_start,
+                preview_frame: info.preview_frame(),
+                trigger_effect: info.trigger_effect,
+                trigger_left: info.trigger_left,
+                trigger_top: info.trigger_top,
+                trigger_width: info.trigger_width,
+                trigger_height: info.trigger_height,
+                trap_sound_effect: info.trap_sound_effect,
+            },
+        ));
+    }
+
+    let metadata_path = path.join(TILESET_METADATA_FILENAME);
+    fs::write(&metadata_path, to_text(&metadata))
+        .with_context(|| format!("failed to write {:?}", metadata_path))
+}
+
+fn read_image(path: &Path, filename: &str) -> Result<IndexedImage> {
+    read_indexed_png(&resolve_path(path, filename))
+}
+
+fn read_object(object: &ObjectText, image: &IndexedImage) -> Result<(ObjectInfo, Sprite)> {
+    let frame_count = object.frames;
+    if !(image.height as usize).is_multiple_of(frame_count) {
+        bail!("image height is not a multiple of the frame count");
+    }
+
+    if object.preview_frame >= frame_count {
+        bail!("preview frame {} out of range", object.preview_frame);
+    }
+
+    let bitmap = image.to_bitmap(TILESET_COLORS)?;
+    let height = image.height / frame_count as u32;
+
+    let mut sprite = Sprite::blank(image.width, height, frame_count);
+    for iframe in 0..frame_count as u32 {
+        let frame = bitmap.sub(0, iframe * height, image.width, height)?;
+        check_dimensions(&frame)?;
+
+        sprite.add_frame(&frame)?;
+    }
+
+    let info = ObjectInfo {
+        interaction_type: InteractionType::new(object.trigger_effect, object.animation_flags),
+        animation_flags: object.animation_flags,
+        animation_loops: object.animation_flags == 0x02,
+        animation_start: object.animation_start,
+        animation_end: frame_count,
+        width: sprite.width,
+        height: sprite.height,
+        trigger_left: trigger(object.trigger_left, 0),
+        trigger_top: trigger(object.trigger_top, -4),
+        trigger_width: trigger(object.trigger_width as i32, 0) as u32,
+        trigger_height: trigger(object.trigger_height as i32, 0) as u32,
+        trigger_effect: object.trigger_effect,
+        trap_sound_effect: object.trap_sound_effect,
+        // The actual offsets are assigned when the tileset is written, only the preview frame
+        // index matters
+        animation_frame_size: 1,
+        preview_frame_offset: object.preview_frame,
+        ..Default::default()
+    };
+
+    Ok((info, sprite))
+}
+
+fn trigger(value: i32, min: i32) -> i32 {
+    value - (value - min).rem_euclid(4)
+}
+
+fn check_dimensions(bitmap: &Bitmap) -> Result<()> {
+    if bitmap.width > 255 || bitmap.height > 255 || bitmap.plane_size().is_err() {
+        bail!(
+            "invalid dimensions {}x{}: width and height must be below 256, and the pixel count \
+             must be a multiple of 8",
+            bitmap.width,
+            bitmap.height
+        );
+    }
+
+    Ok(())
+}
+
+// Eight colors in hex notation for palette entries 8 to 15
+fn parse_palette(colors: &str) -> Result<[PaletteEntry; PALETTE_SIZE]> {
+    let mut palette = [(0, 0, 0); PALETTE_SIZE];
+    let entries: Vec<&str> = colors.split_whitespace().collect();
+
+    if entries.len() != 8 {
+        bail!("expected 8 colors, got {}", entries.len());
+    }
+
+    for (target, entry) in palette[8..].iter_mut().zip(entries) {
+        let rgb = u32::from_str_radix(entry.trim_start_matches('#'), 16)
+            .ok()
+            .filter(|_| entry.trim_start_matches('#').len() == 6)
+            .ok_or_else(|| anyhow!("invalid color '{}'", entry))?;
+
+        *target = ((rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8);
+    }
+
+    Ok(palette)
+}
+
+#[cfg(test)]
+mod test {
+    use std::{env, fs, fs::File, io::BufWriter, path::Path, process};
+
+    use png::{BitDepth, ColorType, Encoder};
+
+    use crate::game_data::tileset_png::{
+        TILESET_METADATA_FILENAME, export_tileset, import_tileset,
+    };
+
+    fn write_png(path: &Path, width: u32, height: u32, pixels: &[u8]) {
+        let mut encoder = Encoder::new(BufWriter::new(File::create(path).unwrap()), width, height);
+        let palette: Vec<u8> = (0..17).flat_map(|i| [i * 8, i * 4, i * 2]).collect();
+        let mut trns = vec![0xff; 16];
+        trns.push(0);
+
+        encoder.set_color(ColorType::Indexed);
+        encoder.set_depth(BitDepth::Eight);
+        encoder.set_palette(palette);
+        encoder.set_trns(trns);
+
+        let mut writer = encoder.write_header().unwrap();
+        writer.write_image_data(pixels).unwrap();
+    }
+
+    fn write_example(path: &Path) {
+        fs::create_dir_all(path).unwrap();
+
+        let tile: Vec<u8> = (0..16 * 4)
+            .map(|i| if i % 3 == 0 { 16 } else { i % 16 })
+            .collect();
+        write_png(&path.join("tile.png"), 16, 4, &tile);
+
+        let object: Vec<u8> = (0..8 * 6).map(|i| i % 16).collect();
+        write_png(&path.join("object.png"), 8, 6, &object);
+
+        fs::write(
+            path.join(TILESET_METADATA_FILENAME),
+            r#"
+[palettes]
+standard = "000000 111111 222222 333333 444444 555555 666666 777777"
+
+[[terrain]]
+index = 5
+file = "tile.png"
+
+[[object]]
+index = 1
+file = "object.png"
+frames = 3
+preview_frame = 1
+trigger_effect = 1
+trigger_top = 6
+"#,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn import() {
+        let path = env::temp_dir().join(format!("rustlings-tileset-import-{}", process::id()));
+        write_example(&path);
+
+        let tileset = import_tileset(&path);
+        fs::remove_dir_all(&path).unwrap();
+        let tileset = tileset.expect("import failed");
+
+        let tile = tileset.tiles[5].as_ref().expect("tile missing");
+        assert_eq!(tileset.terrain_info[5].width, 16);
+        assert_eq!(tile.data[1], 1);
+        assert!(tile.transparency[0] && !tile.transparency[1]);
+        assert_eq!(tile.data[0], 0);
+
+        let object = tileset.object_sprites[1].as_ref().expect("object missing");
+        assert_eq!(
+            (object.width, object.height, object.frames.len()),
+            (8, 2, 3)
+        );
+        assert_eq!(object.frames[2].data[0], 0);
+        assert_eq!(object.frames[2].data[1], 1);
+        assert_eq!(tileset.object_info[1].preview_frame(), 1);
+        assert_eq!(tileset.object_info[1].trigger_top, 4);
+
+        assert_eq!(tileset.palettes.custom[9], (72, 36, 18));
+        assert_eq!(tileset.palettes.standard[9], (0x11, 0x11, 0x11));
+    }
+
+    #[test]
+    fn export_roundtrip() {
+        let path = env::temp_dir().join(format!("rustlings-tileset-export-{}", process::id()));
+        write_example(&path.join("source"));
+
+        let tileset = import_tileset(&path.join("source")).expect("import failed");
+        let exported = export_tileset(&path.join("export"), &tileset)
+            .and_then(|_| import_tileset(&path.join("export")));
+        fs::remove_dir_all(&path).unwrap();
+        let exported = exported.expect("export failed");
+
+        assert_eq!(exported.palettes.custom, tileset.palettes.custom);
+        assert_eq!(exported.palettes.standard, tileset.palettes.standard);
+
+        let tile = tileset.tiles[5].as_ref().unwrap();
+        let exported_tile = exported.tiles[5].as_ref().expect("tile missing");
+        assert_eq!(exported_tile.data, tile.data);
+        assert_eq!(exported_tile.transparency, tile.transparency);
+
+        let object = tileset.object_sprites[1].as_ref().unwrap();
+        let exported_object = exported.object_sprites[1].as_ref().expect("object missing");
+        assert_eq!(exported_object.frames.len(), object.frames.len());
+        assert_eq!(exported_object.frames[2].data, object.frames[2].data);
+        assert_eq!(exported.object_info[1].preview_frame(), 1);
+        assert_eq!(
+            exported.object_info[1].trigger_top,
+            tileset.object_info[1].trigger_top
+        );
+    }
+}
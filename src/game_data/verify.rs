@@ -0,0 +1,565 @@
+use std::{fmt, io};
+
+use crate::game_data::file::encoding::datfile::{self, HEADER_SIZE};
+use crate::game_data::file::level::{
+    LEVEL_SIZE, ODDTABLE_ENTRIES, ODDTABLE_ENTRY_SIZE, ODDTABLE_FILENAME,
+};
+use crate::game_data::file::main::MAIN_SECTIONS;
+use crate::game_data::file::vfs::Vfs;
+use crate::game_data::{LEVELS_PER_FILE, NUM_LEVELS_FILES, NUM_SPECIAL_BACKGROUND, NUM_TILESETS};
+
+// 16 object infos, 64 terrain infos and the palettes
+const GROUND_SIZE: usize = 1056;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DataSetVariant {
+    Lemmings,
+    OhNoMoreLemmings,
+    HolidayLemmings,
+    Demo,
+    Unknown,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum DataProblem {
+    Missing,
+    Unreadable(String),
+    InvalidSize {
+        expected: usize,
+        actual: usize,
+    },
+    Truncated {
+        section: usize,
+        expected: usize,
+        actual: usize,
+    },
+    ChecksumMismatch {
+        section: usize,
+        expected: u8,
+        actual: u8,
+    },
+    Corrupt {
+        section: usize,
+        reason: String,
+    },
+    InvalidSectionSize {
+        section: usize,
+        expected: usize,
+        actual: usize,
+    },
+    InvalidSectionCount {
+        expected: usize,
+        actual: usize,
+    },
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct FileProblem {
+    pub file: String,
+    pub problem: DataProblem,
+}
+
+pub struct DataSetReport {
+    pub variant: DataSetVariant,
+    pub fingerprint: u64,
+    pub problems: Vec<FileProblem>,
+}
+
+enum Layout {
+    Raw {
+        size: usize,
+    },
+    Compressed {
+        sections: usize,
+        section_size: Option<usize>,
+    },
+}
+
+struct ExpectedFile {
+    name: String,
+    layout: Layout,
+}
+
+impl DataSetReport {
+    pub fn is_ok(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+impl fmt::Display for DataSetVariant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            DataSetVariant::Lemmings => "Lemmings",
+            DataSetVariant::OhNoMoreLemmings => "Oh No! More Lemmings",
+            DataSetVariant::HolidayLemmings => "Holiday Lemmings",
+            DataSetVariant::Demo => "Lemmings demo",
+            DataSetVariant::Unknown => "unknown data set",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+impl fmt::Display for DataProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DataProblem::Missing => write!(f, "file is missing"),
+            DataProblem::Unreadable(reason) => write!(f, "unable to read file: {}", reason),
+            DataProblem::InvalidSize { expected, actual } => write!(
+                f,
+                "invalid size: expected {} bytes, got {} bytes",
+                expected, actual
+            ),
+            DataProblem::Truncated {
+                section,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "section {}: file truncated, expected at least {} bytes, got {} bytes",
+                section, expected, actual
+            ),
+            DataProblem::ChecksumMismatch {
+                section,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "section {}: checksum mismatch, expected {:#04x}, got {:#04x}",
+                section, expected, actual
+            ),
+            DataProblem::Corrupt { section, reason } => {
+                write!(f, "section {}: corrupt data: {}", section, reason)
+            }
+            DataProblem::InvalidSectionSize {
+                section,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "section {}: expected {} bytes of decompressed data, got {} bytes",
+                section, expected, actual
+            ),
+            DataProblem::InvalidSectionCount { expected, actual } => {
+                write!(f, "expected {} sections, got {}", expected, actual)
+            }
+        }
+    }
+}
+
+impl fmt::Display for FileProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.file, self.problem)
+    }
+}
+
+impl fmt::Display for DataSetReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} (fingerprint {:016x})",
+            self.variant, self.fingerprint
+        )?;
+
+        for problem in &self.problems {
+            write!(f, "\n  {}", problem)?;
+        }
+
+        Ok(())
+    }
+}
+
+pub fn verify_game_data(vfs: &dyn Vfs) -> DataSetReport {
+    let mut fingerprint = Fingerprint::new();
+    let mut problems: Vec<FileProblem> = Vec::new();
+    let mut present: Vec<String> = Vec::new();
+
+    for expected_file in expected_files() {
+        let data = match vfs.read(&expected_file.name) {
+            Ok(data) => data,
+            Err(err) => {
+                problems.push(FileProblem {
+                    problem: if err.kind() == io::ErrorKind::NotFound {
+                        DataProblem::Missing
+                    } else {
+                        DataProblem::Unreadable(err.to_string())
+                    },
+                    file: expected_file.name,
+                });
+
+                continue;
+            }
+        };
+
+        fingerprint.update(expected_file.name.as_bytes());
+        fingerprint.update(&data);
+
+        problems.extend(
+            verify_file(&data, &expected_file.layout)
+                .into_iter()
+                .map(|problem| FileProblem {
+                    file: expected_file.name.clone(),
+                    problem,
+                }),
+        );
+
+        present.push(expected_file.name);
+    }
+
+    // Files of other releases are only probed for identification
+    for name in onml_level_files() {
+        if let Ok(data) = vfs.read(&name) {
+            fingerprint.update(name.as_bytes());
+            fingerprint.update(&data);
+            present.push(name);
+        }
+    }
+
+    DataSetReport {
+        variant: identify_variant(&present),
+        fingerprint: fingerprint.value(),
+        problems,
+    }
+}
+
+fn onml_level_files() -> Vec<String> {
+    (0..NUM_LEVELS_FILES)
+        .map(|i| format!("dlvel00{}.dat", i))
+        .collect()
+}
+
+fn expected_files() -> Vec<ExpectedFile> {
+    let mut files: Vec<ExpectedFile> = Vec::new();
+
+    for i in 0..NUM_LEVELS_FILES {
+        files.push(ExpectedFile {
+            name: format!("level00{}.dat", i),
+            layout: Layout::Compressed {
+                sections: LEVELS_PER_FILE,
+                section_size: Some(LEVEL_SIZE),
+            },
+        });
+    }
+
+    files.push(ExpectedFile {
+        name: ODDTABLE_FILENAME.to_string(),
+        layout: Layout::Raw {
+            size: ODDTABLE_ENTRIES * ODDTABLE_ENTRY_SIZE,
+        },
+    });
+
+    for i in 0..NUM_TILESETS {
+        files.push(ExpectedFile {
+            name: format!("ground{}o.dat", i),
+            layout: Layout::Raw { size: GROUND_SIZE },
+        });
+
+        files.push(ExpectedFile {
+            name: format!("vgagr{}.dat", i),
+            layout: Layout::Compressed {
+                sections: 2,
+                section_size: None,
+            },
+        });
+    }
+
+    for i in 0..NUM_SPECIAL_BACKGROUND {
+        files.push(ExpectedFile {
+            name: format!("vgaspec{}.dat", i),
+            layout: Layout::Compressed {
+                sections: 1,
+                section_size: None,
+            },
+        });
+    }
+
+    files.push(ExpectedFile {
+        name: "main.dat".to_string(),
+        layout: Layout::Compressed {
+            sections: MAIN_SECTIONS,
+            section_size: None,
+        },
+    });
+
+    files
+}
+
+fn verify_file(data: &[u8], layout: &Layout) -> Vec<DataProblem> {
+    match layout {
+        Layout::Raw { size } if data.len() != *size => vec![DataProblem::InvalidSize {
+            expected: *size,
+            actual: data.len(),
+        }],
+        Layout::Raw { .. } => Vec::new(),
+        Layout::Compressed {
+            sections,
+            section_size,
+        } => verify_compressed(data, *sections, *section_size),
+    }
+}
+
+fn verify_compressed(
+    data: &[u8],
+    expected_sections: usize,
+    section_size: Option<usize>,
+) -> Vec<DataProblem> {
+    let mut problems: Vec<DataProblem> = Vec::new();
+    let mut offset = 0;
+    let mut section = 0;
+
+    while offset < data.len() {
+        if data.len() - offset < HEADER_SIZE {
+            problems.push(DataProblem::Truncated {
+                section,
+                expected: offset + HEADER_SIZE,
+                actual: data.len(),
+            });
+
+            return problems;
+        }
+
+        let header = match datfile::read_header(data, offset) {
+            Ok((header, _)) => header,
+            Err(err) => {
+                problems.push(DataProblem::Corrupt {
+                    section,
+                    reason: err.kind.to_string(),
+                });
+
+                return problems;
+            }
+        };
+
+        let end = offset + header.compressed_data_size;
+        if end > data.len() {
+            problems.push(DataProblem::Truncated {
+                section,
+                expected: end,
+                actual: data.len(),
+            });
+
+            return problems;
+        }
+
+        let checksum =
+            datfile::calculate_checksum(&header, data, offset + HEADER_SIZE).unwrap_or_default();
+
+        if checksum != header.checksum {
+            problems.push(DataProblem::ChecksumMismatch {
+                section,
+                expected: header.checksum,
+                actual: checksum,
+            });
+        } else {
+            match datfile::parse(&data[offset..end]) {
+                Ok(content) => {
+                    if let Some(expected) = section_size
+                        && content.sections[0].data.len() != expected
+                    {
+                        problems.push(DataProblem::InvalidSectionSize {
+                            section,
+                            expected,
+                            actual: content.sections[0].data.len(),
+                        });
+                    }
+                }
+                Err(err) => problems.push(DataProblem::Corrupt {
+                    section,
+                    reason: err.kind.to_string(),
+                }),
+            }
+        }
+
+        offset = end;
+        section += 1;
+    }
+
+    if section != expected_sections {
+        problems.push(DataProblem::InvalidSectionCount {
+            expected: expected_sections,
+            actual: section,
+        });
+    }
+
+    problems
+}
+
+// Each release is recognised by its own files. Lemmings needs the complete set, ONML keeps its
+// levels in dlvel files and has no special backgrounds. Holiday Lemmings and the demo ship fewer
+// level files, numbered from the first one, and only the demo has the special backgrounds or the
+// odd table. Only the full Lemmings data set is supported by the engine, but identifying the
+// others lets us point users in the right direction.
+fn identify_variant(present: &[String]) -> DataSetVariant {
+    let count = |prefix: &str| present.iter().filter(|f| f.starts_with(prefix)).count();
+    let has = |name: &str| present.iter().any(|f| f == name);
+
+    let level_files = count("level");
+    let onml_level_files = count("dlvel");
+    let tilesets = count("ground");
+    let specials = count("vgaspec") > 0 || has(ODDTABLE_FILENAME);
+
+    if expected_files().iter().all(|f| has(&f.name)) {
+        DataSetVariant::Lemmings
+    } else if onml_level_files == NUM_LEVELS_FILES
+        && level_files == 0
+        && tilesets == NUM_TILESETS
+        && !specials
+    {
+        DataSetVariant::OhNoMoreLemmings
+    } else if !(1..NUM_LEVELS_FILES).contains(&level_files)
+        || !(0..level_files).all(|i| has(&format!("level00{}.dat", i)))
+        || onml_level_files > 0
+        || tilesets == 0
+    {
+        DataSetVariant::Unknown
+    } else if specials {
+        DataSetVariant::Demo
+    } else {
+        DataSetVariant::HolidayLemmings
+    }
+}
+
+// FNV-1a
+struct Fingerprint(u64);
+
+impl Fingerprint {
+    fn new() -> Self {
+        Self(0xcbf29ce484222325)
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        for byte in data {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(0x100000001b3);
+        }
+    }
+
+    fn value(&self) -> u64 {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // A single section that decompresses to [0x2a]
+    const SECTION: [u8; 12] = [5, 0x54, 0, 0, 0, 1, 0, 0, 0, 12, 0x54, 0x00];
+
+    #[test]
+    fn valid_file() {
+        assert_eq!(verify_compressed(&SECTION, 1, Some(1)), vec![]);
+    }
+
+    #[test]
+    fn truncated_file() {
+        let data = [SECTION.as_slice(), &SECTION[..11]].concat();
+
+        assert_eq!(
+            verify_compressed(&data, 2, None),
+            vec![DataProblem::Truncated {
+                section: 1,
+                expected: 24,
+                actual: 23
+            }]
+        );
+    }
+
+    #[test]
+    fn checksum_mismatch() {
+        let mut data = SECTION;
+        data[11] = 0x01;
+
+        assert_eq!(
+            verify_compressed(&data, 1, None),
+            vec![DataProblem::ChecksumMismatch {
+                section: 0,
+                expected: 0x54,
+                actual: 0x55
+            }]
+        );
+    }
+
+    #[test]
+    fn section_count_and_size() {
+        assert_eq!(
+            verify_compressed(&SECTION, 2, Some(2)),
+            vec![
+                DataProblem::InvalidSectionSize {
+                    section: 0,
+                    expected: 2,
+                    actual: 1
+                },
+                DataProblem::InvalidSectionCount {
+                    expected: 2,
+                    actual: 1
+                }
+            ]
+        );
+    }
+
+    fn names(prefixes: &[&str], extra: &[String]) -> Vec<String> {
+        expected_files()
+            .into_iter()
+            .map(|f| f.name)
+            .filter(|name| prefixes.iter().any(|prefix| name.starts_with(prefix)))
+            .chain(extra.iter().cloned())
+            .collect()
+    }
+
+    #[test]
+    fn identify_lemmings() {
+        let files = names(&[""], &[]);
+        assert_eq!(identify_variant(&files), DataSetVariant::Lemmings);
+
+        // An incomplete copy is not mistaken for another release
+        let without_level: Vec<String> = files
+            .iter()
+            .filter(|f| *f != "level003.dat")
+            .cloned()
+            .collect();
+        assert_eq!(identify_variant(&without_level), DataSetVariant::Unknown);
+    }
+
+    #[test]
+    fn identify_onml() {
+        let files = names(&["ground", "vgagr", "main"], &onml_level_files());
+        assert_eq!(identify_variant(&files), DataSetVariant::OhNoMoreLemmings);
+
+        let files = names(&["ground", "vgagr", "main"], &onml_level_files()[1..]);
+        assert_eq!(identify_variant(&files), DataSetVariant::Unknown);
+    }
+
+    #[test]
+    fn identify_holiday() {
+        let files = names(
+            &[
+                "level000", "level001", "ground0", "ground1", "vgagr", "main",
+            ],
+            &[],
+        );
+        assert_eq!(identify_variant(&files), DataSetVariant::HolidayLemmings);
+    }
+
+    #[test]
+    fn identify_demo() {
+        let files = names(&["level000", "ground0", "vgagr0", "vgaspec0", "main"], &[]);
+        assert_eq!(identify_variant(&files), DataSetVariant::Demo);
+
+        let files = names(&["level000", "ground0", "vgagr0", "oddtable", "main"], &[]);
+        assert_eq!(identify_variant(&files), DataSetVariant::Demo);
+    }
+
+    #[test]
+    fn identify_unknown() {
+        assert_eq!(identify_variant(&[]), DataSetVariant::Unknown);
+        assert_eq!(
+            identify_variant(&names(&["level", "main"], &[])),
+            DataSetVariant::Unknown
+        );
+        assert_eq!(
+            identify_variant(&names(&["vgaspec", "ground", "main"], &[])),
+            DataSetVariant::Unknown
+        );
+    }
+}
@@ -0,0 +1,43 @@
+use std::{fs, path::Path};
+
+use crate::game_data::TileSet;
+use crate::game_data::file::error::{ErrorLocation, GameDataError, Result};
+use crate::game_data::file::ground::{self, encode_ground};
+use crate::game_data::file::path::resolve_path;
+use crate::game_data::file::vgagr::{encode_vgagr, layout_vgagr};
+
+// Writes groundNo.dat and vgagrN.dat, replacing existing files regardless of the case of their
+// names. Tile and frame offsets are recalculated.
+pub fn write_tileset(path: &Path, index: usize, tileset: &TileSet) -> Result<()> {
+    let mut object_info = tileset.object_info.clone();
+    let mut terrain_info = tileset.terrain_info.clone();
+
+    layout_vgagr(&mut object_info, &mut terrain_info);
+
+    let vgagr_filename = format!("vgagr{}.dat", index);
+    let vgagr = encode_vgagr(
+        &object_info,
+        &terrain_info,
+        &tileset.object_sprites,
+        &tileset.tiles,
+    )
+    .in_file(&vgagr_filename)?;
+
+    let ground_filename = format!("ground{}o.dat", index);
+    let ground = encode_ground(&ground::Content {
+        object_info,
+        terrain_info,
+        palettes: tileset.palettes.clone(),
+    })
+    .in_file(&ground_filename)?;
+
+    println!("writing {}", &ground_filename);
+    fs::write(resolve_path(path, &ground_filename), ground)
+        .map_err(GameDataError::from)
+        .in_file(&ground_filename)?;
+
+    println!("writing {}", &vgagr_filename);
+    fs::write(resolve_path(path, &vgagr_filename), vgagr)
+        .map_err(GameDataError::from)
+        .in_file(&vgagr_filename)
+}
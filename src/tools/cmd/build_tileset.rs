@@ -0,0 +1,12 @@
+use std::path::Path;
+
+use anyhow::Result;
+use rustlings::game_data::{import_tileset, write_tileset};
+
+pub fn main(source: &str, destination: &str, index: usize) -> Result<()> {
+    let tileset = import_tileset(Path::new(source))?;
+
+    write_tileset(Path::new(destination), index, &tileset)?;
+
+    Ok(())
+}
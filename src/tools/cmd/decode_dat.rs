@@ -2,9 +2,16 @@ use std::{fs, path::Path};
 
 use anyhow::{Context, Ok, Result};
 use rustlings::game_data::file::encoding::datfile;
+use rustlings::game_data::resolve_path;
 
 pub fn main(path_name: &str) -> Result<()> {
-    let path = Path::new(path_name);
+    let path = match (
+        Path::new(path_name).parent(),
+        Path::new(path_name).file_name(),
+    ) {
+        (Some(directory), Some(file_name)) => resolve_path(directory, &file_name.to_string_lossy()),
+        _ => Path::new(path_name).to_path_buf(),
+    };
 
     let compressed_data = fs::read(path.as_os_str())
         .with_context(|| format!("failed to load read '{}'", path_name))?;
@@ -1,58 +1,23 @@
-use std::{cmp::Ordering, fs, io::Write, path::Path};
+use std::{fs, path::Path};
 
-use anyhow::Result;
-use rustlings::game_data::{LevelStructure, read_game_data};
-
-fn comparator<T: LevelStructure>(o1: &T, o2: &T) -> Ordering {
-    if o1.get_id() == o2.get_id() {
-        if o1.get_x() > o2.get_x() {
-            Ordering::Greater
-        } else if o1.get_x() < o2.get_x() {
-            Ordering::Less
-        } else {
-            Ordering::Equal
-        }
-    } else if o1.get_id() > o2.get_id() {
-        Ordering::Greater
-    } else {
-        Ordering::Less
-    }
-}
+use anyhow::{Context, Result};
+use rustlings::game_data::{LEVELS_PER_FILE, Loading, format_level, read_game_data_with};
 
 pub fn main(path: &Path, destination: &str) -> Result<()> {
-    let game_data = read_game_data(path)?;
-
-    for level in game_data.levels {
-        let file_name = format!("{}.level.txt", level.parameters.name);
+    let game_data = read_game_data_with(path, Loading::Lazy)?;
+
+    for (i, level) in game_data.levels.iter().enumerate() {
+        let file_name = format!(
+            "level{:03}-{}.level.txt",
+            i / LEVELS_PER_FILE,
+            i % LEVELS_PER_FILE
+        );
         let file_path = Path::new(destination).join(Path::new(&file_name));
 
-        let mut file = fs::File::create(file_path)?;
-
-        writeln!(&mut file, "{}", level)?;
-
-        writeln!(&mut file)?;
-        writeln!(&mut file, "Objects:")?;
-        writeln!(&mut file)?;
-
-        let mut objects = level.objects.clone();
-        objects.sort_by(comparator);
-
-        for object in &objects {
-            writeln!(&mut file, "{}", object)?;
-            writeln!(&mut file)?;
-        }
-
-        writeln!(&mut file)?;
-        writeln!(&mut file, "Tiles:")?;
-        writeln!(&mut file)?;
-
-        let mut terrain_tiles = level.terrain_tiles.clone();
-        terrain_tiles.sort_by(comparator);
+        println!("writing '{}' ({})", file_name, level.parameters.name);
 
-        for tile in &terrain_tiles {
-            writeln!(&mut file, "{}", tile)?;
-            writeln!(&mut file)?;
-        }
+        fs::write(&file_path, format_level(level))
+            .with_context(|| format!("failed to write '{}'", file_name))?;
     }
 
     Ok(())
@@ -0,0 +1,90 @@
+use std::{fs, path::Path};
+
+use anyhow::{Context, Result};
+use rustlings::game_data::{
+    DIFF_PALETTE, GameData, Level, Loading, NUM_LEVELS, diff_level, diff_terrain, match_levels,
+    read_game_data_with, write_indexed_png,
+};
+
+pub fn main(path_a: &Path, path_b: &Path, destination: Option<&str>) -> Result<()> {
+    let game_data_a = read_game_data_with(path_a, Loading::Lazy)?;
+    let game_data_b = read_game_data_with(path_b, Loading::Lazy)?;
+
+    let levels_a = resolve_levels(&game_data_a)?;
+    let levels_b = resolve_levels(&game_data_b)?;
+
+    if let Some(destination) = destination {
+        fs::create_dir_all(destination)
+            .with_context(|| format!("failed to create {:?}", destination))?;
+    }
+
+    let mut num_changed: usize = 0;
+
+    let matches = match_levels(&levels_a, &levels_b);
+
+    for (i, (level_a, j)) in levels_a.iter().zip(&matches).enumerate() {
+        let name = level_a.parameters.name.trim_end();
+
+        let Some(j) = *j else {
+            num_changed += 1;
+            println!("level {} ({})", i, name);
+            println!("  removed");
+            continue;
+        };
+        let level_b = &levels_b[j];
+
+        let mut changes = diff_level(level_a, level_b);
+        if i != j {
+            changes.insert(0, format!("moved to position {}", j));
+        }
+
+        let terrain_a = game_data_a.compose_terrain(level_a)?;
+        let terrain_b = game_data_b.compose_terrain(level_b)?;
+        let (num_pixels, diff) = diff_terrain(&terrain_a, &terrain_b);
+
+        if num_pixels > 0 {
+            changes.push(format!("{} pixels differ", num_pixels));
+        }
+
+        if changes.is_empty() {
+            continue;
+        }
+
+        num_changed += 1;
+        println!("level {} ({})", i, name);
+        for change in &changes {
+            println!("  {}", change);
+        }
+
+        if let Some(destination) = destination
+            && num_pixels > 0
+        {
+            let file_name = format!("level{:03}_diff.png", i);
+
+            println!("writing '{}'", file_name);
+            write_indexed_png(
+                &Path::new(destination).join(file_name),
+                &diff,
+                &DIFF_PALETTE,
+            )?;
+        }
+    }
+
+    for (j, level_b) in levels_b.iter().enumerate() {
+        if !matches.contains(&Some(j)) {
+            num_changed += 1;
+            println!("level {} ({})", j, level_b.parameters.name.trim_end());
+            println!("  added");
+        }
+    }
+
+    println!("{} of {} levels differ", num_changed, NUM_LEVELS);
+
+    Ok(())
+}
+
+fn resolve_levels(game_data: &GameData) -> Result<Vec<Level>> {
+    (0..NUM_LEVELS)
+        .map(|i| game_data.resolve_level(i))
+        .collect()
+}
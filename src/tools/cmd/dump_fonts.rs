@@ -2,14 +2,15 @@ use std::path::Path;
 
 use anyhow::{Result, anyhow};
 use rustlings::game_data::{
-    read_game_data, resolve_skill_panel_font_index, resolve_skill_panel_skill_font_index,
+    Loading, read_game_data_with, resolve_skill_panel_font_index,
+    resolve_skill_panel_skill_font_index,
 };
 
 const CHARS_SKILL_PANEL: &str = "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ%- ";
 const CHARS_SKILL: &str = "0123456789 ";
 
 pub fn main(path: &Path) -> Result<()> {
-    let game_data = read_game_data(path)?;
+    let game_data = read_game_data_with(path, Loading::Lazy)?;
 
     println!("skill panel");
     println!("###########");
@@ -0,0 +1,46 @@
+use std::path::Path;
+
+use anyhow::Result;
+use rustlings::game_data::{Bitmap, Loading, Sprite, read_game_data_with};
+
+fn dump_bitmap(bitmap: &Bitmap) {
+    for y in 0..bitmap.height {
+        for x in 0..bitmap.width {
+            let i = (y * bitmap.width + x) as usize;
+            print!("{}", if bitmap.transparency[i] { '.' } else { '#' });
+        }
+
+        println!();
+    }
+
+    println!();
+}
+
+fn dump_sprite(name: &str, sprite: &Sprite) {
+    println!("{}", name);
+    println!("{}", "=".repeat(name.len()));
+    println!();
+
+    for frame in &sprite.frames {
+        dump_bitmap(frame);
+    }
+}
+
+pub fn main(path: &Path) -> Result<()> {
+    let game_data = read_game_data_with(path, Loading::Lazy)?;
+    let masks = &game_data.masks;
+
+    dump_sprite("bash right", &masks.bash_right);
+    dump_sprite("bash left", &masks.bash_left);
+    dump_sprite("mine right", &masks.mine_right);
+    dump_sprite("mine left", &masks.mine_left);
+
+    println!("explosion");
+    println!("=========");
+    println!();
+    dump_bitmap(&masks.explosion);
+
+    dump_sprite("countdown", &game_data.countdown_digits);
+
+    Ok(())
+}
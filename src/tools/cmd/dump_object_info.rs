@@ -6,7 +6,9 @@ use rustlings::game_data::read_game_data;
 pub fn main(path: &Path) -> Result<()> {
     let game_data = read_game_data(path)?;
 
-    for (i_tile_set, tile_set) in game_data.tilesets.iter().enumerate() {
+    for i_tile_set in 0..game_data.num_tilesets() {
+        let tile_set = game_data.tileset(i_tile_set)?;
+
         println!("TILESET {}", i_tile_set);
         println!("===");
         println!();
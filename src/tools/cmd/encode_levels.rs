@@ -0,0 +1,93 @@
+use std::{collections::BTreeMap, fs, path::Path};
+
+use anyhow::{Context, Result, bail};
+use rustlings::game_data::{Level, encode_level, encode_level_file, parse_level, resolve_path};
+
+const LEVEL_SUFFIX: &str = ".level.txt";
+
+fn read_levels(source: &Path) -> Result<Vec<(String, Level)>> {
+    let mut levels: Vec<(String, Level)> = Vec::new();
+
+    for entry in fs::read_dir(source).with_context(|| format!("failed to read {:?}", source))? {
+        let path = entry?.path();
+        let Some(stem) = path
+            .file_name()
+            .and_then(|s| s.to_str())
+            .and_then(|s| s.strip_suffix(LEVEL_SUFFIX))
+        else {
+            continue;
+        };
+
+        let text =
+            fs::read_to_string(&path).with_context(|| format!("failed to read {:?}", path))?;
+        let level = parse_level(&text).with_context(|| format!("failed to parse {:?}", path))?;
+
+        levels.push((stem.to_string(), level));
+    }
+
+    levels.sort_by(|(name1, _), (name2, _)| name1.cmp(name2));
+
+    Ok(levels)
+}
+
+// Levels named levelNNN-S are packed into levelNNN.dat, with S being the section index.
+fn write_dat(levels: Vec<(String, Level)>, destination: &Path) -> Result<()> {
+    let mut files: BTreeMap<String, BTreeMap<usize, Level>> = BTreeMap::new();
+
+    for (name, level) in levels {
+        let Some((file, section)) = name
+            .split_once('-')
+            .and_then(|(file, section)| Some((file, section.parse::<usize>().ok()?)))
+        else {
+            println!("skipping '{}{}'", name, LEVEL_SUFFIX);
+            continue;
+        };
+
+        files
+            .entry(file.to_string())
+            .or_default()
+            .insert(section, level);
+    }
+
+    for (file, sections) in files {
+        if sections.keys().copied().ne(0..sections.len()) {
+            bail!("levels for {} are not numbered consecutively", file);
+        }
+
+        let file_name = format!("{}.dat", file);
+        let levels: Vec<Level> = sections.into_values().collect();
+
+        println!("writing '{}' ({} levels)", file_name, levels.len());
+
+        let data =
+            encode_level_file(&levels).with_context(|| format!("failed to encode {}", file))?;
+        fs::write(resolve_path(destination, &file_name), data)
+            .with_context(|| format!("failed to write '{}'", file_name))?;
+    }
+
+    Ok(())
+}
+
+fn write_lvl(levels: Vec<(String, Level)>, destination: &Path) -> Result<()> {
+    for (name, level) in levels {
+        let file_name = format!("{}.lvl", name);
+
+        println!("writing '{}'", file_name);
+
+        let data = encode_level(&level).with_context(|| format!("failed to encode {}", name))?;
+        fs::write(destination.join(&file_name), data)
+            .with_context(|| format!("failed to write '{}'", file_name))?;
+    }
+
+    Ok(())
+}
+
+pub fn main(source: &str, destination: &str, lvl: bool) -> Result<()> {
+    let levels = read_levels(Path::new(source))?;
+
+    if lvl {
+        write_lvl(levels, Path::new(destination))
+    } else {
+        write_dat(levels, Path::new(destination))
+    }
+}
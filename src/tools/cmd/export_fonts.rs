@@ -0,0 +1,44 @@
+use std::{fs, path::Path};
+
+use anyhow::{Context, Result};
+use rustlings::game_data::{Loading, read_game_data_with, write_indexed_png};
+
+pub fn main(path: &Path, destination: &str) -> Result<()> {
+    let game_data = read_game_data_with(path, Loading::Lazy)?;
+    let destination = Path::new(destination);
+
+    fs::create_dir_all(destination)
+        .with_context(|| format!("failed to create {:?}", destination))?;
+
+    let skill_panel_palette = game_data.resolve_skill_panel_palette(0)?;
+    let level_palette = game_data.resolve_palette(&game_data.resolve_level(0)?)?;
+
+    for (file_name, sprite, palette) in [
+        (
+            "font_skill_panel.png",
+            &game_data.skill_panel.font,
+            &skill_panel_palette,
+        ),
+        (
+            "font_skills.png",
+            &game_data.skill_panel.font_skills,
+            &skill_panel_palette,
+        ),
+        (
+            "font_menu.png",
+            &game_data.main_menu.font,
+            &game_data.main_menu.palette,
+        ),
+        (
+            "countdown_digits.png",
+            &game_data.countdown_digits,
+            &level_palette,
+        ),
+    ] {
+        println!("writing '{}' ({} glyphs)", file_name, sprite.frames.len());
+
+        write_indexed_png(&destination.join(file_name), &sprite.sheet(), palette)?;
+    }
+
+    Ok(())
+}
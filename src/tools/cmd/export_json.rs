@@ -0,0 +1,14 @@
+use std::{fs, path::Path};
+
+use anyhow::{Context, Result};
+use rustlings::game_data::{export_json, read_game_data};
+
+pub fn main(path: &Path, destination: &str) -> Result<()> {
+    let game_data = read_game_data(path)?;
+    let json = export_json(&game_data)?;
+
+    println!("writing '{}'", destination);
+
+    fs::write(destination, serde_json::to_string_pretty(&json)? + "\n")
+        .with_context(|| format!("failed to write '{}'", destination))
+}
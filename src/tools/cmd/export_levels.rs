@@ -0,0 +1,38 @@
+use std::{fs, path::Path};
+
+use anyhow::{Context, Result};
+use rustlings::game_data::{NUM_LEVELS, read_game_data, render_minimap, write_indexed_png};
+
+pub fn main(path: &Path, destination: &str, thumbnails: bool) -> Result<()> {
+    let game_data = read_game_data(path)?;
+    let destination = Path::new(destination);
+
+    fs::create_dir_all(destination)
+        .with_context(|| format!("failed to create {:?}", destination))?;
+
+    for i in 0..NUM_LEVELS {
+        let level = game_data.resolve_level(i)?;
+        let file_name = format!("level{:03}.png", i);
+        let palette = game_data.resolve_palette(&level)?;
+
+        println!("writing '{}' ({})", file_name, level.parameters.name);
+
+        let bitmap = game_data
+            .compose_level(&level)
+            .with_context(|| format!("failed to compose level {}", i))?;
+        write_indexed_png(&destination.join(&file_name), &bitmap, &palette)?;
+
+        if thumbnails {
+            let file_name = format!("level{:03}_thumbnail.png", i);
+
+            println!("writing '{}'", file_name);
+            write_indexed_png(
+                &destination.join(&file_name),
+                &render_minimap(&bitmap),
+                &palette,
+            )?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,31 @@
+use std::{fs, path::Path};
+
+use anyhow::{Context, Result};
+use rustlings::game_data::{NUM_LEVELS, format_nxlv, read_game_data};
+
+pub fn main(path: &Path, destination: &str) -> Result<()> {
+    let game_data = read_game_data(path)?;
+    let destination = Path::new(destination);
+
+    fs::create_dir_all(destination)
+        .with_context(|| format!("failed to create {:?}", destination))?;
+
+    for i in 0..NUM_LEVELS {
+        let level = game_data.resolve_level(i)?;
+        let file_name = format!("level{:03}.nxlv", i);
+        let object_info = game_data
+            .tileset(level.graphics_set as usize)
+            .map(|tileset| &tileset.object_info[..])
+            .unwrap_or_default();
+
+        println!("writing '{}' ({})", file_name, level.parameters.name);
+
+        fs::write(
+            destination.join(&file_name),
+            format_nxlv(&level, object_info),
+        )
+        .with_context(|| format!("failed to write '{}'", file_name))?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,25 @@
+use std::{fs, path::Path};
+
+use anyhow::{Context, Result};
+use rustlings::game_data::{Loading, read_game_data_with, write_indexed_png};
+
+pub fn main(path: &Path, destination: &str) -> Result<()> {
+    let game_data = read_game_data_with(path, Loading::Lazy)?;
+    let destination = Path::new(destination);
+
+    fs::create_dir_all(destination)
+        .with_context(|| format!("failed to create {:?}", destination))?;
+
+    // Lemmings only use the fixed part of the palette, so any level will do
+    let palette = game_data.resolve_palette(&game_data.resolve_level(0)?)?;
+
+    for (i, sprite) in game_data.lemming_sprites.iter().enumerate() {
+        let file_name = format!("lemming{:02}.png", i);
+
+        println!("writing '{}' ({} frames)", file_name, sprite.frames.len());
+
+        write_indexed_png(&destination.join(&file_name), &sprite.sheet(), &palette)?;
+    }
+
+    Ok(())
+}
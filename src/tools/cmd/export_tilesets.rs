@@ -0,0 +1,19 @@
+use std::path::Path;
+
+use anyhow::Result;
+use rustlings::game_data::{export_tileset, read_game_data};
+
+pub fn main(path: &Path, destination: &str) -> Result<()> {
+    let game_data = read_game_data(path)?;
+
+    for i in 0..game_data.num_tilesets() {
+        let tileset = game_data.tileset(i)?;
+        let directory = format!("tileset{}", i);
+
+        println!("writing '{}'", directory);
+
+        export_tileset(&Path::new(destination).join(&directory), tileset)?;
+    }
+
+    Ok(())
+}
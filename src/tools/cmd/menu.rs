@@ -0,0 +1,113 @@
+use std::{cmp::max, path::Path, thread::sleep, time::Duration};
+
+use anyhow::{Result, anyhow};
+use rustlings::game_data::{GameData, Loading, read_game_data_with};
+use rustlings::sdl::SDLSprite;
+use sdl3::{event::Event, keyboard::Keycode};
+
+use crate::cmd::util::{create_window, timestamp};
+
+fn display_menu(game_data: &GameData) -> Result<()> {
+    let sdl_context = sdl3::init().map_err(|s| anyhow!(s))?;
+    sdl3::hint::set("SDL_RENDER_VSYNC", "1");
+    sdl3::hint::set("SDL_FRAMEBUFFER_ACCELERATION", "1");
+
+    let sdl_video = sdl_context.video().map_err(|s| anyhow!(s))?;
+    let mut event_pump = sdl_context.event_pump().map_err(|s| anyhow!(s))?;
+
+    let window = create_window(&sdl_video, false)?;
+
+    let mut canvas = window.into_canvas();
+    canvas.clear();
+
+    let texture_creator = canvas.texture_creator();
+
+    let menu = &game_data.main_menu;
+    let palette = &menu.palette;
+
+    let mut sprites: Vec<SDLSprite> = Vec::new();
+
+    for bitmap in [
+        &menu.logo,
+        &menu.background,
+        &menu.sign_play,
+        &menu.sign_level_code,
+        &menu.sign_music,
+        &menu.sign_difficulty,
+        &menu.sign_exit,
+        &menu.sign_music_note,
+        &menu.sign_fx,
+        &menu.reel,
+    ]
+    .into_iter()
+    .chain(menu.difficulty_signs.iter())
+    {
+        sprites.push(SDLSprite::from_bitmap(bitmap, palette, &texture_creator)?);
+    }
+
+    for sprite in [
+        &menu.blink,
+        &menu.scroller_left,
+        &menu.scroller_right,
+        &menu.font,
+    ] {
+        sprites.push(SDLSprite::from_sprite(sprite, palette, &texture_creator)?);
+    }
+
+    let mut running = true;
+    let mut iframe = 0;
+    let mut last_draw: u32 = 0;
+
+    while running {
+        let now = timestamp();
+
+        if now - last_draw > 1000 / 15 {
+            let mut x: i32 = 0;
+            let mut y: i32 = 1;
+            let mut height: i32 = 0;
+
+            canvas.clear();
+
+            for sprite in sprites.iter_mut() {
+                if x + 2 * sprite.width as i32 > 1280 {
+                    x = 0;
+                    y += 2 * (height + 1);
+                    height = 0;
+                }
+
+                sprite.blit(&mut canvas, x, y, iframe, 2, false, false)?;
+
+                x += (sprite.width as i32 + 1) * 2;
+                height = max(height, sprite.height as i32 + 1);
+            }
+
+            canvas.present();
+
+            iframe += 1;
+            last_draw = now;
+        }
+
+        for event in event_pump.poll_iter() {
+            match event {
+                Event::Quit { .. } => running = false,
+                Event::KeyDown {
+                    keycode: Some(Keycode::Escape),
+                    ..
+                } => running = false,
+                _ => (),
+            }
+        }
+
+        sleep(Duration::from_millis(1));
+    }
+
+    Ok(())
+}
+
+pub fn main(path: &Path) -> Result<()> {
+    let game_data = read_game_data_with(path, Loading::Lazy)?;
+
+    display_menu(&game_data)?;
+
+    Ok(())
+}
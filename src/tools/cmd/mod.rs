@@ -1,9 +1,22 @@
+pub mod build_tileset;
 pub mod decode_dat;
 pub mod decode_levels;
+pub mod diff_levels;
 pub mod dump_fonts;
+pub mod dump_masks;
 pub mod dump_object_info;
+pub mod encode_levels;
+pub mod export_fonts;
+pub mod export_json;
+pub mod export_levels;
+pub mod export_nxlv;
+pub mod export_sprites;
+pub mod export_tilesets;
+pub mod menu;
+pub mod render_text;
 pub mod sprites;
 pub mod tilesets;
+pub mod verify_data;
 pub mod view_levels;
 
 mod util;
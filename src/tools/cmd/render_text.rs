@@ -0,0 +1,86 @@
+use std::path::Path;
+
+use anyhow::{Result, bail};
+use rustlings::game_data::{
+    LEVEL_HEIGHT, LEVEL_WIDTH, Loading, TextPixel, file::ground::InteractionType,
+    read_game_data_with, render_ansi, render_ascii,
+};
+
+pub fn main(path: &Path, level_index: usize, x: u32, width: u32, ascii: bool) -> Result<()> {
+    let game_data = read_game_data_with(path, Loading::Lazy)?;
+    let level = game_data.resolve_level(level_index)?;
+    let terrain = game_data.compose_terrain(&level)?;
+    let palette = game_data.resolve_palette(&level)?;
+
+    if x >= LEVEL_WIDTH {
+        bail!("x must be below {}", LEVEL_WIDTH);
+    }
+
+    let size = (LEVEL_WIDTH * LEVEL_HEIGHT) as usize;
+    let mut pixels: Vec<TextPixel> = (0..size)
+        .map(|i| {
+            if terrain.transparency[i] {
+                TextPixel::Empty
+            } else {
+                TextPixel::Terrain(terrain.data[i])
+            }
+        })
+        .collect();
+
+    let mut fill = |left: i32, top: i32, width: u32, height: u32, pixel: TextPixel| {
+        // Clip against the level, keeping the far edges where they are
+        let right = (left as i64 + width as i64).clamp(0, LEVEL_WIDTH as i64) as u32;
+        let bottom = (top as i64 + height as i64).clamp(0, LEVEL_HEIGHT as i64) as u32;
+        let left = left.max(0) as u32;
+        let top = top.max(0) as u32;
+
+        for y in top..bottom {
+            for x in left..right {
+                pixels[(y * LEVEL_WIDTH + x) as usize] = pixel;
+            }
+        }
+    };
+
+    for steel_area in &level.steel_areas {
+        fill(
+            steel_area.x,
+            steel_area.y,
+            steel_area.width,
+            steel_area.height,
+            TextPixel::Steel,
+        );
+    }
+
+    for object in &level.objects {
+        let object_info =
+            game_data.resolve_object(object.id as usize, level.graphics_set as usize)?;
+
+        if matches!(
+            object_info.interaction_type,
+            InteractionType::None | InteractionType::Entrance
+        ) {
+            continue;
+        }
+
+        fill(
+            object.x + object_info.trigger_left,
+            object.y + object_info.trigger_top,
+            object_info.trigger_width,
+            object_info.trigger_height,
+            TextPixel::Trigger(object_info.interaction_type),
+        );
+    }
+
+    let columns = x..x.saturating_add(width).min(LEVEL_WIDTH);
+    let pixel = |x: u32, y: u32| pixels[(y * LEVEL_WIDTH + x) as usize];
+
+    println!("{}", level.parameters.name);
+
+    if ascii {
+        print!("{}", render_ascii(columns, LEVEL_HEIGHT, pixel));
+    } else {
+        print!("{}", render_ansi(columns, LEVEL_HEIGHT, &palette, pixel));
+    }
+
+    Ok(())
+}
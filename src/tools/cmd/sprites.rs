@@ -1,7 +1,7 @@
 use std::{path::Path, thread::sleep, time::Duration};
 
 use anyhow::{Result, anyhow};
-use rustlings::game_data::{GameData, read_game_data};
+use rustlings::game_data::{GameData, Loading, read_game_data_with};
 use rustlings::sdl::SDLSprite;
 use sdl3::{event::Event, keyboard::Keycode};
 
@@ -78,7 +78,7 @@ fn display_sprites(game_data: &GameData) -> Result<()> {
 }
 
 pub fn main(path: &Path) -> Result<()> {
-    let game_data = read_game_data(path)?;
+    let game_data = read_game_data_with(path, Loading::Lazy)?;
 
     display_sprites(&game_data)?;
 
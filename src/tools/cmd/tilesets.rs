@@ -24,11 +24,12 @@ fn display_tileset(game_data: &GameData) -> Result<()> {
 
     let mut spritesets: Vec<Vec<SDLSprite>> = Vec::new();
 
-    for i in 0..game_data.tilesets.len() {
+    for i in 0..game_data.num_tilesets() {
         let mut sprites: Vec<SDLSprite> = Vec::new();
-        let palette = &game_data.tilesets[i].palettes.custom;
+        let tileset = game_data.tileset(i)?;
+        let palette = &tileset.palettes.custom;
 
-        for object_sprite in &game_data.tilesets[i].object_sprites {
+        for object_sprite in &tileset.object_sprites {
             if let Some(sdl_sprite) = object_sprite
                 .as_ref()
                 .and_then(|sprite| SDLSprite::from_sprite(sprite, palette, &texture_creator).ok())
@@ -37,7 +38,7 @@ fn display_tileset(game_data: &GameData) -> Result<()> {
             }
         }
 
-        for tile in &game_data.tilesets[i].tiles {
+        for tile in &tileset.tiles {
             if let Some(sdl_sprite) = tile
                 .as_ref()
                 .and_then(|bitmap| SDLSprite::from_bitmap(bitmap, palette, &texture_creator).ok())
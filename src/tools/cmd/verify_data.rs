@@ -0,0 +1,22 @@
+use std::path::Path;
+
+use anyhow::{Result, bail};
+use rustlings::game_data::{open_vfs, verify_game_data};
+
+pub fn main(path: &Path) -> Result<()> {
+    let report = verify_game_data(open_vfs(path)?.as_ref());
+
+    println!("{}", report);
+
+    if !report.is_ok() {
+        bail!(
+            "found {} problem(s) in {}",
+            report.problems.len(),
+            path.display()
+        );
+    }
+
+    println!("all files OK");
+
+    Ok(())
+}
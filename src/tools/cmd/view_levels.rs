@@ -53,17 +53,10 @@ fn dump_level(level_index: usize, level: &Level) {
 
 fn get_palette(data: &GameData, level: &Level) -> Result<[PaletteEntry; PALETTE_SIZE]> {
     if level.extended_graphics_set > 0 {
-        data.special_backgrounds
-            .get(level.extended_graphics_set as usize - 1)
-            .ok_or(anyhow!(
-                "invalid extended graphics set {}",
-                level.extended_graphics_set
-            ))
+        data.special_background(level.extended_graphics_set as usize - 1)
             .map(|x| x.palette)
     } else {
-        data.tilesets
-            .get(level.graphics_set as usize)
-            .ok_or(anyhow!("invalid graphics set {}", level.graphics_set))
+        data.tileset(level.graphics_set as usize)
             .map(|x| x.palettes.custom)
     }
 }
@@ -129,10 +122,8 @@ fn compose_level(
     let mut background_data: Vec<u8> = vec![255; LEVEL_WIDTH as usize * LEVEL_HEIGHT as usize];
 
     if level.extended_graphics_set > 0 {
-        let special_background = data
-            .special_backgrounds
-            .get(level.extended_graphics_set as usize - 1)
-            .ok_or(anyhow!("bad extended graphics set"))?;
+        let special_background =
+            data.special_background(level.extended_graphics_set as usize - 1)?;
 
         for y in 0..special_background.bitmap.height {
             for x in 0..special_background.bitmap.width {
@@ -150,9 +141,7 @@ fn compose_level(
 
     for tile in &level.terrain_tiles {
         let bitmap_optional = data
-            .tilesets
-            .get(level.graphics_set as usize)
-            .ok_or(anyhow!("bad graphics set"))?
+            .tileset(level.graphics_set as usize)?
             .tiles
             .get(tile.id as usize)
             .and_then(|x| x.as_ref());
@@ -209,9 +198,10 @@ fn build_object_sprites<'a, T>(
     palette: &[PaletteEntry; PALETTE_SIZE],
     texture_creator: &'a TextureCreator<T>,
 ) -> Result<ObjectSprites<'a>> {
-    let mut sprites: ObjectSprites = Vec::with_capacity(data.tilesets.len());
+    let mut sprites: ObjectSprites = Vec::with_capacity(data.num_tilesets());
 
-    for tileset in &data.tilesets {
+    for i in 0..data.num_tilesets() {
+        let tileset = data.tileset(i)?;
         let mut object_sprites: Vec<Option<SDLSprite>> = Vec::with_capacity(OBJECTS_PER_TILESET);
 
         for object_sprite in &tileset.object_sprites {
@@ -3,11 +3,13 @@ mod cmd;
 use std::path::Path;
 
 use anyhow::Result;
-use clap::{Arg, ArgMatches, Command};
+use clap::{Arg, ArgAction, ArgMatches, Command};
 
 const ARG_GAME_DATA_PATH: &str = "GAME_DATA_PATH";
+const ARG_OTHER_GAME_DATA_PATH: &str = "OTHER_GAME_DATA_PATH";
 const ARG_DAT_FILE_PATH: &str = "DAT_FILE";
 const ARG_DESTINATION_PATH: &str = "DESTINATION_PATH";
+const ARG_SOURCE_PATH: &str = "SOURCE_PATH";
 
 fn game_data_path(matches: &ArgMatches) -> &Path {
     Path::new(
@@ -20,7 +22,7 @@ fn game_data_path(matches: &ArgMatches) -> &Path {
 fn main() -> Result<()> {
     let arg_data_path = Arg::new(ARG_GAME_DATA_PATH)
         .required(true)
-        .help("path to lemmings data files")
+        .help("path to lemmings data files or zip archive")
         .index(1);
 
     let mut command = Command::new("rustlings-tools")
@@ -51,7 +53,7 @@ fn main() -> Result<()> {
                 .arg(
                     Arg::new(ARG_GAME_DATA_PATH)
                         .required(true)
-                        .help("path to lemmings data files")
+                        .help("path to lemmings data files or zip archive")
                         .index(1),
                 )
                 .arg(
@@ -67,7 +69,7 @@ fn main() -> Result<()> {
                 .arg(
                     Arg::new(ARG_GAME_DATA_PATH)
                         .required(true)
-                        .help("path to lemmings data files")
+                        .help("path to lemmings data files or zip archive")
                         .index(1),
                 )
                 .arg(
@@ -77,13 +79,147 @@ fn main() -> Result<()> {
                         .index(2),
                 ),
         )
+        .subcommand(
+            Command::new("encode-levels")
+                .about("encode level text files into dat files")
+                .arg(
+                    Arg::new(ARG_SOURCE_PATH)
+                        .required(true)
+                        .help("path to level text files")
+                        .index(1),
+                )
+                .arg(
+                    Arg::new(ARG_DESTINATION_PATH)
+                        .required(true)
+                        .help("destination path")
+                        .index(2),
+                )
+                .arg(
+                    Arg::new("lvl")
+                        .help("write uncompressed .lvl files")
+                        .long("lvl")
+                        .action(ArgAction::SetTrue),
+                ),
+        )
+        .subcommand(
+            Command::new("diff-levels")
+                .about("compare the levels of two data directories or packs")
+                .arg(arg_data_path.clone())
+                .arg(
+                    Arg::new(ARG_OTHER_GAME_DATA_PATH)
+                        .required(true)
+                        .help("path to the modified data files or zip archive")
+                        .index(2),
+                )
+                .arg(
+                    Arg::new("png")
+                        .help("write PNGs highlighting the differing pixels to this directory")
+                        .long("png"),
+                ),
+        )
+        .subcommand(
+            Command::new("export-json")
+                .about("export level and object metadata as JSON")
+                .arg(arg_data_path.clone())
+                .arg(
+                    Arg::new(ARG_DESTINATION_PATH)
+                        .required(true)
+                        .help("destination file")
+                        .index(2),
+                ),
+        )
+        .subcommand(
+            Command::new("export-nxlv")
+                .about("export levels to the NeoLemmix level format")
+                .arg(arg_data_path.clone())
+                .arg(
+                    Arg::new(ARG_DESTINATION_PATH)
+                        .required(true)
+                        .help("destination path")
+                        .index(2),
+                ),
+        )
+        .subcommand(
+            Command::new("export-sprites")
+                .about("write lemming animations as PNG sprite sheets")
+                .arg(arg_data_path.clone())
+                .arg(
+                    Arg::new(ARG_DESTINATION_PATH)
+                        .required(true)
+                        .help("destination path")
+                        .index(2),
+                ),
+        )
+        .subcommand(
+            Command::new("export-tilesets")
+                .about("write tileset tiles and objects as PNGs")
+                .arg(arg_data_path.clone())
+                .arg(
+                    Arg::new(ARG_DESTINATION_PATH)
+                        .required(true)
+                        .help("destination path")
+                        .index(2),
+                ),
+        )
+        .subcommand(
+            Command::new("export-fonts")
+                .about("write fonts as PNGs")
+                .arg(arg_data_path.clone())
+                .arg(
+                    Arg::new(ARG_DESTINATION_PATH)
+                        .required(true)
+                        .help("destination path")
+                        .index(2),
+                ),
+        )
+        .subcommand(
+            Command::new("export-levels")
+                .about("write fully composed levels as PNGs")
+                .arg(arg_data_path.clone())
+                .arg(
+                    Arg::new(ARG_DESTINATION_PATH)
+                        .required(true)
+                        .help("destination path")
+                        .index(2),
+                )
+                .arg(
+                    Arg::new("thumbnails")
+                        .help("also write minimap sized thumbnails")
+                        .long("thumbnails")
+                        .action(ArgAction::SetTrue),
+                ),
+        )
+        .subcommand(
+            Command::new("build-tileset")
+                .about("build ground and vgagr files from PNGs and tileset.txt")
+                .arg(
+                    Arg::new(ARG_SOURCE_PATH)
+                        .required(true)
+                        .help("path to tileset.txt and PNG files")
+                        .index(1),
+                )
+                .arg(
+                    Arg::new(ARG_DESTINATION_PATH)
+                        .required(true)
+                        .help("destination path")
+                        .index(2),
+                )
+                .arg(
+                    Arg::new("index")
+                        .help("tileset index")
+                        .short('i')
+                        .long("index")
+                        .value_parser(clap::value_parser!(usize))
+                        .default_value("0"),
+                ),
+        )
         .subcommand(
             Command::new("dump-object-info")
                 .about("dump object info for all tilesets")
                 .arg(
                     Arg::new(ARG_GAME_DATA_PATH)
                         .required(true)
-                        .help("path to lemmings data files")
+                        .help("path to lemmings data files or zip archive")
                         .index(1),
                 ),
         )
@@ -92,10 +228,60 @@ fn main() -> Result<()> {
                 .arg(
                     Arg::new(ARG_GAME_DATA_PATH)
                         .required(true)
-                        .help("path to lemmings data files")
+                        .help("path to lemmings data files or zip archive")
                         .index(1),
                 )
                 .about("dump fonts"),
+        )
+        .subcommand(
+            Command::new("dump-masks")
+                .about("dump terrain masks and countdown digits")
+                .arg(arg_data_path.clone()),
+        )
+        .subcommand(
+            Command::new("menu")
+                .about("display main menu graphics")
+                .arg(arg_data_path.clone()),
+        )
+        .subcommand(
+            Command::new("render-text")
+                .about("render a level as terminal text")
+                .arg(arg_data_path.clone())
+                .arg(
+                    Arg::new("level")
+                        .help("level index")
+                        .short('l')
+                        .long("level")
+                        .value_parser(clap::value_parser!(usize))
+                        .default_value("0"),
+                )
+                .arg(
+                    Arg::new("x")
+                        .help("first column")
+                        .short('x')
+                        .long("x")
+                        .value_parser(clap::value_parser!(u32))
+                        .default_value("0"),
+                )
+                .arg(
+                    Arg::new("width")
+                        .help("number of columns")
+                        .short('w')
+                        .long("width")
+                        .value_parser(clap::value_parser!(u32))
+                        .default_value("1600"),
+                )
+                .arg(
+                    Arg::new("ascii")
+                        .help("plain ASCII, one character per pixel")
+                        .long("ascii")
+                        .action(ArgAction::SetTrue),
+                ),
+        )
+        .subcommand(
+            Command::new("verify-data")
+                .about("verify and identify lemmings data files")
+                .arg(arg_data_path.clone()),
         );
 
     let matches = command.clone().get_matches();
@@ -127,6 +313,83 @@ fn main() -> Result<()> {
                 .expect("unreachable"),
         ),
 
+        Some(("encode-levels", subcommand_matches)) => cmd::encode_levels::main(
+            subcommand_matches
+                .get_one::<String>(ARG_SOURCE_PATH)
+                .expect("unreachable"),
+            subcommand_matches
+                .get_one::<String>(ARG_DESTINATION_PATH)
+                .expect("unreachable"),
+            subcommand_matches.get_flag("lvl"),
+        ),
+
+        Some(("diff-levels", subcommand_matches)) => cmd::diff_levels::main(
+            game_data_path(subcommand_matches),
+            Path::new(
+                subcommand_matches
+                    .get_one::<String>(ARG_OTHER_GAME_DATA_PATH)
+                    .expect("unreachable"),
+            ),
+            subcommand_matches
+                .get_one::<String>("png")
+                .map(String::as_str),
+        ),
+
+        Some(("export-json", subcommand_matches)) => cmd::export_json::main(
+            game_data_path(subcommand_matches),
+            subcommand_matches
+                .get_one::<String>(ARG_DESTINATION_PATH)
+                .expect("unreachable"),
+        ),
+
+        Some(("export-nxlv", subcommand_matches)) => cmd::export_nxlv::main(
+            game_data_path(subcommand_matches),
+            subcommand_matches
+                .get_one::<String>(ARG_DESTINATION_PATH)
+                .expect("unreachable"),
+        ),
+
+        Some(("export-sprites", subcommand_matches)) => cmd::export_sprites::main(
+            game_data_path(subcommand_matches),
+            subcommand_matches
+                .get_one::<String>(ARG_DESTINATION_PATH)
+                .expect("unreachable"),
+        ),
+
+        Some(("export-tilesets", subcommand_matches)) => cmd::export_tilesets::main(
+            game_data_path(subcommand_matches),
+            subcommand_matches
+                .get_one::<String>(ARG_DESTINATION_PATH)
+                .expect("unreachable"),
+        ),
+
+        Some(("export-fonts", subcommand_matches)) => cmd::export_fonts::main(
+            game_data_path(subcommand_matches),
+            subcommand_matches
+                .get_one::<String>(ARG_DESTINATION_PATH)
+                .expect("unreachable"),
+        ),
+
+        Some(("export-levels", subcommand_matches)) => cmd::export_levels::main(
+            game_data_path(subcommand_matches),
+            subcommand_matches
+                .get_one::<String>(ARG_DESTINATION_PATH)
+                .expect("unreachable"),
+            subcommand_matches.get_flag("thumbnails"),
+        ),
+
+        Some(("build-tileset", subcommand_matches)) => cmd::build_tileset::main(
+            subcommand_matches
+                .get_one::<String>(ARG_SOURCE_PATH)
+                .expect("unreachable"),
+            subcommand_matches
+                .get_one::<String>(ARG_DESTINATION_PATH)
+                .expect("unreachable"),
+            *subcommand_matches
+                .get_one::<usize>("index")
+                .expect("unreachable"),
+        ),
+
         Some(("dump-object-info", subcommand_matches)) => {
             cmd::dump_object_info::main(game_data_path(subcommand_matches))
         }
@@ -135,6 +398,28 @@ fn main() -> Result<()> {
             cmd::dump_fonts::main(game_data_path(subcommand_matches))
         }
 
+        Some(("dump-masks", subcommand_matches)) => {
+            cmd::dump_masks::main(game_data_path(subcommand_matches))
+        }
+
+        Some(("menu", subcommand_matches)) => cmd::menu::main(game_data_path(subcommand_matches)),
+
+        Some(("render-text", subcommand_matches)) => cmd::render_text::main(
+            game_data_path(subcommand_matches),
+            *subcommand_matches
+                .get_one::<usize>("level")
+                .expect("unreachable"),
+            *subcommand_matches.get_one::<u32>("x").expect("unreachable"),
+            *subcommand_matches
+                .get_one::<u32>("width")
+                .expect("unreachable"),
+            subcommand_matches.get_flag("ascii"),
+        ),
+
+        Some(("verify-data", subcommand_matches)) => {
+            cmd::verify_data::main(game_data_path(subcommand_matches))
+        }
+
         _ => command.print_help().map_err(anyhow::Error::from),
     }
 }